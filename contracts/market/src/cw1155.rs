@@ -0,0 +1,38 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Binary, Uint128};
+
+// messages of cw1155 (cw-plus 0.9) used by the market
+
+// sent by cw1155 contract with `receive` hook
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw1155ReceiveMsg {
+  pub operator: String,
+  // None if the token is minted to the market
+  pub from: Option<String>,
+  pub token_id: String,
+  pub amount: Uint128,
+  pub msg: Binary,
+}
+
+// sent by cw1155 contract with `batch_receive` hook
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw1155BatchReceiveMsg {
+  pub operator: String,
+  pub from: Option<String>,
+  pub batch: Vec<(String, Uint128)>,
+  pub msg: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw1155ExecuteMsg {
+  SendFrom {
+    from: String,
+    to: String,
+    token_id: String,
+    value: Uint128,
+    msg: Option<Binary>,
+  },
+}
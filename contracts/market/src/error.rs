@@ -25,6 +25,15 @@ pub enum ContractError {
   #[error("Auction cancel fee rate is higher than 100%")]
   InvalidFeeRate {},
 
+  #[error("Sum of the protocol fee rate and the royalty rate is higher than 100%")]
+  InvalidProtocolFeeRate {},
+
+  #[error("Fee discount rate is higher than 100%")]
+  InvalidFeeDiscount {},
+
+  #[error("Referral share is higher than 100%")]
+  InvalidReferralShare {},
+
   #[error("The order doesn't have fixed price option")]
   NoFixedPrice {},
 
@@ -49,9 +58,78 @@ pub enum ContractError {
   #[error("Auction is not expired")]
   NotExpired {},
 
+  #[error("Auction already has a bid")]
+  BidExist {},
+
   #[error("Cancel fee mismatch you must send {} ", fee_asset)]
   CancelFeeMismatch { fee_asset: Asset },
 
+  #[error("The offer is not for this collection")]
+  CollectionMismatch {},
+
+  #[error("The offer is not for this token")]
+  TokenIdMismatch {},
+
+  #[error("No nft in the bundle")]
+  EmptyBundle {},
+
+  #[error("End price must be lower than start price")]
+  InvalidPriceRange {},
+
+  #[error("Start must be earlier than end and both must be the same type")]
+  InvalidPeriod {},
+
+  #[error("Reserve price must not exceed fixed price")]
+  InvalidReservePrice {},
+
+  #[error("Dutch auction can not be updated")]
+  DutchAuction {},
+
+  #[error("Sealed bid auction can not be updated")]
+  SealedBidAuction {},
+
+  #[error("Bid is already committed")]
+  AlreadyCommitted {},
+
+  #[error("Auction is not executed yet")]
+  NotSettled {},
+
+  #[error("Bid is already revealed")]
+  AlreadyRevealed {},
+
+  #[error("Reveal phase has not started")]
+  RevealNotStarted {},
+
+  #[error("Revealed bid does not match the commitment or is out of range")]
+  InvalidReveal {},
+
+  #[error("You must pay at least {} (current price)", price)]
+  InsufficientPayment { price: Asset },
+
+  #[error("Only allowed buyers can buy or bid on this order")]
+  NotAllowedBuyer {},
+
+  #[error("Order is not a swap order")]
+  NotSwapOrder {},
+
+  #[error("Swap order can not be updated")]
+  SwapOrder {},
+
+  #[error("Bundle must be a single nft to fulfill a swap")]
+  InvalidSwapBundle {},
+
+  #[error("Total price exceeds max total")]
+  MaxTotalExceeded {},
+
+  #[error("Invalid amount")]
+  InvalidAmount {},
+
+  #[error("Duplicate asset in prices")]
+  DuplicateAsset {},
+
+  #[error("Token is already listed")]
+  AlreadyListed {},
+
   #[error("You must bid higher or equal to {} (min bid amount)", min_bid_amount)]
   MinPrice { min_bid_amount: Uint128 },
 }
\ No newline at end of file
@@ -1,11 +1,16 @@
-use cosmwasm_std::{from_binary, to_binary, Addr, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, StdError, StdResult, Response, WasmMsg, Uint128};
+use cosmwasm_std::{from_binary, to_binary, Addr, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Order::Ascending as Ascending, StdError, StdResult, Storage, Response, WasmMsg, Uint128};
 use cw_storage_plus::U64Key;
 use cw721::{Cw721ReceiveMsg, Cw721ExecuteMsg};
 use cw20::Cw20ReceiveMsg;
 use cw0::Expiration;
-
-use crate::state::{AuctionInfo, Config, CollectionInfo, MarketContract, Order, Royalty};
-use crate::msgs::{InstantiateMsg, ExecuteMsg, Cw20HookMsg, Cw721HookMsg};
+use sha2::{Digest, Sha256};
+
+use crate::state::{
+  AuctionExtension, AuctionInfo, BundleItem, DutchAuctionInfo, SealedBid, SealedBidAuctionInfo, SealedBidSettlement, UnrevealedPolicy, Config, CollectionInfo,
+  FeeDiscount, FeeDiscountSource, MarketContract, MAX_FLOOR_SCAN, Offer, Order, Outbid, Royalty, SaleType, Trade, TradeStats, Sweetener, parse_floor_key, SweetenerPayer, SwapInfo, SwapRoyalty, Cw1155Info
+};
+use crate::msgs::{InstantiateMsg, ExecuteMsg, Cw20HookMsg, Cw721HookMsg, Cw1155HookMsg, ReceiveMsg};
+use crate::cw1155::Cw1155ExecuteMsg;
 use crate::error::ContractError;
 use crate::asset::{Asset, AssetInfo};
 
@@ -22,18 +27,34 @@ impl<'a> MarketContract<'a> {
       return Err(StdError::generic_err("Cancel fee rate can't exceed 1"))
     }
 
+    if msg.protocol_fee_rate > Decimal::one() {
+      return Err(StdError::generic_err("Protocol fee rate can't exceed 1"))
+    }
+
     let config = Config {
       owner: deps.api.addr_validate(msg.owner.as_str())?,
       min_increase: msg.min_increase,
       max_auction_duration_block: msg.max_auction_duration_block,
       max_auction_duration_second: msg.max_auction_duration_second,
       auction_cancel_fee_rate: msg.auction_cancel_fee_rate,
+      auction_extension_block: msg.auction_extension_block,
+      auction_extension_second: msg.auction_extension_second,
+      protocol_fee_rate: msg.protocol_fee_rate,
+      treasury: deps.api.addr_validate(msg.treasury.as_str())?,
+      fee_discount: None,
+      referral_share: Decimal::zero(),
     };
 
     self.config.save(deps.storage, &config)?;
 
     let order_index = 1u64;
     self.order_index.save(deps.storage, &order_index)?;
+
+    let offer_index = 1u64;
+    self.offer_index.save(deps.storage, &offer_index)?;
+
+    let trade_index = 1u64;
+    self.trade_index.save(deps.storage, &trade_index)?;
     Ok(Response::new())
   }
 
@@ -46,17 +67,75 @@ impl<'a> MarketContract<'a> {
   ) -> Result<Response, ContractError> {
     match msg {
       ExecuteMsg::ReceiveNft(msg) => self.receive_nft(deps, env, info, msg),
-      ExecuteMsg::Receive(msg) => self.receive_token(deps, env, info, msg),
-      ExecuteMsg::UpdateConfig { owner, min_increase, max_auction_duration_block, max_auction_duration_second, auction_cancel_fee_rate } 
-        => self.update_config(deps, env, info, owner, min_increase, max_auction_duration_block, max_auction_duration_second, auction_cancel_fee_rate),
-      ExecuteMsg::ExecuteOrder { order_id } => self.execute_order(deps, env, info.clone(), info.sender, order_id, None),
+      ExecuteMsg::Receive(ReceiveMsg::Cw20(msg)) => self.receive_token(deps, env, info, msg),
+      ExecuteMsg::Receive(ReceiveMsg::Cw1155(msg)) => {
+        let sender = msg.from.unwrap_or(msg.operator);
+        self.receive_cw1155(deps, env, info, sender, vec![(msg.token_id, msg.amount)], msg.msg)
+      }
+      ExecuteMsg::BatchReceive(msg) => {
+        let sender = msg.from.unwrap_or(msg.operator);
+        self.receive_cw1155(deps, env, info, sender, msg.batch, msg.msg)
+      }
+      ExecuteMsg::UpdateConfig {
+        owner, min_increase, max_auction_duration_block, max_auction_duration_second, auction_cancel_fee_rate,
+        auction_extension_block, auction_extension_second, protocol_fee_rate, treasury, fee_discount, referral_share
+      } => self.update_config(
+        deps, env, info, owner, min_increase, max_auction_duration_block, max_auction_duration_second, auction_cancel_fee_rate,
+        auction_extension_block, auction_extension_second, protocol_fee_rate, treasury, fee_discount, referral_share
+      ),
+      ExecuteMsg::ExecuteOrder { order_id, amount, referrer }
+        => self.execute_order(deps, env, info.clone(), info.sender, order_id, amount, referrer, None),
+      ExecuteMsg::ExecuteOrders { order_ids, max_total, skip_unavailable }
+        => self.execute_orders(deps, env, info.clone(), info.sender, order_ids, max_total, skip_unavailable, None),
+      ExecuteMsg::SweepFloor { nft_address, count, max_unit_price, asset }
+        => self.sweep_floor(deps, env, info.clone(), info.sender, nft_address, count, max_unit_price, asset, None),
       ExecuteMsg::CancelOrder { order_id } => self.cancel_order(deps, env, info.clone(), info.sender, order_id, None),
-      ExecuteMsg::AddCollection { nft_address, support_assets, royalties } 
-        => self.add_collection(deps, env, info, nft_address, support_assets, royalties),
-      ExecuteMsg::UpdateCollection { nft_address, support_assets, royalties } 
-        => self.update_collection(deps, env, info, nft_address, support_assets, royalties),
-      ExecuteMsg::Bid { order_id, bid_price } => self.bid(deps, env, info.clone(), info.sender, order_id, bid_price),
-      ExecuteMsg::ExecuteAuction { order_id } => self.execute_auction(deps, env, info, order_id)
+      ExecuteMsg::ReclaimExpired { order_ids } => self.reclaim_expired(deps, env, info, order_ids),
+      ExecuteMsg::UpdateOrder { order_id, price, fixed_price, expiration }
+        => self.update_order(deps, env, info, order_id, price, fixed_price, expiration),
+      ExecuteMsg::AddCollection { nft_address, support_assets, royalties, auction_extension, swap_royalty, protocol_fee_rate } 
+        => self.add_collection(
+          deps, env, info, nft_address, support_assets, royalties, auction_extension, swap_royalty, protocol_fee_rate
+        ),
+      ExecuteMsg::UpdateCollection {
+        nft_address, support_assets, royalties, auction_extension, clear_auction_extension, swap_royalty, protocol_fee_rate
+      } => self.update_collection(
+        deps, env, info, nft_address, support_assets, royalties, auction_extension, clear_auction_extension.unwrap_or(false),
+        swap_royalty, protocol_fee_rate
+      ),
+      ExecuteMsg::Bid { order_id, bid_price, referrer }
+        => self.bid(deps, env, info.clone(), info.sender, order_id, bid_price, referrer),
+      ExecuteMsg::ExecuteAuction { order_id } => self.execute_auction(deps, env, info, order_id),
+      ExecuteMsg::MakeCollectionOffer { nft_address, price }
+        => self.make_offer(deps, env, info.clone(), info.sender, nft_address, None, price, Expiration::Never {}, None),
+      ExecuteMsg::MakeOffer { nft_address, token_id, price, expiration }
+        => self.make_offer(deps, env, info.clone(), info.sender, nft_address, Some(token_id), price, expiration, None),
+      ExecuteMsg::CancelOffer { offer_id } => self.cancel_offer(deps, env, info, offer_id),
+      ExecuteMsg::MakeBundleFixedPriceOrder { price, expiration, other_prices, buyers } => {
+        let buyers = self.validate_buyers(deps.as_ref(), buyers)?;
+        let items = self.take_bundle(deps.storage, &info.sender)?;
+        self.make_fixed_price_order(deps, env, info.sender, items, price, other_prices.unwrap_or_default(), buyers, None, expiration)
+      }
+      ExecuteMsg::MakeBundleAuctionOrder { start_price, expiration, fixed_price, reserve_price, second_price, buyers } => {
+        let buyers = self.validate_buyers(deps.as_ref(), buyers)?;
+        let items = self.take_bundle(deps.storage, &info.sender)?;
+        self.make_auction_order(
+          deps, env, info.sender, items, start_price, fixed_price, reserve_price, second_price.unwrap_or(false), buyers, expiration
+        )
+      }
+      ExecuteMsg::WithdrawBundle {} => self.withdraw_bundle(deps, env, info),
+      ExecuteMsg::CommitBid { order_id, commitment, deposit }
+        => self.commit_bid(deps, env, info.clone(), info.sender, order_id, commitment, deposit, None),
+      ExecuteMsg::RevealBid { order_id, amount, salt } => self.reveal_bid(deps, env, info, order_id, amount, salt),
+      ExecuteMsg::WithdrawSealedBid { order_id, bidder } => self.withdraw_sealed_bid(deps, info, order_id, bidder),
+      ExecuteMsg::MakeBundleSwapOrder { nft_address, token_id, sweetener, expiration } => {
+        let items = self.take_bundle(deps.storage, &info.sender)?;
+        self.make_swap_order(deps, env, info.clone(), info.sender, items, nft_address, token_id, sweetener, expiration, None)
+      }
+      ExecuteMsg::FulfillBundleSwap { order_id } => {
+        let item = self.take_swap_item(deps.storage, &info.sender)?;
+        self.fulfill_swap(deps, env, info.clone(), info.sender, order_id, item, None)
+      }
     }
   }
 }
@@ -75,595 +154,2770 @@ impl<'a> MarketContract <'a> {
 
     let cw721_msg = from_binary::<Cw721HookMsg>(&msg.msg);
 
+    // token can be in only one order
+    if self.orders.idx.token.may_load_pk(deps.storage, (contract_addr.clone(), msg.token_id.clone()))?.is_some() {
+      return Err(ContractError::AlreadyListed {})
+    }
+
     match cw721_msg {
       Ok(Cw721HookMsg::MakeFixedPriceOrder {
-        price
+        price, expiration, other_prices, buyers
       }) => {
-        let collection_info = self.collections.load(deps.storage, contract_addr.to_string())?;
+        let item = BundleItem { nft_address: contract_addr, token_id: msg.token_id };
+        let buyers = self.validate_buyers(deps.as_ref(), buyers)?;
 
-        let support_assets = collection_info.support_assets;
+        self.make_fixed_price_order(deps, env, sender, vec![item], price, other_prices.unwrap_or_default(), buyers, None, expiration)
+      }
 
-        let mut support = false;
-        
-        for asset in support_assets.iter() {
-          if asset == &price.info {
-            support = true;
-            break;
-          }
-        }
+      Ok(Cw721HookMsg::MakeAuctionOrder {
+        start_price, fixed_price, reserve_price, second_price, expiration, buyers
+      }) => {
+        let item = BundleItem { nft_address: contract_addr, token_id: msg.token_id };
+        let buyers = self.validate_buyers(deps.as_ref(), buyers)?;
 
-        if !support {
-          return Err(ContractError::Unsupport {});
-        }
+        self.make_auction_order(
+          deps, env, sender, vec![item], start_price, fixed_price, reserve_price, second_price.unwrap_or(false), buyers, expiration
+        )
+      }
+
+      Ok(Cw721HookMsg::MakeDutchAuctionOrder {
+        start_price, end_price, start, end
+      }) => {
+        let item = BundleItem { nft_address: contract_addr, token_id: msg.token_id };
 
-        let id = self.order_index.load(deps.storage)?;
+        self.make_dutch_auction_order(deps, env, sender, vec![item], start_price, end_price, start, end)
+      }
 
-        let order = Order {
-          id,
-          seller_address: sender.clone(),
-          nft_address: contract_addr.clone(),
-          token_id: msg.token_id.clone(),
-          price: Some(price.clone()),
-          auction_info: None
-        };
+      Ok(Cw721HookMsg::MakeSealedBidAuctionOrder {
+        min_price, bid_end, reveal_end, unrevealed_policy, second_price
+      }) => {
+        let item = BundleItem { nft_address: contract_addr, token_id: msg.token_id };
+
+        self.make_sealed_bid_auction_order(deps, env, sender, vec![item], min_price, bid_end, reveal_end, unrevealed_policy, second_price.unwrap_or(false))
+      }
+
+      Ok(Cw721HookMsg::AddToBundle {}) => {
+        // only listable collection
+        self.collections.load(deps.storage, contract_addr.to_string())?;
+
+        let item = BundleItem { nft_address: contract_addr.clone(), token_id: msg.token_id.clone() };
 
-        let key = U64Key::new(id);
+        let mut items = self.bundles.may_load(deps.storage, sender.to_string())?.unwrap_or_default();
+        items.push(item);
 
-        self.orders.save(deps.storage, key, &order)?;
-        self.order_index.save(deps.storage, &(id + 1))?;
+        self.bundles.save(deps.storage, sender.to_string(), &items)?;
 
         Ok(Response::new()
-          .add_attribute("action", "make_fixed_price_order")
+          .add_attribute("action", "add_to_bundle")
           .add_attribute("sender", sender)
-          .add_attribute("order_id", id.to_string())
           .add_attribute("nft_address", contract_addr)
           .add_attribute("token_id", msg.token_id)
-          .add_attribute("price", format!("{}", price))
         )
       }
 
-      Ok(Cw721HookMsg::MakeAuctionOrder {
-        start_price, fixed_price, expiration
+      Ok(Cw721HookMsg::MakeSwapOrder {
+        nft_address, token_id, sweetener, expiration
       }) => {
-        let collection_info = self.collections.load(deps.storage, contract_addr.to_string())?;
+        let item = BundleItem { nft_address: contract_addr, token_id: msg.token_id };
 
-        let support_assets = collection_info.support_assets;
+        self.make_swap_order(deps, env, info, sender, vec![item], nft_address, token_id, sweetener, expiration, None)
+      }
 
-        let mut support = false;
-        
-        if let Some(fixed_price) = fixed_price.clone() {
-          if fixed_price.info != start_price.info {
-            return Err(ContractError::AssetInfoMismatch {})
-          }
-        }
+      Ok(Cw721HookMsg::FulfillSwap { order_id }) => {
+        let item = BundleItem { nft_address: contract_addr, token_id: msg.token_id };
 
-        for asset in support_assets.iter() {
-          if asset == &start_price.info {
-            support = true;
-            break;
-          }
-        }
+        self.fulfill_swap(deps, env, info, sender, order_id, item, None)
+      }
 
-        if !support {
-          return Err(ContractError::Unsupport {});
-        }
+      Ok(Cw721HookMsg::AcceptCollectionOffer { offer_id })
+        => self.accept_offer(deps, env, contract_addr, sender, msg.token_id, offer_id, true),
 
-        let id = self.order_index.load(deps.storage)?;
-        
-        let config = self.config.load(deps.storage)?;
+      Ok(Cw721HookMsg::AcceptOffer { offer_id })
+        => self.accept_offer(deps, env, contract_addr, sender, msg.token_id, offer_id, false),
 
-        // check expiration
-        match expiration {
-          Expiration::Never {} => {
-            return Err(ContractError::Never {})
-          }
-          Expiration::AtHeight(height) => {
-            if expiration.is_expired(&env.block) {
-              return Err(ContractError::Expired {})
-            }
+      Err(err) => Err(ContractError::Std(err)),
+    }
+  }
 
-            if (height - env.block.height) > config.max_auction_duration_block {
-              return Err(ContractError::MaxDuration {})
-            }
-          }
-          Expiration::AtTime(timestamp) => {
-            if expiration.is_expired(&env.block) {
-              return Err(ContractError::Expired {})
-            }
+  #[allow(clippy::too_many_arguments)]
+  pub fn make_fixed_price_order(
+    &self,
+    deps: DepsMut,
+    env: Env,
+    seller: Addr,
+    // first item is the main nft of the order, others are sold together as a bundle
+    items: Vec<BundleItem>,
+    price: Asset,
+    other_prices: Vec<Asset>,
+    buyers: Vec<Addr>,
+    cw1155_info: Option<Cw1155Info>,
+    expiration: Option<Expiration>
+  ) -> Result<Response, ContractError> {
+    if let Some(cw1155_info) = cw1155_info.clone() {
+      if cw1155_info.amount.is_zero() {
+        return Err(ContractError::InvalidAmount {})
+      }
+    }
 
-            if (timestamp.seconds() - env.block.time.seconds()) > config.max_auction_duration_second {
-              return Err(ContractError::MaxDuration {})
-            }
-          }
-        }
+    let mut prices = vec![price.clone()];
+    prices.extend(other_prices.clone());
 
-        let auction_info = AuctionInfo {
-          highest_bid: start_price.clone(),
-          bidder: None,
-          expiration
-        };
+    self.assert_unique_prices(&prices)?;
 
-        let order = Order {
-          id,
-          seller_address: sender.clone(),
-          nft_address: contract_addr.clone(),
-          token_id: msg.token_id.clone(),
-          price: fixed_price.clone(),
-          auction_info: Some(auction_info)
-        };
+    for item in items.iter() {
+      for price in prices.iter() {
+        self.assert_support_asset(deps.as_ref(), &item.nft_address, &price.info)?;
+      }
+    }
 
-        let key = U64Key::new(id);
+    if let Some(expiration) = expiration {
+      if expiration.is_expired(&env.block) {
+        return Err(ContractError::Expired {})
+      }
+    }
 
-        self.orders.save(deps.storage, key, &order)?;
-        self.order_index.save(deps.storage, &(id + 1))?;
+    let id = self.order_index.load(deps.storage)?;
+
+    let order = Order {
+      id,
+      seller_address: seller.clone(),
+      nft_address: items[0].nft_address.clone(),
+      token_id: items[0].token_id.clone(),
+      price: Some(price.clone()),
+      buyers: buyers.clone(),
+      other_prices,
+      auction_info: None,
+      expiration,
+      bundle: items[1..].to_vec(),
+      dutch_auction_info: None,
+      sealed_bid_auction_info: None,
+      swap_info: None,
+      cw1155_info
+    };
 
-        Ok(Response::new()
-          .add_attribute("action", "make_auction_order")
-          .add_attribute("sender", sender)
-          .add_attribute("order_id", id.to_string())
-          .add_attribute("nft_address", contract_addr)
-          .add_attribute("token_id", msg.token_id)
-          .add_attribute("fixed_price", if let Some(fixed_price) = fixed_price {
-            format!("{}", fixed_price)
-          } else {
-            "null".to_string()
-          })
-          .add_attribute("start_price", format!("{}", start_price))
-          .add_attribute("expiration", format!("{}", expiration))
-        )
-      }
+    let key = U64Key::new(id);
 
-      Err(err) => Err(ContractError::Std(err)),
-    }
+    self.orders.save(deps.storage, key, &order)?;
+    self.order_index.save(deps.storage, &(id + 1))?;
+
+    Ok(Response::new()
+      .add_attribute("action", "make_fixed_price_order")
+      .add_attribute("sender", seller)
+      .add_attribute("order_id", id.to_string())
+      .add_attribute("nft_address", order.nft_address)
+      .add_attribute("token_id", order.token_id)
+      .add_attribute("bundle_size", items.len().to_string())
+      .add_attribute("private", (!buyers.is_empty()).to_string())
+      .add_attribute("price", format!("{}", price))
+      .add_attribute("expiration", if let Some(expiration) = expiration {
+        format!("{}", expiration)
+      } else {
+        "null".to_string()
+      })
+    )
   }
 
-  pub fn receive_token(
+  #[allow(clippy::too_many_arguments)]
+  pub fn make_auction_order(
     &self,
     deps: DepsMut,
     env: Env,
-    info: MessageInfo,
-    msg: Cw20ReceiveMsg
+    seller: Addr,
+    // first item is the main nft of the order, others are sold together as a bundle
+    items: Vec<BundleItem>,
+    start_price: Asset,
+    fixed_price: Option<Asset>,
+    reserve_price: Option<Asset>,
+    second_price: bool,
+    buyers: Vec<Addr>,
+    expiration: Expiration
   ) -> Result<Response, ContractError> {
-    let contract_addr = info.sender.clone();
+    if let Some(fixed_price) = fixed_price.clone() {
+      if fixed_price.info != start_price.info {
+        return Err(ContractError::AssetInfoMismatch {})
+      }
+    }
 
-    let sender = deps.api.addr_validate(&msg.sender)?;
+    if let Some(reserve_price) = reserve_price.clone() {
+      if reserve_price.info != start_price.info {
+        return Err(ContractError::AssetInfoMismatch {})
+      }
 
-    let cw20_msg = from_binary::<Cw20HookMsg>(&msg.msg)?;
+      // fixed price can not bypass reserve price
+      if let Some(fixed_price) = fixed_price.clone() {
+        if reserve_price.amount > fixed_price.amount {
+          return Err(ContractError::InvalidReservePrice {})
+        }
+      }
+    }
 
-    let asset = Asset {
-      info: AssetInfo::Token { contract_addr: contract_addr.to_string() },
-      amount: msg.amount
+    for item in items.iter() {
+      self.assert_support_asset(deps.as_ref(), &item.nft_address, &start_price.info)?;
+    }
+
+    let id = self.order_index.load(deps.storage)?;
+
+    // check expiration
+    self.assert_auction_expiration(deps.as_ref(), &env, &expiration)?;
+
+    let auction_info = AuctionInfo {
+      highest_bid: start_price.clone(),
+      bidder: None,
+      expiration,
+      runner_up_bid: None,
+      second_price,
+      referrer: None
     };
 
-    match cw20_msg {
-      Cw20HookMsg::ExecuteOrder { order_id } 
-        => self.execute_order(deps, env, info, sender, order_id, Some(asset)),
-      Cw20HookMsg::Bid { order_id } 
-        => self.bid(deps, env, info, sender, order_id, asset),
-      Cw20HookMsg::CancelOrder { order_id } 
-        => self.cancel_order(deps, env, info, sender, order_id, Some(asset))
+    let order = Order {
+      id,
+      seller_address: seller.clone(),
+      nft_address: items[0].nft_address.clone(),
+      token_id: items[0].token_id.clone(),
+      price: fixed_price.clone(),
+      buyers: buyers.clone(),
+      other_prices: vec![],
+      auction_info: Some(auction_info),
+      expiration: None,
+      bundle: items[1..].to_vec(),
+      dutch_auction_info: None,
+      sealed_bid_auction_info: None,
+      swap_info: None,
+      cw1155_info: None
+    };
+
+    let key = U64Key::new(id);
+
+    self.orders.save(deps.storage, key.clone(), &order)?;
+    self.order_index.save(deps.storage, &(id + 1))?;
+
+    if let Some(reserve_price) = reserve_price.clone() {
+      self.reserve_prices.save(deps.storage, key, &reserve_price)?;
     }
+
+    Ok(Response::new()
+      .add_attribute("action", "make_auction_order")
+      .add_attribute("sender", seller)
+      .add_attribute("order_id", id.to_string())
+      .add_attribute("nft_address", order.nft_address)
+      .add_attribute("token_id", order.token_id)
+      .add_attribute("bundle_size", items.len().to_string())
+      .add_attribute("private", (!buyers.is_empty()).to_string())
+      .add_attribute("fixed_price", if let Some(fixed_price) = fixed_price {
+        format!("{}", fixed_price)
+      } else {
+        "null".to_string()
+      })
+      .add_attribute("start_price", format!("{}", start_price))
+      .add_attribute("has_reserve_price", reserve_price.is_some().to_string())
+      .add_attribute("expiration", format!("{}", expiration))
+    )
   }
 
-  pub fn update_config(
+  #[allow(clippy::too_many_arguments)]
+  pub fn make_dutch_auction_order(
     &self,
     deps: DepsMut,
-    _env: Env,
-    info: MessageInfo,
-    owner: Option<String>,
-    min_increase: Option<Decimal>,
-    max_auction_duration_block: Option<u64>,
-    max_auction_duration_second: Option<u64>,
-    auction_cancel_fee_rate: Option<Decimal>
+    env: Env,
+    seller: Addr,
+    // first item is the main nft of the order, others are sold together as a bundle
+    items: Vec<BundleItem>,
+    start_price: Asset,
+    end_price: Asset,
+    start: Expiration,
+    end: Expiration
   ) -> Result<Response, ContractError> {
-    let mut config: Config = self.config.load(deps.storage)?;
-    
-    if info.sender != config.owner {
-      return Err(ContractError::Unauthorized {})
+    if start_price.info != end_price.info {
+      return Err(ContractError::AssetInfoMismatch {})
     }
 
-    if let Some(owner) = owner {
-      config.owner = deps.api.addr_validate(&owner)?;
+    if end_price.amount >= start_price.amount {
+      return Err(ContractError::InvalidPriceRange {})
     }
 
-    if let Some(min_increase) = min_increase {
-      config.min_increase = min_increase;
+    for item in items.iter() {
+      self.assert_support_asset(deps.as_ref(), &item.nft_address, &start_price.info)?;
     }
 
-    if let Some(max_auction_duration_block) = max_auction_duration_block {
-      config.max_auction_duration_block = max_auction_duration_block;
+    self.assert_period(&start, &end)?;
+
+    if end.is_expired(&env.block) {
+      return Err(ContractError::Expired {})
     }
 
-    if let Some(max_auction_duration_second) = max_auction_duration_second {
-      config.max_auction_duration_second = max_auction_duration_second;
+    let id = self.order_index.load(deps.storage)?;
+
+    let dutch_auction_info = DutchAuctionInfo {
+      start_price: start_price.clone(),
+      end_price: end_price.clone(),
+      start,
+      end
+    };
+
+    let order = Order {
+      id,
+      seller_address: seller.clone(),
+      nft_address: items[0].nft_address.clone(),
+      token_id: items[0].token_id.clone(),
+      price: None,
+      buyers: vec![],
+      other_prices: vec![],
+      auction_info: None,
+      expiration: None,
+      bundle: items[1..].to_vec(),
+      dutch_auction_info: Some(dutch_auction_info),
+      sealed_bid_auction_info: None,
+      swap_info: None,
+      cw1155_info: None
+    };
+
+    let key = U64Key::new(id);
+
+    self.orders.save(deps.storage, key, &order)?;
+    self.order_index.save(deps.storage, &(id + 1))?;
+
+    Ok(Response::new()
+      .add_attribute("action", "make_dutch_auction_order")
+      .add_attribute("sender", seller)
+      .add_attribute("order_id", id.to_string())
+      .add_attribute("nft_address", order.nft_address)
+      .add_attribute("token_id", order.token_id)
+      .add_attribute("bundle_size", items.len().to_string())
+      .add_attribute("start_price", format!("{}", start_price))
+      .add_attribute("end_price", format!("{}", end_price))
+      .add_attribute("start", format!("{}", start))
+      .add_attribute("end", format!("{}", end))
+    )
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  pub fn make_sealed_bid_auction_order(
+    &self,
+    deps: DepsMut,
+    env: Env,
+    seller: Addr,
+    // first item is the main nft of the order, others are sold together as a bundle
+    items: Vec<BundleItem>,
+    min_price: Asset,
+    bid_end: Expiration,
+    reveal_end: Expiration,
+    unrevealed_policy: UnrevealedPolicy,
+    second_price: bool
+  ) -> Result<Response, ContractError> {
+    for item in items.iter() {
+      self.assert_support_asset(deps.as_ref(), &item.nft_address, &min_price.info)?;
     }
 
-    if let Some(auction_cancel_fee_rate) = auction_cancel_fee_rate {
-      if auction_cancel_fee_rate > Decimal::one() {
-        return Err(ContractError::InvalidFeeRate {})
-      }
+    // whole auction must be within max duration
+    self.assert_auction_expiration(deps.as_ref(), &env, &reveal_end)?;
+    self.assert_period(&bid_end, &reveal_end)?;
 
-      config.auction_cancel_fee_rate = auction_cancel_fee_rate;
+    if bid_end.is_expired(&env.block) {
+      return Err(ContractError::Expired {})
     }
 
-    self.config.save(deps.storage, &config)?;
+    let id = self.order_index.load(deps.storage)?;
+
+    let sealed_bid_auction_info = SealedBidAuctionInfo {
+      min_price: min_price.clone(),
+      bid_end,
+      reveal_end,
+      unrevealed_policy,
+      highest_bid: None,
+      bidder: None,
+      runner_up_bid: None,
+      second_price
+    };
 
-    Ok(Response::new().add_attribute("action", "update_config"))
+    let order = Order {
+      id,
+      seller_address: seller.clone(),
+      nft_address: items[0].nft_address.clone(),
+      token_id: items[0].token_id.clone(),
+      price: None,
+      buyers: vec![],
+      other_prices: vec![],
+      auction_info: None,
+      expiration: None,
+      bundle: items[1..].to_vec(),
+      dutch_auction_info: None,
+      sealed_bid_auction_info: Some(sealed_bid_auction_info),
+      swap_info: None,
+      cw1155_info: None
+    };
+
+    let key = U64Key::new(id);
+
+    self.orders.save(deps.storage, key, &order)?;
+    self.order_index.save(deps.storage, &(id + 1))?;
+
+    Ok(Response::new()
+      .add_attribute("action", "make_sealed_bid_auction_order")
+      .add_attribute("sender", seller)
+      .add_attribute("order_id", id.to_string())
+      .add_attribute("nft_address", order.nft_address)
+      .add_attribute("token_id", order.token_id)
+      .add_attribute("bundle_size", items.len().to_string())
+      .add_attribute("min_price", format!("{}", min_price))
+      .add_attribute("bid_end", format!("{}", bid_end))
+      .add_attribute("reveal_end", format!("{}", reveal_end))
+    )
   }
 
-  pub fn execute_order(
+  #[allow(clippy::too_many_arguments)]
+  pub fn make_swap_order(
     &self,
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    sender: Addr,
-    order_id: u64,
+    seller: Addr,
+    // first item is the main nft of the order, others are swapped together as a bundle
+    items: Vec<BundleItem>,
+    nft_address: String,
+    token_id: Option<String>,
+    sweetener: Option<Sweetener>,
+    expiration: Option<Expiration>,
     // for cw20
     asset: Option<Asset>,
   ) -> Result<Response, ContractError> {
-    let key = U64Key::new(order_id);
-    let order = self.orders.load(deps.storage, key)?;
-    let price = order.clone().price;
+    let nft_address = deps.api.addr_validate(&nft_address)?;
 
-    if let Some(price) = price {
-      if let Some(asset) = asset {
-        if price != asset {
-          return Err(ContractError::TokenMismatch {})
-        }
-      } else {
-        // native sent balance check
-        price.assert_sent_native_token_balance(&info)?;
+    // only listable collections
+    self.collections.load(deps.storage, nft_address.to_string())?;
+
+    for item in items.iter() {
+      self.collections.load(deps.storage, item.nft_address.to_string())?;
+    }
+
+    // seller sends funds only for own sweetener
+    if sweetener.as_ref().map(|sweetener| &sweetener.payer) != Some(&SweetenerPayer::Seller) {
+      self.assert_no_funds(&info, &asset)?;
+    }
+
+    if let Some(sweetener) = sweetener.clone() {
+      for item in items.iter() {
+        self.assert_support_asset(deps.as_ref(), &item.nft_address, &sweetener.asset.info)?;
       }
+      self.assert_support_asset(deps.as_ref(), &nft_address, &sweetener.asset.info)?;
 
-      let (messages, remain_amount) = self.execute_order_(deps, order.clone(), sender.clone(), price.clone(), false)?;
+      if sweetener.payer == SweetenerPayer::Seller {
+        self.assert_escrowed(&info, &sweetener.asset, asset)?;
+      }
+    }
 
-      Ok(Response::new().add_messages(messages)
-        .add_attribute("action", "execute_order")
-        .add_attribute("sender", sender.to_string())
-        .add_attribute("order_id", order_id.to_string())
-        .add_attribute("buyer", sender.to_string())
-        .add_attribute("seller", order.seller_address)
-        .add_attribute("price", format!("{}", price))
-        .add_attribute("royalty_amount", price.amount - remain_amount)
-      )
-    } else {
-      return Err(ContractError::NoFixedPrice {})
+    if let Some(expiration) = expiration {
+      if expiration.is_expired(&env.block) {
+        return Err(ContractError::Expired {})
+      }
     }
+
+    let id = self.order_index.load(deps.storage)?;
+
+    let swap_info = SwapInfo {
+      nft_address: nft_address.clone(),
+      token_id: token_id.clone(),
+      sweetener: sweetener.clone()
+    };
+
+    let order = Order {
+      id,
+      seller_address: seller.clone(),
+      nft_address: items[0].nft_address.clone(),
+      token_id: items[0].token_id.clone(),
+      price: None,
+      buyers: vec![],
+      other_prices: vec![],
+      auction_info: None,
+      expiration,
+      bundle: items[1..].to_vec(),
+      dutch_auction_info: None,
+      sealed_bid_auction_info: None,
+      swap_info: Some(swap_info),
+      cw1155_info: None
+    };
+
+    let key = U64Key::new(id);
+
+    self.orders.save(deps.storage, key, &order)?;
+    self.order_index.save(deps.storage, &(id + 1))?;
+
+    Ok(Response::new()
+      .add_attribute("action", "make_swap_order")
+      .add_attribute("sender", seller)
+      .add_attribute("order_id", id.to_string())
+      .add_attribute("nft_address", order.nft_address)
+      .add_attribute("token_id", order.token_id)
+      .add_attribute("bundle_size", items.len().to_string())
+      .add_attribute("wanted_nft_address", nft_address)
+      .add_attribute("wanted_token_id", token_id.unwrap_or_else(|| "null".to_string()))
+      .add_attribute("sweetener", if let Some(sweetener) = sweetener {
+        format!("{}", sweetener.asset)
+      } else {
+        "null".to_string()
+      })
+      .add_attribute("expiration", if let Some(expiration) = expiration {
+        format!("{}", expiration)
+      } else {
+        "null".to_string()
+      })
+    )
   }
 
-  pub fn execute_auction(
+  #[allow(clippy::too_many_arguments)]
+  pub fn commit_bid(
     &self,
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    order_id: u64
+    sender: Addr,
+    order_id: u64,
+    commitment: Binary,
+    deposit: Asset,
+    // for cw20
+    asset: Option<Asset>,
   ) -> Result<Response, ContractError> {
-    let key = U64Key::new(order_id);
-    let order = self.orders.load(deps.storage, key)?;
+    self.assert_escrowed(&info, &deposit, asset)?;
 
-    // check is auction
-    let auction_info = order.clone().auction_info;
-    if let Some(auction_info) = auction_info {
-      // check expiration
-      if !auction_info.expiration.is_expired(&env.block) {
-        return Err(ContractError::NotExpired {})
+    let order = self.orders.load(deps.storage, U64Key::new(order_id))?;
+
+    if let Some(sealed_bid_auction_info) = order.sealed_bid_auction_info {
+      if sealed_bid_auction_info.bid_end.is_expired(&env.block) {
+        return Err(ContractError::Expired {})
       }
 
-      let bidder = auction_info.bidder.clone();
-    
-      let (messages, remain_amount, buyer): (Vec<CosmosMsg>, Uint128, String);
-      
-      if let Some(bidder) = bidder {
-        let (messages_, remain_amount_) = self.execute_order_(deps, order.clone(), bidder.clone(), auction_info.highest_bid.clone(), true)?;
-        messages = messages_;
-        remain_amount = remain_amount_;
-        buyer = bidder.to_string()
-        // no bidder
-      } else {
-        // return nft to seller
-        messages = vec![CosmosMsg::Wasm(WasmMsg::Execute {
-          contract_addr: order.nft_address.to_string(),
-          msg: to_binary(&Cw721ExecuteMsg::TransferNft {
-            recipient: order.seller_address.to_string(), 
-            token_id: order.token_id
-          })?,
-          funds: vec![]
-        })];
+      if deposit.info != sealed_bid_auction_info.min_price.info {
+        return Err(ContractError::AssetInfoMismatch {})
+      }
 
-        remain_amount = auction_info.highest_bid.amount;
-        buyer = "null".to_string();
+      if deposit.amount < sealed_bid_auction_info.min_price.amount {
+        return Err(ContractError::MinPrice { min_bid_amount: sealed_bid_auction_info.min_price.amount })
+      }
 
-        // remove order
-        self.orders.remove(deps.storage, U64Key::new(order.id))?;
+      let key = (U64Key::new(order_id), sender.clone());
+
+      if self.sealed_bids.has(deps.storage, key.clone()) {
+        return Err(ContractError::AlreadyCommitted {})
       }
 
-      Ok(Response::new().add_messages(messages)
-        .add_attribute("action", "execute_auction")
-        .add_attribute("sender", info.sender.to_string())
+      let sealed_bid = SealedBid {
+        order_id,
+        bidder: sender.clone(),
+        commitment,
+        deposit: deposit.clone(),
+        amount: None
+      };
+
+      self.sealed_bids.save(deps.storage, key, &sealed_bid)?;
+
+      Ok(Response::new()
+        .add_attribute("action", "commit_bid")
+        .add_attribute("sender", sender.to_string())
         .add_attribute("order_id", order_id.to_string())
-        .add_attribute("buyer", buyer)
-        .add_attribute("seller", order.seller_address)
-        .add_attribute("price", format!("{}", auction_info.highest_bid))
-        .add_attribute("royalty_amount", auction_info.highest_bid.amount - remain_amount)
+        .add_attribute("deposit", format!("{}", deposit))
       )
     } else {
-      return Err(ContractError::NotAuction {})
+      Err(ContractError::NotAuction {})
     }
   }
 
-  pub fn cancel_order(
+  pub fn withdraw_sealed_bid(
     &self,
     deps: DepsMut,
-    env: Env,
     info: MessageInfo,
-    sender: Addr,
     order_id: u64,
-    cancel_fee: Option<Asset>
+    bidder: Option<String>
   ) -> Result<Response, ContractError> {
-    let key = U64Key::new(order_id);
+    let bidder = match bidder {
+      Some(bidder) => deps.api.addr_validate(&bidder)?,
+      None => info.sender.clone()
+    };
 
-    let order = self.orders.load(deps.storage, key.clone())?;
+    let settlement = self.sealed_bid_settlements.may_load(deps.storage, U64Key::new(order_id))?
+      .ok_or(ContractError::NotSettled {})?;
 
-    // only seller can execute
-    if order.seller_address != sender.clone() {
-      return Err(ContractError::Unauthorized {})
-    }
+    let key = (U64Key::new(order_id), bidder.clone());
+    let sealed_bid = self.sealed_bids.load(deps.storage, key.clone())?;
 
+    self.sealed_bids.remove(deps.storage, key);
+
+    let recipient = match (sealed_bid.amount, settlement.unrevealed_policy) {
+      (None, UnrevealedPolicy::Forfeit) => settlement.seller,
+      _ => bidder.clone()
+    };
+
+    // settlement is done when all deposits are withdrawn
+    let deposit_left = self.sealed_bids
+      .prefix(U64Key::new(order_id))
+      .keys(deps.storage, None, None, Ascending)
+      .next()
+      .is_some();
+
+    if !deposit_left {
+      self.sealed_bid_settlements.remove(deps.storage, U64Key::new(order_id));
+    }
+
+    Ok(Response::new()
+      .add_message(sealed_bid.deposit.clone().into_msg(&deps.querier, recipient.clone())?)
+      .add_attribute("action", "withdraw_sealed_bid")
+      .add_attribute("sender", info.sender.to_string())
+      .add_attribute("order_id", order_id.to_string())
+      .add_attribute("bidder", bidder.to_string())
+      .add_attribute("recipient", recipient.to_string())
+      .add_attribute("deposit", format!("{}", sealed_bid.deposit))
+    )
+  }
+
+  pub fn reveal_bid(
+    &self,
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    order_id: u64,
+    amount: Uint128,
+    salt: String
+  ) -> Result<Response, ContractError> {
+    let mut order = self.orders.load(deps.storage, U64Key::new(order_id))?;
+
+    if let Some(mut sealed_bid_auction_info) = order.sealed_bid_auction_info.clone() {
+      if !sealed_bid_auction_info.bid_end.is_expired(&env.block) {
+        return Err(ContractError::RevealNotStarted {})
+      }
+
+      if sealed_bid_auction_info.reveal_end.is_expired(&env.block) {
+        return Err(ContractError::Expired {})
+      }
+
+      let key = (U64Key::new(order_id), info.sender.clone());
+      let mut sealed_bid = self.sealed_bids.load(deps.storage, key.clone())?;
+
+      if sealed_bid.amount.is_some() {
+        return Err(ContractError::AlreadyRevealed {})
+      }
+
+      let hash = Sha256::digest(format!("{}:{}:{}:{}", order_id, info.sender, amount, salt).as_bytes());
+
+      if hash[..] != sealed_bid.commitment[..] {
+        return Err(ContractError::InvalidReveal {})
+      }
+
+      if amount > sealed_bid.deposit.amount || amount < sealed_bid_auction_info.min_price.amount {
+        return Err(ContractError::InvalidReveal {})
+      }
+
+      sealed_bid.amount = Some(amount);
+      self.sealed_bids.save(deps.storage, key, &sealed_bid)?;
+
+      let revealed_bid = Asset {
+        info: sealed_bid.deposit.info.clone(),
+        amount
+      };
+
+      // first revealer wins the tie
+      match sealed_bid_auction_info.highest_bid.clone() {
+        Some(highest_bid) if amount <= highest_bid.amount => {
+          let is_runner_up = match sealed_bid_auction_info.runner_up_bid.clone() {
+            Some(runner_up_bid) => amount > runner_up_bid.amount,
+            None => true
+          };
+
+          if is_runner_up {
+            sealed_bid_auction_info.runner_up_bid = Some(revealed_bid);
+          }
+        }
+        highest_bid => {
+          sealed_bid_auction_info.runner_up_bid = highest_bid;
+          sealed_bid_auction_info.highest_bid = Some(revealed_bid);
+          sealed_bid_auction_info.bidder = Some(info.sender.clone());
+        }
+      }
+
+      order.sealed_bid_auction_info = Some(sealed_bid_auction_info);
+      self.orders.save(deps.storage, U64Key::new(order_id), &order)?;
+
+      Ok(Response::new()
+        .add_attribute("action", "reveal_bid")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("order_id", order_id.to_string())
+        .add_attribute("amount", amount.to_string())
+      )
+    } else {
+      Err(ContractError::NotAuction {})
+    }
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  pub fn fulfill_swap(
+    &self,
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    sender: Addr,
+    order_id: u64,
+    // nft of the counterparty
+    item: BundleItem,
+    // for cw20
+    asset: Option<Asset>,
+  ) -> Result<Response, ContractError> {
+    let key = U64Key::new(order_id);
+    let order = self.orders.load(deps.storage, key.clone())?;
+
+    let swap_info = match order.swap_info.clone() {
+      Some(swap_info) => swap_info,
+      None => return Err(ContractError::NotSwapOrder {})
+    };
+
+    if order.is_expired(&env.block) {
+      return Err(ContractError::Expired {})
+    }
+
+    if item.nft_address != swap_info.nft_address {
+      return Err(ContractError::CollectionMismatch {})
+    }
+
+    if let Some(token_id) = swap_info.token_id {
+      if token_id != item.token_id {
+        return Err(ContractError::TokenIdMismatch {})
+      }
+    }
+
+    // counterparty sends funds only for own sweetener
+    if swap_info.sweetener.as_ref().map(|sweetener| &sweetener.payer) != Some(&SweetenerPayer::Counterparty) {
+      self.assert_no_funds(&info, &asset)?;
+    }
+
+    let items = order.items();
+
+    // swap nfts
+    let mut messages = self.transfer_nfts(&items, &sender)?;
+    messages.extend(self.transfer_nfts(std::slice::from_ref(&item), &order.seller_address)?);
+
+    // price of the trade is the sweetener paid by the counterparty
+    let mut price: Option<Asset> = None;
+    let mut royalty_amount = Uint128::zero();
+    let mut protocol_fee = Uint128::zero();
+
+    // sweetener goes to the other side. royalties follow the swap royalty policy of the collections sold for it
+    if let Some(sweetener) = swap_info.sweetener.clone() {
+      let (payout_messages, remain_amount, protocol_fee_) = match sweetener.payer {
+        SweetenerPayer::Seller => {
+          self.payout(
+            deps.as_ref(), vec![item.nft_address.clone()], sender.clone(), order.seller_address.clone(), sweetener.asset.clone(), true, None
+          )?
+        }
+        SweetenerPayer::Counterparty => {
+          self.assert_escrowed(&info, &sweetener.asset, asset)?;
+          price = Some(sweetener.asset.clone());
+
+          let nft_addresses = items.iter().map(|item| item.nft_address.clone()).collect();
+          self.payout(
+            deps.as_ref(), nft_addresses, order.seller_address.clone(), sender.clone(), sweetener.asset.clone(), true, None
+          )?
+        }
+      };
+
+      messages.extend(payout_messages);
+      royalty_amount = sweetener.asset.amount - remain_amount - protocol_fee_;
+      protocol_fee = protocol_fee_;
+    }
+
+    self.save_trade(
+      deps.storage, &env, SaleType::Swap, Some(order_id), None, items, None, order.seller_address.clone(), sender.clone(), price, royalty_amount,
+      protocol_fee
+    )?;
+
+    // remove order
+    self.orders.remove(deps.storage, key)?;
+
+    Ok(Response::new().add_messages(messages)
+      .add_attribute("action", "fulfill_swap")
+      .add_attribute("sender", sender.to_string())
+      .add_attribute("order_id", order_id.to_string())
+      .add_attribute("seller", order.seller_address)
+      .add_attribute("counterparty", sender)
+      .add_attribute("nft_address", item.nft_address)
+      .add_attribute("token_id", item.token_id)
+      .add_attribute("sweetener", if let Some(sweetener) = swap_info.sweetener {
+        format!("{}", sweetener.asset)
+      } else {
+        "null".to_string()
+      })
+      .add_attribute("protocol_fee", protocol_fee)
+    )
+  }
+
+  pub fn withdraw_bundle(
+    &self,
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+  ) -> Result<Response, ContractError> {
+    let items = self.take_bundle(deps.storage, &info.sender)?;
+
+    let messages = self.transfer_nfts(&items, &info.sender)?;
+
+    Ok(Response::new().add_messages(messages)
+      .add_attribute("action", "withdraw_bundle")
+      .add_attribute("sender", info.sender)
+    )
+  }
+
+  pub fn receive_cw1155(
+    &self,
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    sender: String,
+    batch: Vec<(String, Uint128)>,
+    msg: Binary
+  ) -> Result<Response, ContractError> {
+    let contract_addr = info.sender;
+
+    let seller = deps.api.addr_validate(&sender)?;
+
+    let cw1155_msg = from_binary::<Cw1155HookMsg>(&msg)?;
+
+    match cw1155_msg {
+      Cw1155HookMsg::MakeFixedPriceOrder { price, expiration } => {
+        let mut response = Response::new();
+
+        // an order per token id
+        for (token_id, amount) in batch {
+          let item = BundleItem { nft_address: contract_addr.clone(), token_id };
+          let cw1155_info = Cw1155Info { amount, remaining: amount };
+
+          let res = self.make_fixed_price_order(
+            deps.branch(), env.clone(), seller.clone(), vec![item], price.clone(), vec![], vec![], Some(cw1155_info), expiration
+          )?;
+
+          response = response.add_attributes(res.attributes);
+        }
+
+        Ok(response)
+      }
+    }
+  }
+
+  pub fn receive_token(
+    &self,
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw20ReceiveMsg
+  ) -> Result<Response, ContractError> {
+    let contract_addr = info.sender.clone();
+
+    let sender = deps.api.addr_validate(&msg.sender)?;
+
+    let cw20_msg = from_binary::<Cw20HookMsg>(&msg.msg)?;
+
+    let asset = Asset {
+      info: AssetInfo::Token { contract_addr: contract_addr.to_string() },
+      amount: msg.amount
+    };
+
+    match cw20_msg {
+      Cw20HookMsg::ExecuteOrder { order_id, amount, referrer } 
+        => self.execute_order(deps, env, info, sender, order_id, amount, referrer, Some(asset)),
+      Cw20HookMsg::ExecuteOrders { order_ids, skip_unavailable }
+        => self.execute_orders(deps, env, info, sender, order_ids, asset.clone(), skip_unavailable, Some(asset)),
+      Cw20HookMsg::SweepFloor { nft_address, count, max_unit_price }
+        => self.sweep_floor(deps, env, info, sender, nft_address, count, max_unit_price, asset.clone(), Some(asset)),
+      Cw20HookMsg::Bid { order_id, referrer } 
+        => self.bid(deps, env, info, sender, order_id, asset, referrer),
+      Cw20HookMsg::CancelOrder { order_id } 
+        => self.cancel_order(deps, env, info, sender, order_id, Some(asset)),
+      Cw20HookMsg::MakeCollectionOffer { nft_address }
+        => self.make_offer(deps, env, info, sender, nft_address, None, asset.clone(), Expiration::Never {}, Some(asset)),
+      Cw20HookMsg::MakeOffer { nft_address, token_id, expiration }
+        => self.make_offer(deps, env, info, sender, nft_address, Some(token_id), asset.clone(), expiration, Some(asset)),
+      Cw20HookMsg::CommitBid { order_id, commitment }
+        => self.commit_bid(deps, env, info, sender, order_id, commitment, asset.clone(), Some(asset)),
+      Cw20HookMsg::MakeBundleSwapOrder { nft_address, token_id, expiration } => {
+        let items = self.take_bundle(deps.storage, &sender)?;
+        let sweetener = Sweetener { asset: asset.clone(), payer: SweetenerPayer::Seller };
+
+        self.make_swap_order(deps, env, info, sender, items, nft_address, token_id, Some(sweetener), expiration, Some(asset))
+      }
+      Cw20HookMsg::FulfillBundleSwap { order_id } => {
+        let item = self.take_swap_item(deps.storage, &sender)?;
+
+        self.fulfill_swap(deps, env, info, sender, order_id, item, Some(asset))
+      }
+    }
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  pub fn update_config(
+    &self,
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    owner: Option<String>,
+    min_increase: Option<Decimal>,
+    max_auction_duration_block: Option<u64>,
+    max_auction_duration_second: Option<u64>,
+    auction_cancel_fee_rate: Option<Decimal>,
+    auction_extension_block: Option<u64>,
+    auction_extension_second: Option<u64>,
+    protocol_fee_rate: Option<Decimal>,
+    treasury: Option<String>,
+    fee_discount: Option<FeeDiscount>,
+    referral_share: Option<Decimal>,
+  ) -> Result<Response, ContractError> {
+    let mut config: Config = self.config.load(deps.storage)?;
+    
+    if info.sender != config.owner {
+      return Err(ContractError::Unauthorized {})
+    }
+
+    if let Some(owner) = owner {
+      config.owner = deps.api.addr_validate(&owner)?;
+    }
+
+    if let Some(min_increase) = min_increase {
+      config.min_increase = min_increase;
+    }
+
+    if let Some(max_auction_duration_block) = max_auction_duration_block {
+      config.max_auction_duration_block = max_auction_duration_block;
+    }
+
+    if let Some(max_auction_duration_second) = max_auction_duration_second {
+      config.max_auction_duration_second = max_auction_duration_second;
+    }
+
+    if let Some(auction_cancel_fee_rate) = auction_cancel_fee_rate {
+      if auction_cancel_fee_rate > Decimal::one() {
+        return Err(ContractError::InvalidFeeRate {})
+      }
+
+      config.auction_cancel_fee_rate = auction_cancel_fee_rate;
+    }
+
+    if let Some(auction_extension_block) = auction_extension_block {
+      config.auction_extension_block = auction_extension_block;
+    }
+
+    if let Some(auction_extension_second) = auction_extension_second {
+      config.auction_extension_second = auction_extension_second;
+    }
+
+    if let Some(protocol_fee_rate) = protocol_fee_rate {
+      if protocol_fee_rate > Decimal::one() {
+        return Err(ContractError::InvalidProtocolFeeRate {})
+      }
+
+      // collections without override use the new rate
+      let collections = self.collections
+        .range(deps.storage, None, None, Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+      for (_, collection) in collections.iter() {
+        if collection.protocol_fee_rate.is_none() {
+          self.assert_protocol_fee_rate(protocol_fee_rate, &collection.royalties)?;
+        }
+      }
+
+      config.protocol_fee_rate = protocol_fee_rate;
+    }
+
+    if let Some(treasury) = treasury {
+      config.treasury = deps.api.addr_validate(&treasury)?;
+    }
+
+    if let Some(fee_discount) = fee_discount {
+      if fee_discount.tiers.iter().any(|tier| tier.discount_rate > Decimal::one()) {
+        return Err(ContractError::InvalidFeeDiscount {})
+      }
+
+      let source = match fee_discount.source {
+        FeeDiscountSource::Cw20 { contract_addr }
+          => FeeDiscountSource::Cw20 { contract_addr: deps.api.addr_validate(contract_addr.as_str())? },
+        FeeDiscountSource::Staking { contract_addr }
+          => FeeDiscountSource::Staking { contract_addr: deps.api.addr_validate(contract_addr.as_str())? },
+      };
+
+      config.fee_discount = if fee_discount.tiers.is_empty() {
+        None
+      } else {
+        Some(FeeDiscount { source, tiers: fee_discount.tiers })
+      };
+    }
+
+    if let Some(referral_share) = referral_share {
+      if referral_share > Decimal::one() {
+        return Err(ContractError::InvalidReferralShare {})
+      }
+
+      config.referral_share = referral_share;
+    }
+
+    self.config.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  pub fn execute_order(
+    &self,
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    sender: Addr,
+    order_id: u64,
+    // units to buy from cw1155 order
+    amount: Option<Uint128>,
+    referrer: Option<String>,
+    // for cw20
+    asset: Option<Asset>,
+  ) -> Result<Response, ContractError> {
+    let referrer = referrer.map(|referrer| deps.api.addr_validate(&referrer)).transpose()?;
+    let key = U64Key::new(order_id);
+    let order = self.orders.load(deps.storage, key)?;
+    let price = order.current_price(&env.block);
+
+    let units = match (&order.cw1155_info, amount) {
+      (Some(cw1155_info), amount) => {
+        let units = amount.unwrap_or(cw1155_info.remaining);
+
+        if units.is_zero() || units > cw1155_info.remaining {
+          return Err(ContractError::InvalidAmount {})
+        }
+
+        units
+      }
+      (None, None) => Uint128::from(1u128),
+      (None, Some(_)) => return Err(ContractError::InvalidAmount {})
+    };
+
+    if order.is_expired(&env.block) {
+      return Err(ContractError::Expired {})
+    }
+
+    if !order.is_allowed_buyer(&sender) {
+      return Err(ContractError::NotAllowedBuyer {})
+    }
+
+    if let Some(mut price) = price {
+      // overpayment of dutch auction is refunded to the buyer
+      let mut refund_messages: Vec<CosmosMsg> = vec![];
+
+      if order.dutch_auction_info.is_some() {
+        let paid_amount = if let Some(asset) = asset {
+          if price.info != asset.info {
+            return Err(ContractError::TokenMismatch {})
+          }
+          asset.amount
+        } else {
+          match price.info.clone() {
+            AssetInfo::NativeToken { denom } => {
+              info.funds.iter().find(|x| x.denom == denom).map(|coin| coin.amount).unwrap_or_default()
+            }
+            AssetInfo::Token { .. } => return Err(ContractError::TokenMismatch {})
+          }
+        };
+
+        if paid_amount < price.amount {
+          return Err(ContractError::InsufficientPayment { price })
+        }
+
+        let paid = Asset {
+          info: price.info.clone(),
+          amount: paid_amount
+        };
+
+        refund_messages = self.refund_difference(deps.as_ref(), &paid, &price, &sender)?;
+      } else {
+        // buyer can pay with any of the order prices. price of cw1155 order is per unit
+        let prices = order.prices(&env.block).into_iter()
+          .map(|price| Ok(Asset { amount: price.amount.checked_mul(units)?, info: price.info }))
+          .collect::<StdResult<Vec<Asset>>>()?;
+
+        price = self.select_price(&info, &prices, asset)?;
+      }
+
+      let (mut messages, remain_amount, protocol_fee) = if order.cw1155_info.is_some() {
+        self.execute_cw1155_order_(deps, &env, order.clone(), sender.clone(), units, price.clone(), referrer.clone())?
+      } else {
+        self.execute_order_(deps, &env, order.clone(), sender.clone(), price.clone(), false, referrer.clone())?
+      };
+      messages.extend(refund_messages);
+
+      Ok(Response::new().add_messages(messages)
+        .add_attribute("action", "execute_order")
+        .add_attribute("sender", sender.to_string())
+        .add_attribute("order_id", order_id.to_string())
+        .add_attribute("buyer", sender.to_string())
+        .add_attribute("seller", order.seller_address)
+        .add_attribute("price", format!("{}", price))
+        .add_attribute("amount", units.to_string())
+        .add_attribute("royalty_amount", price.amount - remain_amount - protocol_fee)
+        .add_attribute("protocol_fee", protocol_fee)
+        .add_attribute("referrer", referrer.map(|referrer| referrer.to_string()).unwrap_or_else(|| "null".to_string()))
+      )
+    } else {
+      Err(ContractError::NoFixedPrice {})
+    }
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  pub fn execute_orders(
+    &self,
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    sender: Addr,
+    order_ids: Vec<u64>,
+    max_total: Asset,
+    skip_unavailable: bool,
+    // for cw20
+    asset: Option<Asset>,
+  ) -> Result<Response, ContractError> {
+    self.assert_escrowed(&info, &max_total, asset)?;
+
+    let mut selected: Vec<(Order, Asset)> = vec![];
+    let mut total = Uint128::zero();
+    let mut skipped: Vec<String> = vec![];
+
+    // check all orders before executing any
+    for order_id in order_ids.iter() {
+      let selected_ = self.orders.load(deps.storage, U64Key::new(*order_id))
+        .map_err(ContractError::Std)
+        .and_then(|order| {
+          // order can be bought only once
+          if selected.iter().any(|(selected_order, _)| selected_order.id == order.id) {
+            return Err(ContractError::Std(StdError::not_found("order")))
+          }
+
+          let price = self.sweep_price(&env, &sender, &order, &max_total.info)?;
+
+          if total + price.amount > max_total.amount {
+            return Err(ContractError::MaxTotalExceeded {})
+          }
+
+          Ok((order, price))
+        });
+
+      match selected_ {
+        Ok((order, price)) => {
+          total += price.amount;
+          selected.push((order, price));
+        }
+        Err(_) if skip_unavailable => skipped.push(order_id.to_string()),
+        Err(err) => return Err(err)
+      }
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let executed: Vec<String> = selected.iter().map(|(order, _)| order.id.to_string()).collect();
+    let mut royalty_amount = Uint128::zero();
+    let mut protocol_fee = Uint128::zero();
+
+    for (order, price) in selected {
+      let (messages_, remain_amount, protocol_fee_) = if let Some(cw1155_info) = order.cw1155_info.clone() {
+        self.execute_cw1155_order_(deps.branch(), &env, order, sender.clone(), cw1155_info.remaining, price.clone(), None)?
+      } else {
+        self.execute_order_(deps.branch(), &env, order, sender.clone(), price.clone(), false, None)?
+      };
+
+      royalty_amount += price.amount - remain_amount - protocol_fee_;
+      protocol_fee += protocol_fee_;
+      messages.extend(messages_);
+    }
+
+    // refund unused amount
+    let refund_amount = max_total.amount - total;
+
+    if !refund_amount.is_zero() {
+      messages.push((Asset {
+        info: max_total.info.clone(),
+        amount: refund_amount
+      }).into_msg(&deps.querier, sender.clone())?);
+    }
+
+    Ok(Response::new().add_messages(messages)
+      .add_attribute("action", "execute_orders")
+      .add_attribute("sender", sender.to_string())
+      .add_attribute("buyer", sender.to_string())
+      .add_attribute("executed_order_ids", executed.join(","))
+      .add_attribute("skipped_order_ids", skipped.join(","))
+      .add_attribute("total", format!("{}", Asset { info: max_total.info, amount: total }))
+      .add_attribute("royalty_amount", royalty_amount)
+      .add_attribute("protocol_fee", protocol_fee)
+      .add_attribute("refund_amount", refund_amount.to_string())
+    )
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  pub fn sweep_floor(
+    &self,
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    sender: Addr,
+    nft_address: String,
+    count: u32,
+    max_unit_price: Uint128,
+    budget: Asset,
+    // for cw20
+    asset: Option<Asset>,
+  ) -> Result<Response, ContractError> {
+    self.assert_escrowed(&info, &budget, asset)?;
+
+    let nft_address = deps.api.addr_validate(&nft_address)?;
+
+    let mut selected: Vec<(Order, Uint128, Asset)> = vec![];
+    let mut total = Uint128::zero();
+    let mut remaining_count = Uint128::from(count);
+
+    // orders are sorted by unit price, stop reading at the first unaffordable one.
+    // reading is limited, so the sweep buys what is found within the limit
+    let keys = self.orders.idx.floor
+      .prefix((nft_address, budget.info.to_string()))
+      .keys(deps.storage, None, None, Ascending)
+      .take(MAX_FLOOR_SCAN);
+
+    for key in keys {
+      let (unit_price, pk) = parse_floor_key(&key);
+
+      if remaining_count.is_zero() || unit_price > max_unit_price || total + unit_price > budget.amount {
+        break
+      }
+
+      let order = self.orders.load(deps.storage, U64Key::from(pk))?;
+
+      if order.is_expired(&env.block) || !order.is_allowed_buyer(&sender) {
+        continue
+      }
+
+      let affordable = (budget.amount - total) / unit_price.max(Uint128::from(1u128));
+
+      let units = match order.cw1155_info.clone() {
+        Some(cw1155_info) => cw1155_info.remaining.min(remaining_count).min(affordable),
+        None => Uint128::from(1u128)
+      };
+
+      let price = Asset { info: budget.info.clone(), amount: unit_price.checked_mul(units)? };
+
+      total += price.amount;
+      remaining_count -= units;
+      selected.push((order, units, price));
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let executed: Vec<String> = selected.iter().map(|(order, _, _)| order.id.to_string()).collect();
+    let mut bought_count = Uint128::zero();
+    let mut royalty_amount = Uint128::zero();
+    let mut protocol_fee = Uint128::zero();
+
+    for (order, units, price) in selected {
+      let (messages_, remain_amount, protocol_fee_) = if order.cw1155_info.is_some() {
+        self.execute_cw1155_order_(deps.branch(), &env, order, sender.clone(), units, price.clone(), None)?
+      } else {
+        self.execute_order_(deps.branch(), &env, order, sender.clone(), price.clone(), false, None)?
+      };
+
+      royalty_amount += price.amount - remain_amount - protocol_fee_;
+      protocol_fee += protocol_fee_;
+      bought_count += units;
+      messages.extend(messages_);
+    }
+
+    // refund unused amount
+    let refund_amount = budget.amount - total;
+
+    if !refund_amount.is_zero() {
+      messages.push((Asset {
+        info: budget.info.clone(),
+        amount: refund_amount
+      }).into_msg(&deps.querier, sender.clone())?);
+    }
+
+    Ok(Response::new().add_messages(messages)
+      .add_attribute("action", "sweep_floor")
+      .add_attribute("sender", sender.to_string())
+      .add_attribute("buyer", sender.to_string())
+      .add_attribute("executed_order_ids", executed.join(","))
+      .add_attribute("count", bought_count.to_string())
+      .add_attribute("total", format!("{}", Asset { info: budget.info, amount: total }))
+      .add_attribute("royalty_amount", royalty_amount)
+      .add_attribute("protocol_fee", protocol_fee)
+      .add_attribute("refund_amount", refund_amount.to_string())
+    )
+  }
+
+  pub fn execute_auction(
+    &self,
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    order_id: u64
+  ) -> Result<Response, ContractError> {
+    let key = U64Key::new(order_id);
+    let order = self.orders.load(deps.storage, key)?;
+
+    if let Some(sealed_bid_auction_info) = order.sealed_bid_auction_info.clone() {
+      return self.execute_sealed_bid_auction(deps, env, info, order, sealed_bid_auction_info)
+    }
+
+    // check is auction
+    let auction_info = order.clone().auction_info;
+    if let Some(auction_info) = auction_info {
+      // check expiration
+      if !auction_info.expiration.is_expired(&env.block) {
+        return Err(ContractError::NotExpired {})
+      }
+
+      let bidder = auction_info.bidder.clone();
+      let reserve_met = self.reserve_met(deps.as_ref(), order_id)?;
+    
+      let (mut messages, remain_amount, protocol_fee, buyer, price): (Vec<CosmosMsg>, Uint128, Uint128, String, Asset);
+      
+      if let Some(bidder) = bidder.clone().filter(|_| reserve_met) {
+        price = if auction_info.second_price {
+          let reserve_amount = self.reserve_prices.may_load(deps.storage, U64Key::new(order_id))?
+            .map(|reserve_price| reserve_price.amount)
+            .unwrap_or_default();
+
+          self.clearing_price(deps.as_ref(), &auction_info.highest_bid, auction_info.runner_up_bid.clone(), reserve_amount)?
+        } else {
+          auction_info.highest_bid.clone()
+        };
+
+        // refund the difference between the bid and the clearing price
+        let refund_messages = self.refund_difference(deps.as_ref(), &auction_info.highest_bid, &price, &bidder)?;
+
+        let (messages_, remain_amount_, protocol_fee_) = self.execute_order_(
+          deps, &env, order.clone(), bidder.clone(), price.clone(), true, auction_info.referrer.clone()
+        )?;
+        messages = messages_;
+        messages.extend(refund_messages);
+        remain_amount = remain_amount_;
+        protocol_fee = protocol_fee_;
+        buyer = bidder.to_string()
+        // no bidder or reserve price not met
+      } else {
+        // return nft to seller
+        messages = self.transfer_nfts(&order.items(), &order.seller_address)?;
+
+        // refund highest bid
+        if let Some(bidder) = bidder {
+          messages.push(auction_info.highest_bid.clone().into_msg(&deps.querier, bidder)?);
+        }
+
+        price = auction_info.highest_bid.clone();
+        remain_amount = auction_info.highest_bid.amount;
+        protocol_fee = Uint128::zero();
+        buyer = "null".to_string();
+
+        // remove order
+        self.orders.remove(deps.storage, U64Key::new(order.id))?;
+        self.reserve_prices.remove(deps.storage, U64Key::new(order.id));
+        self.remove_outbids(deps.storage, order.id)?;
+      }
+
+      Ok(Response::new().add_messages(messages)
+        .add_attribute("action", "execute_auction")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("order_id", order_id.to_string())
+        .add_attribute("buyer", buyer)
+        .add_attribute("seller", order.seller_address)
+        .add_attribute("price", format!("{}", price))
+        .add_attribute("royalty_amount", price.amount - remain_amount - protocol_fee)
+        .add_attribute("protocol_fee", protocol_fee)
+      )
+    } else {
+      Err(ContractError::NotAuction {})
+    }
+  }
+
+  pub fn cancel_order(
+    &self,
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    sender: Addr,
+    order_id: u64,
+    cancel_fee: Option<Asset>
+  ) -> Result<Response, ContractError> {
+    let key = U64Key::new(order_id);
+
+    let order = self.orders.load(deps.storage, key.clone())?;
+
+    // only seller can execute
+    if order.seller_address != sender.clone() {
+      return Err(ContractError::Unauthorized {})
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+
+    // if auction refund latest bid first
+    let auction_info = order.clone().auction_info;
+    if let Some(auction_info) = auction_info {
+      // can not cancel expired auction
+      if auction_info.expiration.is_expired(&env.block) {
+        return Err(ContractError::Expired {})
+      }
+      messages = self.refund_bid(deps.as_ref(), info.clone(), order.clone(), cancel_fee)?;
+    }
+
+    // sealed bid auction can not be canceled after commit
+    if order.sealed_bid_auction_info.is_some() {
+      let bid_exist = self.sealed_bids
+        .prefix(key.clone())
+        .keys(deps.storage, None, None, Ascending)
+        .next()
+        .is_some();
+
+      if bid_exist {
+        return Err(ContractError::BidExist {})
+      }
+    }
+
+    // return nft and escrowed sweetener to seller
+    messages.extend(self.return_nfts(&env, &order)?);
+    messages.extend(self.refund_sweetener(deps.as_ref(), &order)?);
+
+    // remove order
+    self.orders.remove(deps.storage, key.clone())?;
+    self.reserve_prices.remove(deps.storage, key);
+    self.remove_outbids(deps.storage, order_id)?;
+
+    Ok(Response::new().add_messages(messages)
+      .add_attribute("action", "cancel_order")
+      .add_attribute("sender", sender)
+      .add_attribute("order_id", order_id.to_string())
+    )
+  }
+
+  pub fn reclaim_expired(
+    &self,
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    order_ids: Vec<u64>
+  ) -> Result<Response, ContractError> {
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut reclaimed_ids: Vec<String> = vec![];
+    let mut skipped_ids: Vec<String> = vec![];
+
+    for order_id in order_ids.iter() {
+      let key = U64Key::new(*order_id);
+
+      // skip missing, auction and not expired orders. one bad id must not block the others
+      let order = match self.orders.may_load(deps.storage, key.clone())? {
+        Some(order) if order.auction_info.is_none() && order.is_expired(&env.block) => order,
+        _ => {
+          skipped_ids.push(order_id.to_string());
+          continue
+        }
+      };
+
+      // return nft and escrowed sweetener to seller
+      messages.extend(self.return_nfts(&env, &order)?);
+      messages.extend(self.refund_sweetener(deps.as_ref(), &order)?);
+
+      // remove order
+      self.orders.remove(deps.storage, key)?;
+      reclaimed_ids.push(order_id.to_string());
+    }
+
+    Ok(Response::new().add_messages(messages)
+      .add_attribute("action", "reclaim_expired")
+      .add_attribute("sender", info.sender)
+      .add_attribute("order_ids", reclaimed_ids.join(","))
+      .add_attribute("skipped_order_ids", skipped_ids.join(","))
+    )
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  pub fn update_order(
+    &self,
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    order_id: u64,
+    price: Option<Asset>,
+    fixed_price: Option<Asset>,
+    expiration: Option<Expiration>,
+  ) -> Result<Response, ContractError> {
+    let key = U64Key::new(order_id);
+
+    let mut order = self.orders.load(deps.storage, key.clone())?;
+
+    // only seller can execute
+    if order.seller_address != info.sender {
+      return Err(ContractError::Unauthorized {})
+    }
+
+    if order.dutch_auction_info.is_some() {
+      return Err(ContractError::DutchAuction {})
+    }
+
+    if order.sealed_bid_auction_info.is_some() {
+      return Err(ContractError::SealedBidAuction {})
+    }
+
+    if order.swap_info.is_some() {
+      return Err(ContractError::SwapOrder {})
+    }
+
+    if let Some(mut auction_info) = order.auction_info.clone() {
+      // can not update expired auction
+      if auction_info.expiration.is_expired(&env.block) {
+        return Err(ContractError::Expired {})
+      }
+
+      // can not update auction after bid
+      if auction_info.bidder.is_some() {
+        return Err(ContractError::BidExist {})
+      }
+
+      if let Some(start_price) = price {
+        for item in order.items().iter() {
+          self.assert_support_asset(deps.as_ref(), &item.nft_address, &start_price.info)?;
+        }
+        auction_info.highest_bid = start_price;
+      }
+
+      if let Some(fixed_price) = fixed_price {
+        order.price = Some(fixed_price);
+      }
+
+      if let Some(fixed_price) = order.price.clone() {
+        if fixed_price.info != auction_info.highest_bid.info {
+          return Err(ContractError::AssetInfoMismatch {})
+        }
+      }
+
+      // reserve price stays in the asset of the start price
+      if let Some(reserve_price) = self.reserve_prices.may_load(deps.storage, key.clone())? {
+        if reserve_price.info != auction_info.highest_bid.info {
+          return Err(ContractError::AssetInfoMismatch {})
+        }
+
+        if let Some(fixed_price) = order.price.clone() {
+          if reserve_price.amount > fixed_price.amount {
+            return Err(ContractError::InvalidReservePrice {})
+          }
+        }
+      }
+
+      if let Some(expiration) = expiration {
+        self.assert_auction_expiration(deps.as_ref(), &env, &expiration)?;
+        auction_info.expiration = expiration;
+      }
+
+      order.auction_info = Some(auction_info);
+    } else {
+      if fixed_price.is_some() {
+        return Err(ContractError::NotAuction {})
+      }
+
+      if let Some(price) = price {
+        for item in order.items().iter() {
+          self.assert_support_asset(deps.as_ref(), &item.nft_address, &price.info)?;
+        }
+
+        let mut prices = vec![price.clone()];
+        prices.extend(order.other_prices.clone());
+        self.assert_unique_prices(&prices)?;
+
+        order.price = Some(price);
+      }
+
+      if let Some(expiration) = expiration {
+        if expiration.is_expired(&env.block) {
+          return Err(ContractError::Expired {})
+        }
+
+        order.expiration = Some(expiration);
+      }
+    }
+
+    self.orders.save(deps.storage, key, &order)?;
+
+    Ok(Response::new()
+      .add_attribute("action", "update_order")
+      .add_attribute("sender", info.sender)
+      .add_attribute("order_id", order_id.to_string())
+    )
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  pub fn add_collection(
+    &self,
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    nft_address: String,
+    support_assets: Vec<AssetInfo>,
+    royalties: Vec<Royalty>,
+    auction_extension: Option<AuctionExtension>,
+    swap_royalty: Option<SwapRoyalty>,
+    protocol_fee_rate: Option<Decimal>,
+  ) -> Result<Response, ContractError> {
+    // only owner can execute this
+    let config = self.config.load(deps.storage)?;
+
+    if config.owner != info.sender {
+      return Err(ContractError::Unauthorized {})
+    }
+
+    if let Ok(Some(_)) = self.collections.may_load(deps.storage, nft_address.clone()) {
+      return Err(ContractError::CollectionExist {})
+    }
+
+    let mut sum_rotalty_rate = Decimal::zero();
+
+    for royalty in royalties.iter() {
+      sum_rotalty_rate = sum_rotalty_rate + royalty.royalty_rate;
+    }
+
+    if sum_rotalty_rate > Decimal::one() {
+      return Err(ContractError::InvalidRoyaltyRate {})
+    }
+
+    if sum_rotalty_rate > Decimal::one() {
+      return Err(ContractError::InvalidRoyaltyRate {})
+    }
+
+    self.assert_protocol_fee_rate(protocol_fee_rate.unwrap_or(config.protocol_fee_rate), &royalties)?;
+
+    let collection_info = CollectionInfo {
+      nft_address: deps.api.addr_validate(&nft_address)?,
+      royalties,
+      support_assets,
+      auction_extension,
+      swap_royalty: swap_royalty.unwrap_or(SwapRoyalty::Sweetener),
+      protocol_fee_rate,
+    };
+
+    self.collections.save(deps.storage, nft_address.clone(), &collection_info)?;
+
+    Ok(Response::new()
+      .add_attribute("action", "add_collection")
+      .add_attribute("sender", info.sender)
+      .add_attribute("nft_address", nft_address)
+    )
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  pub fn update_collection(
+    &self,
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    nft_address: String,
+    support_assets: Option<Vec<AssetInfo>>,
+    royalties: Option<Vec<Royalty>>,
+    auction_extension: Option<AuctionExtension>,
+    clear_auction_extension: bool,
+    swap_royalty: Option<SwapRoyalty>,
+    protocol_fee_rate: Option<Decimal>,
+  ) -> Result<Response, ContractError> {
+    // only owner can execute this
+    let config = self.config.load(deps.storage)?;
+
+    if config.owner != info.sender {
+      return Err(ContractError::Unauthorized {})
+    }
+
+    let mut collection = self.collections.load(deps.storage, nft_address.clone())?;
+
+    if let Some(support_assets) = support_assets {
+      collection.support_assets = support_assets;
+    }
+
+    if let Some(royalties) = royalties {
+      let mut sum_rotalty_rate = Decimal::zero();
+
+      for royalty in royalties.iter() {
+        sum_rotalty_rate = sum_rotalty_rate + royalty.royalty_rate;
+      }
+
+      if sum_rotalty_rate > Decimal::one() {
+        return Err(ContractError::InvalidRoyaltyRate {})
+      }
+
+      collection.royalties = royalties;
+    }
+
+    if clear_auction_extension {
+      collection.auction_extension = None;
+    } else if let Some(auction_extension) = auction_extension {
+      collection.auction_extension = Some(auction_extension);
+    }
+
+    if let Some(swap_royalty) = swap_royalty {
+      collection.swap_royalty = swap_royalty;
+    }
+
+    if let Some(protocol_fee_rate) = protocol_fee_rate {
+      collection.protocol_fee_rate = Some(protocol_fee_rate);
+    }
+
+    self.assert_protocol_fee_rate(
+      collection.protocol_fee_rate.unwrap_or(config.protocol_fee_rate),
+      &collection.royalties
+    )?;
+
+    self.collections.save(deps.storage, nft_address.clone(), &collection)?;
+
+    Ok(Response::new()
+      .add_attribute("action", "update_collection")
+      .add_attribute("sender", info.sender)
+      .add_attribute("nft_address", nft_address)
+    )
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  pub fn bid(
+    &self,
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    sender: Addr,
+    order_id: u64,
+    bid_price: Asset,
+    referrer: Option<String>,
+  ) -> Result<Response, ContractError> {
+    let referrer = referrer.map(|referrer| deps.api.addr_validate(&referrer)).transpose()?;
+    let mut order = self.orders.load(deps.storage, U64Key::new(order_id))?;
+
+    let auction_info = order.auction_info.clone();
+
+    if let Some(auction_info) = auction_info {
+      if !order.is_allowed_buyer(&sender) {
+        return Err(ContractError::NotAllowedBuyer {})
+      }
+
+      // native sent balance check
+      bid_price.assert_sent_native_token_balance(&info)?;
+
+      let config = self.config.load(deps.storage)?;
+
+      if bid_price.info != auction_info.highest_bid.info {
+        return Err(ContractError::AssetInfoMismatch {})
+      }
+
+      if auction_info.expiration.is_expired(&env.block) {
+        return Err(ContractError::Expired {})
+      }
+
+      let min_bid_amount = auction_info.highest_bid.amount * (config.min_increase + Decimal::one());
+
+      if min_bid_amount > bid_price.amount {
+        return Err(ContractError::MinPrice { min_bid_amount })
+      }
+
+      let mut messages: Vec<CosmosMsg> = vec![];
+
+      let bidder = auction_info.clone().bidder;
+
+      // refund former bid
+      if let Some(bidder) = bidder {
+        messages.push(auction_info.clone().highest_bid.into_msg(&deps.querier, bidder.clone())?);
+
+        if bidder != sender {
+          self.outbids.save(deps.storage, (bidder.clone(), U64Key::new(order_id)), &Outbid {
+            order_id,
+            bidder,
+            bid: auction_info.highest_bid.clone(),
+            highest_bid: bid_price.clone(),
+            height: env.block.height,
+            time: env.block.time.seconds()
+          })?;
+        }
+      }
+
+      // sender is the highest bidder again
+      self.outbids.remove(deps.storage, (sender.clone(), U64Key::new(order_id)))?;
+
+      // update highest bid
+      let mut auction_info = auction_info;
+      auction_info.runner_up_bid = Some(auction_info.highest_bid.clone());
+      auction_info.highest_bid = bid_price.clone();
+      auction_info.bidder = Some(sender.clone());
+      auction_info.referrer = referrer;
+
+      // soft close. collection setting overrides config
+      let collection_info = self.collections.load(deps.storage, order.nft_address.to_string())?;
+      let auction_extension = collection_info.auction_extension.unwrap_or(AuctionExtension {
+        block: config.auction_extension_block,
+        second: config.auction_extension_second,
+      });
+
+      auction_info.expiration = self.extend_expiration(&env, auction_info.expiration, &auction_extension);
+
+      order.auction_info = Some(auction_info);
+
+      self.orders.save(deps.storage, U64Key::new(order_id), &order)?;
+
+      Ok(Response::new().add_messages(messages)
+      .add_attribute("action", "bid")
+      .add_attribute("sender", sender.to_string())
+      .add_attribute("order_id", order_id.to_string())
+      .add_attribute("bidder", sender.to_string())
+      .add_attribute("bid_price", format!("{}", bid_price))
+      .add_attribute("expiration", format!("{}", order.auction_info.unwrap().expiration))
+    )
+    } else {
+      Err(ContractError::NotAuction {})
+    }
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  pub fn make_offer(
+    &self,
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    sender: Addr,
+    nft_address: String,
+    // if None, collection offer
+    token_id: Option<String>,
+    price: Asset,
+    expiration: Expiration,
+    // for cw20
+    asset: Option<Asset>,
+  ) -> Result<Response, ContractError> {
+    self.assert_escrowed(&info, &price, asset)?;
+
+    if expiration.is_expired(&env.block) {
+      return Err(ContractError::Expired {})
+    }
+
+    let nft_address = deps.api.addr_validate(&nft_address)?;
+
+    self.assert_support_asset(deps.as_ref(), &nft_address, &price.info)?;
+
+    let id = self.offer_index.load(deps.storage)?;
+
+    let offer = Offer {
+      id,
+      offerer: sender.clone(),
+      nft_address: nft_address.clone(),
+      token_id: token_id.clone(),
+      price: price.clone(),
+      expiration
+    };
+
+    self.offers.save(deps.storage, U64Key::new(id), &offer)?;
+    self.offer_index.save(deps.storage, &(id + 1))?;
+
+    Ok(Response::new()
+      .add_attribute("action", if token_id.is_some() { "make_offer" } else { "make_collection_offer" })
+      .add_attribute("sender", sender)
+      .add_attribute("offer_id", id.to_string())
+      .add_attribute("nft_address", nft_address)
+      .add_attribute("token_id", token_id.unwrap_or_else(|| "null".to_string()))
+      .add_attribute("price", format!("{}", price))
+      .add_attribute("expiration", format!("{}", expiration))
+    )
+  }
+
+  pub fn cancel_offer(
+    &self,
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    offer_id: u64
+  ) -> Result<Response, ContractError> {
+    let key = U64Key::new(offer_id);
+    let offer = self.offers.load(deps.storage, key.clone())?;
+
+    // only offerer can execute
+    if offer.offerer != info.sender {
+      return Err(ContractError::Unauthorized {})
+    }
+
+    // refund escrowed price
+    let message = offer.price.clone().into_msg(&deps.querier, offer.offerer.clone())?;
+
+    self.offers.remove(deps.storage, key)?;
+
+    Ok(Response::new().add_message(message)
+      .add_attribute("action", "cancel_offer")
+      .add_attribute("sender", info.sender)
+      .add_attribute("offer_id", offer_id.to_string())
+    )
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  pub fn accept_offer(
+    &self,
+    deps: DepsMut,
+    env: Env,
+    nft_address: Addr,
+    seller: Addr,
+    token_id: String,
+    offer_id: u64,
+    is_collection_offer: bool,
+  ) -> Result<Response, ContractError> {
+    let key = U64Key::new(offer_id);
+    let offer = self.offers.load(deps.storage, key.clone())?;
+
+    if offer.nft_address != nft_address {
+      return Err(ContractError::CollectionMismatch {})
+    }
+
+    match offer.token_id.clone() {
+      Some(offer_token_id) => {
+        if is_collection_offer || offer_token_id != token_id {
+          return Err(ContractError::TokenIdMismatch {})
+        }
+      }
+      None => {
+        if !is_collection_offer {
+          return Err(ContractError::TokenIdMismatch {})
+        }
+      }
+    }
+
+    if offer.expiration.is_expired(&env.block) {
+      return Err(ContractError::Expired {})
+    }
+
+    // transfer nft to offerer
+    let mut messages: Vec<CosmosMsg> = vec![CosmosMsg::Wasm(WasmMsg::Execute {
+      contract_addr: nft_address.to_string(),
+      msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+        recipient: offer.offerer.to_string(),
+        token_id: token_id.clone()
+      })?,
+      funds: vec![]
+    })];
+
+    // pay royalties and seller from escrowed price
+    let (payout_messages, remain_amount, protocol_fee) = self.payout(
+      deps.as_ref(), vec![nft_address.clone()], seller.clone(), offer.offerer.clone(), offer.price.clone(), false, None
+    )?;
+    messages.extend(payout_messages);
+
+    let royalty_amount = offer.price.amount - remain_amount - protocol_fee;
+    let item = BundleItem { nft_address: nft_address.clone(), token_id: token_id.clone() };
+    self.save_trade(
+      deps.storage, &env, SaleType::Offer, None, Some(offer_id), vec![item], None, seller.clone(), offer.offerer.clone(), Some(offer.price.clone()),
+      royalty_amount, protocol_fee
+    )?;
+
+    self.offers.remove(deps.storage, key)?;
+
+    Ok(Response::new().add_messages(messages)
+      .add_attribute("action", if is_collection_offer { "accept_collection_offer" } else { "accept_offer" })
+      .add_attribute("sender", seller.to_string())
+      .add_attribute("offer_id", offer_id.to_string())
+      .add_attribute("nft_address", nft_address)
+      .add_attribute("token_id", token_id)
+      .add_attribute("buyer", offer.offerer)
+      .add_attribute("seller", seller)
+      .add_attribute("price", format!("{}", offer.price))
+      .add_attribute("royalty_amount", royalty_amount)
+      .add_attribute("protocol_fee", protocol_fee)
+    )
+  }
+}
+
+// helper
+impl<'a> MarketContract <'a> {
+  fn execute_sealed_bid_auction(
+    &self,
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    order: Order,
+    sealed_bid_auction_info: SealedBidAuctionInfo
+  ) -> Result<Response, ContractError> {
+    if !sealed_bid_auction_info.reveal_end.is_expired(&env.block) {
+      return Err(ContractError::NotExpired {})
+    }
+
+    let mut refund_messages: Vec<CosmosMsg> = vec![];
+
+    let clearing_price = match sealed_bid_auction_info.highest_bid.clone() {
+      Some(highest_bid) if sealed_bid_auction_info.second_price => Some(self.clearing_price(
+        deps.as_ref(),
+        &highest_bid,
+        sealed_bid_auction_info.runner_up_bid.clone(),
+        sealed_bid_auction_info.min_price.amount
+      )?),
+      highest_bid => highest_bid
+    };
+
+    // winner gets back the rest of the deposit
+    if let (Some(bidder), Some(clearing_price)) = (sealed_bid_auction_info.bidder.clone(), clearing_price.clone()) {
+      let key = (U64Key::new(order.id), bidder.clone());
+      let sealed_bid = self.sealed_bids.load(deps.storage, key.clone())?;
+
+      self.sealed_bids.remove(deps.storage, key);
+
+      let refund_amount = sealed_bid.deposit.amount.checked_sub(clearing_price.amount)?;
+
+      if !refund_amount.is_zero() {
+        refund_messages.push(
+          (Asset {
+            info: sealed_bid.deposit.info,
+            amount: refund_amount
+          }).into_msg(&deps.querier, bidder)?
+        );
+      }
+    }
+
+    // other deposits are withdrawn one by one
+    let deposit_left = self.sealed_bids
+      .prefix(U64Key::new(order.id))
+      .keys(deps.storage, None, None, Ascending)
+      .next()
+      .is_some();
+
+    if deposit_left {
+      self.sealed_bid_settlements.save(deps.storage, U64Key::new(order.id), &SealedBidSettlement {
+        seller: order.seller_address.clone(),
+        unrevealed_policy: sealed_bid_auction_info.unrevealed_policy.clone()
+      })?;
+    }
+
+    let (mut messages, remain_amount, protocol_fee, buyer, price): (Vec<CosmosMsg>, Uint128, Uint128, String, Asset);
+
+    if let (Some(bidder), Some(clearing_price)) = (sealed_bid_auction_info.bidder, clearing_price) {
+      let (messages_, remain_amount_, protocol_fee_) = self.execute_order_(
+        deps, &env, order.clone(), bidder.clone(), clearing_price.clone(), true, None
+      )?;
+      messages = messages_;
+      remain_amount = remain_amount_;
+      protocol_fee = protocol_fee_;
+      buyer = bidder.to_string();
+      price = clearing_price;
+      // no valid reveal
+    } else {
+      // return nft to seller
+      messages = self.transfer_nfts(&order.items(), &order.seller_address)?;
+
+      price = Asset {
+        info: sealed_bid_auction_info.min_price.info,
+        amount: Uint128::zero()
+      };
+      remain_amount = Uint128::zero();
+      protocol_fee = Uint128::zero();
+      buyer = "null".to_string();
+
+      // remove order
+      self.orders.remove(deps.storage, U64Key::new(order.id))?;
+    }
+
+    messages.extend(refund_messages);
+
+    Ok(Response::new().add_messages(messages)
+      .add_attribute("action", "execute_auction")
+      .add_attribute("sender", info.sender.to_string())
+      .add_attribute("order_id", order.id.to_string())
+      .add_attribute("buyer", buyer)
+      .add_attribute("seller", order.seller_address)
+      .add_attribute("price", format!("{}", price))
+      .add_attribute("royalty_amount", price.amount - remain_amount - protocol_fee)
+      .add_attribute("protocol_fee", protocol_fee)
+    )
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  fn execute_order_(
+    &self,
+    deps: DepsMut,
+    env: &Env,
+    order: Order,
+    buyer: Addr,
+    price: Asset,
+    is_auction_execute: bool,
+    referrer: Option<Addr>,
+  ) -> Result<(Vec<CosmosMsg>, Uint128, Uint128), ContractError> {
+    let mut messages: Vec<CosmosMsg> = vec![];
+
+    // refund asset to last bidder
+    let auction_info = order.auction_info.clone();
+
+    if let Some(auction_info) = auction_info {
+      if !is_auction_execute {
+        let bid_price = auction_info.highest_bid;
+        let bidder = auction_info.bidder;
+        
+        if let Some(bidder) = bidder {
+          messages.push(bid_price.into_msg(&deps.querier, bidder)?)
+        }
+      }
+    }
+
+    let items = order.items();
+
+    // transfer nfts to buyer
+    messages.extend(self.transfer_nfts(&items, &buyer)?);
+
+    // transfer royalty and remain amount to seller
+    let nft_addresses = items.iter().map(|item| item.nft_address.clone()).collect();
+    let (payout_messages, remain_amount, protocol_fee) = self.payout(
+      deps.as_ref(), nft_addresses, order.seller_address.clone(), buyer.clone(), price.clone(), false, referrer
+    )?;
+    messages.extend(payout_messages);
+
+    let sale_type = if order.sealed_bid_auction_info.is_some() {
+      SaleType::SealedBidAuction
+    } else if is_auction_execute {
+      SaleType::Auction
+    } else if order.dutch_auction_info.is_some() {
+      SaleType::DutchAuction
+    } else {
+      SaleType::FixedPrice
+    };
+
+    let royalty_amount = price.amount - remain_amount - protocol_fee;
+    self.save_trade(
+      deps.storage, env, sale_type, Some(order.id), None, items, None, order.seller_address.clone(), buyer, Some(price), royalty_amount, protocol_fee
+    )?;
+
+    // remove order
+    self.orders.remove(deps.storage, U64Key::new(order.id))?;
+    self.reserve_prices.remove(deps.storage, U64Key::new(order.id));
+    self.remove_outbids(deps.storage, order.id)?;
+
+    Ok((messages, remain_amount, protocol_fee))
+  }
+
+  // price to buy the whole order with the asset
+  fn sweep_price(
+    &self,
+    env: &Env,
+    buyer: &Addr,
+    order: &Order,
+    asset_info: &AssetInfo
+  ) -> Result<Asset, ContractError> {
+    if order.is_expired(&env.block) {
+      return Err(ContractError::Expired {})
+    }
+
+    if !order.is_allowed_buyer(buyer) {
+      return Err(ContractError::NotAllowedBuyer {})
+    }
+
+    let prices = order.prices(&env.block);
+
+    if prices.is_empty() {
+      return Err(ContractError::NoFixedPrice {})
+    }
+
+    let price = match prices.into_iter().find(|price| &price.info == asset_info) {
+      Some(price) => price,
+      None => return Err(ContractError::TokenMismatch {})
+    };
+
+    let units = order.cw1155_info.clone().map(|cw1155_info| cw1155_info.remaining).unwrap_or_else(|| Uint128::from(1u128));
+
+    Ok(Asset {
+      amount: price.amount.checked_mul(units)?,
+      info: price.info
+    })
+  }
+
+  // partial fill of cw1155 order. order is removed when all units are sold
+  #[allow(clippy::too_many_arguments)]
+  fn execute_cw1155_order_(
+    &self,
+    deps: DepsMut,
+    env: &Env,
+    mut order: Order,
+    buyer: Addr,
+    units: Uint128,
+    price: Asset,
+    referrer: Option<Addr>,
+  ) -> Result<(Vec<CosmosMsg>, Uint128, Uint128), ContractError> {
+    let mut messages = vec![self.transfer_cw1155(env, &order, units, &buyer)?];
+
+    let (payout_messages, remain_amount, protocol_fee) = self.payout(
+      deps.as_ref(), vec![order.nft_address.clone()], order.seller_address.clone(), buyer.clone(), price.clone(), false, referrer
+    )?;
+    messages.extend(payout_messages);
+
+    let royalty_amount = price.amount - remain_amount - protocol_fee;
+    self.save_trade(
+      deps.storage, env, SaleType::FixedPrice, Some(order.id), None, order.items(), Some(units), order.seller_address.clone(), buyer, Some(price),
+      royalty_amount, protocol_fee
+    )?;
+
+    let mut cw1155_info = order.cw1155_info.clone().unwrap();
+    cw1155_info.remaining = cw1155_info.remaining.checked_sub(units)?;
+
+    if cw1155_info.remaining.is_zero() {
+      self.orders.remove(deps.storage, U64Key::new(order.id))?;
+    } else {
+      order.cw1155_info = Some(cw1155_info);
+      self.orders.save(deps.storage, U64Key::new(order.id), &order)?;
+    }
+
+    Ok((messages, remain_amount, protocol_fee))
+  }
+
+  // append record of completed sale
+  #[allow(clippy::too_many_arguments)]
+  fn save_trade(
+    &self,
+    storage: &mut dyn Storage,
+    env: &Env,
+    sale_type: SaleType,
+    order_id: Option<u64>,
+    offer_id: Option<u64>,
+    items: Vec<BundleItem>,
+    amount: Option<Uint128>,
+    seller: Addr,
+    buyer: Addr,
+    price: Option<Asset>,
+    royalty_amount: Uint128,
+    protocol_fee: Uint128,
+  ) -> StdResult<()> {
+    let id = self.trade_index.load(storage)?;
+
+    let trade = Trade {
+      id,
+      sale_type,
+      order_id,
+      offer_id,
+      nft_address: items[0].nft_address.clone(),
+      token_id: items[0].token_id.clone(),
+      bundle: items[1..].to_vec(),
+      amount,
+      seller,
+      buyer,
+      price,
+      royalty_amount,
+      protocol_fee,
+      height: env.block.height,
+      time: env.block.time.seconds()
+    };
+
+    self.trades.save(storage, U64Key::new(id), &trade)?;
+    self.trade_index.save(storage, &(id + 1))?;
+
+    // swaps are not sales
+    if trade.sale_type == SaleType::Swap {
+      return Ok(())
+    }
+
+    // update sale aggregates
+    if let Some(price) = trade.price.clone() {
+      let items = trade.items();
+
+      // price of a nft. bundle price is divided by the number of nfts, cw1155 price by the units
+      let units = trade.amount.unwrap_or_else(|| Uint128::from(items.len() as u128));
+      let unit_price = price.amount / units.max(Uint128::from(1u128));
+
+      let update_stats = |stats: Option<TradeStats>, volume: Uint128| -> StdResult<TradeStats> {
+        let mut stats = stats.unwrap_or(TradeStats {
+          asset: price.info.clone(),
+          volume: Uint128::zero(),
+          sales_count: 0,
+          all_time_high: Uint128::zero()
+        });
+
+        stats.volume = stats.volume.checked_add(volume)?;
+        stats.sales_count += 1;
+        stats.all_time_high = stats.all_time_high.max(unit_price);
+
+        Ok(stats)
+      };
+
+      // volume of bundle is split pro rata to the number of nfts of each collection
+      let nft_addresses: Vec<Addr> = items.into_iter().map(|item| item.nft_address).collect();
+
+      for (nft_address, count) in count_by_collection(&nft_addresses) {
+        let volume = price.amount.multiply_ratio(count, nft_addresses.len() as u128);
+
+        self.collection_stats.update(storage, (nft_address, price.info.to_string()), |stats| update_stats(stats, volume))?;
+      }
+
+      self.market_stats.update(storage, price.info.to_string(), |stats| update_stats(stats, price.amount))?;
+    }
+
+    Ok(())
+  }
+
+  // royalties of each collection are paid pro rata to the number of its nfts in the order
+  #[allow(clippy::too_many_arguments)]
+  fn payout(
+    &self,
+    deps: Deps,
+    nft_addresses: Vec<Addr>,
+    seller: Addr,
+    buyer: Addr,
+    price: Asset,
+    is_swap: bool,
+    referrer: Option<Addr>,
+  ) -> Result<(Vec<CosmosMsg>, Uint128, Uint128), ContractError> {
+    let config = self.config.load(deps.storage)?;
+    let mut messages: Vec<CosmosMsg> = vec![];
+
+    let collections = count_by_collection(&nft_addresses);
+
+    let mut remain_amount = price.amount;
+    let mut protocol_fee = Uint128::zero();
+
+    for (nft_address, count) in collections.iter() {
+      // get royalty
+      let collection_info = self.collections.load(deps.storage, nft_address.to_string())?;
+
+      let share_amount = price.amount.multiply_ratio(*count, nft_addresses.len() as u128);
+
+      // protocol fee is taken from every sale. collection setting overrides config
+      protocol_fee += share_amount * collection_info.protocol_fee_rate.unwrap_or(config.protocol_fee_rate);
+
+      if is_swap && collection_info.swap_royalty == SwapRoyalty::Exempt {
+        continue
+      }
+
+      // transfer royalty
+      for royalty in collection_info.royalties.iter() {
+        messages.push(
+          (Asset {
+            info: price.info.clone(),
+            amount: share_amount * royalty.royalty_rate
+          }).into_msg(&deps.querier, royalty.address.clone())?
+        );
+
+        remain_amount = remain_amount.checked_sub(share_amount * royalty.royalty_rate)?;
+      }
+    }
+
+    // discount by governance token balance. the better tier of seller and buyer is used.
+    // failed balance query gives no discount, so the sale is not blocked by the token contract
+    if let Some(fee_discount) = config.fee_discount.clone().filter(|_| !protocol_fee.is_zero()) {
+      let discount_rate = std::cmp::max(
+        fee_discount.discount_rate(&deps.querier, &seller).unwrap_or_default(),
+        fee_discount.discount_rate(&deps.querier, &buyer).unwrap_or_default()
+      );
+
+      protocol_fee -= protocol_fee * discount_rate;
+    }
+
+    let mut treasury_amount = protocol_fee;
+
+    // transfer share of protocol fee to referrer
+    if let Some(referrer) = referrer {
+      let referral_fee = protocol_fee * config.referral_share;
+
+      if !referral_fee.is_zero() {
+        messages.push(
+          (Asset {
+            info: price.info.clone(),
+            amount: referral_fee
+          }).into_msg(&deps.querier, referrer)?
+        );
+
+        treasury_amount = treasury_amount.checked_sub(referral_fee)?;
+      }
+    }
+
+    // transfer protocol fee to treasury
+    if !treasury_amount.is_zero() {
+      messages.push(
+        (Asset {
+          info: price.info.clone(),
+          amount: treasury_amount
+        }).into_msg(&deps.querier, config.treasury)?
+      );
+    }
+
+    remain_amount = remain_amount.checked_sub(protocol_fee)?;
+
+    // transfer remain amount to seller
+    messages.push(
+      (Asset {
+        info: price.info,
+        amount: remain_amount
+      }).into_msg(&deps.querier, seller)?
+    );
+
+    Ok((messages, remain_amount, protocol_fee))
+  }
+
+  fn transfer_nfts(
+    &self,
+    items: &[BundleItem],
+    recipient: &Addr
+  ) -> Result<Vec<CosmosMsg>, ContractError> {
     let mut messages: Vec<CosmosMsg> = vec![];
 
-    // if auction refund latest bid first
-    let auction_info = order.clone().auction_info;
-    if let Some(auction_info) = auction_info {
-      // can not cancel expired auction
-      if auction_info.expiration.is_expired(&env.block) {
-        return Err(ContractError::Expired {})
-      }
-      messages = self.refund_bid(deps.as_ref(), info.clone(), order.clone(), cancel_fee)?;
+    for item in items.iter() {
+      messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: item.nft_address.to_string(),
+        msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+          recipient: recipient.to_string(),
+          token_id: item.token_id.clone()
+        })?,
+        funds: vec![]
+      }));
     }
 
-    // return nft to seller
-    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+    Ok(messages)
+  }
+
+  fn transfer_cw1155(
+    &self,
+    env: &Env,
+    order: &Order,
+    amount: Uint128,
+    recipient: &Addr
+  ) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
       contract_addr: order.nft_address.to_string(),
-      msg: to_binary(&Cw721ExecuteMsg::TransferNft {
-        recipient: order.seller_address.to_string(),
-        token_id: order.token_id
+      msg: to_binary(&Cw1155ExecuteMsg::SendFrom {
+        from: env.contract.address.to_string(),
+        to: recipient.to_string(),
+        token_id: order.token_id.clone(),
+        value: amount,
+        msg: None
       })?,
       funds: vec![]
-    }));
+    }))
+  }
 
-    // remove order
-    self.orders.remove(deps.storage, key)?;
+  // return unsold nfts of the order to the seller
+  fn return_nfts(
+    &self,
+    env: &Env,
+    order: &Order
+  ) -> Result<Vec<CosmosMsg>, ContractError> {
+    match &order.cw1155_info {
+      Some(cw1155_info) => Ok(vec![self.transfer_cw1155(env, order, cw1155_info.remaining, &order.seller_address)?]),
+      None => self.transfer_nfts(&order.items(), &order.seller_address)
+    }
+  }
 
-    Ok(Response::new().add_messages(messages)
-      .add_attribute("action", "cancel_order")
-      .add_attribute("sender", sender)
-      .add_attribute("order_id", order_id.to_string())
-    )
+  fn validate_buyers(
+    &self,
+    deps: Deps,
+    buyers: Option<Vec<String>>
+  ) -> StdResult<Vec<Addr>> {
+    buyers.unwrap_or_default().iter().map(|buyer| deps.api.addr_validate(buyer)).collect()
   }
 
-  pub fn add_collection(
+  // load and clear pending bundle of the seller
+  fn take_bundle(
     &self,
-    deps: DepsMut,
-    _env: Env,
-    info: MessageInfo,
-    nft_address: String,
-    support_assets: Vec<AssetInfo>,
-    royalties: Vec<Royalty>,
-  ) -> Result<Response, ContractError> {
-    // only owner can execute this
-    let config = self.config.load(deps.storage)?;
+    storage: &mut dyn Storage,
+    seller: &Addr
+  ) -> Result<Vec<BundleItem>, ContractError> {
+    let items = self.bundles.may_load(storage, seller.to_string())?.unwrap_or_default();
 
-    if config.owner != info.sender {
-      return Err(ContractError::Unauthorized {})
+    if items.is_empty() {
+      return Err(ContractError::EmptyBundle {})
     }
 
-    if let Ok(Some(_)) = self.collections.may_load(deps.storage, nft_address.clone()) {
-      return Err(ContractError::CollectionExist {})
-    }
+    self.bundles.remove(storage, seller.to_string());
 
-    let mut sum_rotalty_rate = Decimal::zero();
+    Ok(items)
+  }
 
-    for royalty in royalties.iter() {
-      sum_rotalty_rate = sum_rotalty_rate + royalty.royalty_rate;
-    }
+  // pending bundle of the counterparty must be a single nft
+  fn take_swap_item(
+    &self,
+    storage: &mut dyn Storage,
+    sender: &Addr
+  ) -> Result<BundleItem, ContractError> {
+    let mut items = self.take_bundle(storage, sender)?;
 
-    if sum_rotalty_rate > Decimal::one() {
-      return Err(ContractError::InvalidRoyaltyRate {})
+    if items.len() != 1 {
+      return Err(ContractError::InvalidSwapBundle {})
     }
 
-    if sum_rotalty_rate > Decimal::one() {
-      return Err(ContractError::InvalidRoyaltyRate {})
+    Ok(items.remove(0))
+  }
+
+  // return escrowed sweetener of seller
+  fn refund_sweetener(
+    &self,
+    deps: Deps,
+    order: &Order
+  ) -> StdResult<Vec<CosmosMsg>> {
+    if let Some(SwapInfo { sweetener: Some(sweetener), .. }) = order.swap_info.clone() {
+      if sweetener.payer == SweetenerPayer::Seller {
+        return Ok(vec![sweetener.asset.into_msg(&deps.querier, order.seller_address.clone())?])
+      }
     }
 
-    let collection_info = CollectionInfo {
-      nft_address: deps.api.addr_validate(&nft_address)?,
-      royalties,
-      support_assets,
-    };
+    Ok(vec![])
+  }
 
-    self.collections.save(deps.storage, nft_address.clone(), &collection_info)?;
+  fn assert_support_asset(
+    &self,
+    deps: Deps,
+    nft_address: &Addr,
+    asset_info: &AssetInfo
+  ) -> Result<(), ContractError> {
+    let collection_info = self.collections.load(deps.storage, nft_address.to_string())?;
 
-    Ok(Response::new()
-      .add_attribute("action", "add_collection")
-      .add_attribute("sender", info.sender)
-      .add_attribute("nft_address", nft_address)
-    )
+    if !collection_info.support_assets.contains(asset_info) {
+      return Err(ContractError::Unsupport {})
+    }
+
+    Ok(())
   }
 
-  pub fn update_collection(
+  fn assert_auction_expiration(
     &self,
-    deps: DepsMut,
-    _env: Env,
-    info: MessageInfo,
-    nft_address: String,
-    support_assets: Option<Vec<AssetInfo>>,
-    royalties: Option<Vec<Royalty>>,
-  ) -> Result<Response, ContractError> {
-    // only owner can execute this
+    deps: Deps,
+    env: &Env,
+    expiration: &Expiration
+  ) -> Result<(), ContractError> {
     let config = self.config.load(deps.storage)?;
 
-    if config.owner != info.sender {
-      return Err(ContractError::Unauthorized {})
-    }
+    match expiration {
+      Expiration::Never {} => {
+        return Err(ContractError::Never {})
+      }
+      Expiration::AtHeight(height) => {
+        if expiration.is_expired(&env.block) {
+          return Err(ContractError::Expired {})
+        }
 
-    let mut collection = self.collections.load(deps.storage, nft_address.clone())?;
+        if (height - env.block.height) > config.max_auction_duration_block {
+          return Err(ContractError::MaxDuration {})
+        }
+      }
+      Expiration::AtTime(timestamp) => {
+        if expiration.is_expired(&env.block) {
+          return Err(ContractError::Expired {})
+        }
 
-    if let Some(support_assets) = support_assets {
-      collection.support_assets = support_assets;
+        if (timestamp.seconds() - env.block.time.seconds()) > config.max_auction_duration_second {
+          return Err(ContractError::MaxDuration {})
+        }
+      }
     }
 
-    if let Some(royalties) = royalties {
-      let mut sum_rotalty_rate = Decimal::zero();
+    Ok(())
+  }
 
-      for royalty in royalties.iter() {
-        sum_rotalty_rate = sum_rotalty_rate + royalty.royalty_rate;
-      }
+  // true if there is no reserve price or the highest bid reaches it
+  pub fn reserve_met(
+    &self,
+    deps: Deps,
+    order_id: u64
+  ) -> StdResult<bool> {
+    let order = self.orders.load(deps.storage, U64Key::new(order_id))?;
 
-      if sum_rotalty_rate > Decimal::one() {
-        return Err(ContractError::InvalidRoyaltyRate {})
-      }
+    let auction_info = match order.auction_info {
+      Some(auction_info) => auction_info,
+      None => return Err(StdError::generic_err("Order is not an auction"))
+    };
 
-      collection.royalties = royalties;
+    match self.reserve_prices.may_load(deps.storage, U64Key::new(order_id))? {
+      Some(reserve_price) => Ok(
+        auction_info.bidder.is_some()
+          && auction_info.highest_bid.info == reserve_price.info
+          && auction_info.highest_bid.amount >= reserve_price.amount
+      ),
+      None => Ok(true)
     }
+  }
 
-    self.collections.save(deps.storage, nft_address.clone(), &collection)?;
+  // if expiration is within the extension window, extend it to the window from now
+  fn extend_expiration(
+    &self,
+    env: &Env,
+    expiration: Expiration,
+    auction_extension: &AuctionExtension
+  ) -> Expiration {
+    match expiration {
+      Expiration::AtHeight(height) => {
+        if height - env.block.height < auction_extension.block {
+          return Expiration::AtHeight(env.block.height + auction_extension.block)
+        }
+      }
+      Expiration::AtTime(timestamp) => {
+        if timestamp.seconds() - env.block.time.seconds() < auction_extension.second {
+          return Expiration::AtTime(env.block.time.plus_seconds(auction_extension.second))
+        }
+      }
+      Expiration::Never {} => {}
+    }
 
-    Ok(Response::new()
-      .add_attribute("action", "update_collection")
-      .add_attribute("sender", info.sender)
-      .add_attribute("nft_address", nft_address)
-    )
+    expiration
   }
 
-  pub fn bid(
+  // winner of second price auction pays runner-up bid + min increase (at least floor_amount), but not more than own bid
+  fn clearing_price(
     &self,
-    deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    sender: Addr,
-    order_id: u64,
-    bid_price: Asset,
-  ) -> Result<Response, ContractError> {
-    let mut order = self.orders.load(deps.storage, U64Key::new(order_id))?;
+    deps: Deps,
+    highest_bid: &Asset,
+    runner_up_bid: Option<Asset>,
+    floor_amount: Uint128
+  ) -> StdResult<Asset> {
+    let config = self.config.load(deps.storage)?;
 
-    let auction_info = order.auction_info;
+    let amount = match runner_up_bid {
+      Some(runner_up_bid) => runner_up_bid.amount * (config.min_increase + Decimal::one()),
+      None => Uint128::zero()
+    };
 
-    if let Some(auction_info) = auction_info {
-      // native sent balance check
-      bid_price.assert_sent_native_token_balance(&info)?;
+    Ok(Asset {
+      info: highest_bid.info.clone(),
+      amount: amount.max(floor_amount).min(highest_bid.amount)
+    })
+  }
 
-      let config = self.config.load(deps.storage)?;
+  fn refund_difference(
+    &self,
+    deps: Deps,
+    paid: &Asset,
+    price: &Asset,
+    recipient: &Addr
+  ) -> StdResult<Vec<CosmosMsg>> {
+    let mut messages: Vec<CosmosMsg> = vec![];
 
-      if bid_price.info != auction_info.highest_bid.info {
-        return Err(ContractError::AssetInfoMismatch {})
-      }
+    if paid.amount > price.amount {
+      messages.push(
+        (Asset {
+          info: paid.info.clone(),
+          amount: paid.amount - price.amount
+        }).into_msg(&deps.querier, recipient.clone())?
+      );
+    }
 
-      if auction_info.expiration.is_expired(&env.block) {
-        return Err(ContractError::Expired {})
+    Ok(messages)
+  }
+
+  // start and end must be the same type and start must be earlier
+  fn assert_period(
+    &self,
+    start: &Expiration,
+    end: &Expiration
+  ) -> Result<(), ContractError> {
+    match (start, end) {
+      (Expiration::AtHeight(start), Expiration::AtHeight(end)) => {
+        if start >= end {
+          return Err(ContractError::InvalidPeriod {})
+        }
+      }
+      (Expiration::AtTime(start), Expiration::AtTime(end)) => {
+        if start >= end {
+          return Err(ContractError::InvalidPeriod {})
+        }
       }
+      (Expiration::Never {}, _) | (_, Expiration::Never {}) => {
+        return Err(ContractError::Never {})
+      }
+      _ => {
+        return Err(ContractError::InvalidPeriod {})
+      }
+    }
 
-      let min_bid_amount = auction_info.highest_bid.amount * (config.min_increase + Decimal::one());
+    Ok(())
+  }
 
-      if min_bid_amount > bid_price.amount {
-        return Err(ContractError::MinPrice { min_bid_amount })
+  // each asset can be listed only once in the prices of an order
+  fn assert_unique_prices(
+    &self,
+    prices: &[Asset]
+  ) -> Result<(), ContractError> {
+    for (i, price) in prices.iter().enumerate() {
+      if prices[i + 1..].iter().any(|x| x.info == price.info) {
+        return Err(ContractError::DuplicateAsset {})
       }
+    }
 
-      let mut messages: Vec<CosmosMsg> = vec![];
+    Ok(())
+  }
 
-      let bidder = auction_info.clone().bidder;
+  // protocol fee and royalties are paid from the price, so they can't exceed it
+  fn assert_protocol_fee_rate(
+    &self,
+    protocol_fee_rate: Decimal,
+    royalties: &[Royalty]
+  ) -> Result<(), ContractError> {
+    let sum_rate = royalties.iter().fold(protocol_fee_rate, |sum, royalty| sum + royalty.royalty_rate);
 
-      // refund former bid
-      if let Some(bidder) = bidder {
-        messages.push(auction_info.clone().highest_bid.into_msg(&deps.querier, bidder)?);
+    if sum_rate > Decimal::one() {
+      return Err(ContractError::InvalidProtocolFeeRate {})
+    }
+
+    Ok(())
+  }
+
+  // find the price buyer pays with. cw20 comes with receive hook, native token comes with funds
+  fn select_price(
+    &self,
+    info: &MessageInfo,
+    prices: &[Asset],
+    asset: Option<Asset>
+  ) -> Result<Asset, ContractError> {
+    if let Some(asset) = asset {
+      return match prices.iter().find(|price| price.info == asset.info) {
+        Some(price) if *price == asset => Ok(asset),
+        _ => Err(ContractError::TokenMismatch {})
       }
+    }
 
-      // update highest bid
-      let mut auction_info = auction_info;
-      auction_info.highest_bid = bid_price.clone();
-      auction_info.bidder = Some(sender.clone());
+    let native_prices: Vec<&Asset> = prices.iter()
+      .filter(|price| matches!(price.info, AssetInfo::NativeToken { .. }))
+      .collect();
 
-      order.auction_info = Some(auction_info);
+    // prefer the denom sent with funds
+    let price = native_prices.iter()
+      .find(|price| info.funds.iter().any(|coin| price.info == AssetInfo::NativeToken { denom: coin.denom.clone() }))
+      .or_else(|| native_prices.first())
+      .ok_or(ContractError::TokenMismatch {})?;
 
-      self.orders.save(deps.storage, U64Key::new(order_id), &order)?;
+    // native sent balance check
+    price.assert_sent_native_token_balance(info)?;
 
-      Ok(Response::new().add_messages(messages)
-      .add_attribute("action", "bid")
-      .add_attribute("sender", sender.to_string())
-      .add_attribute("order_id", order_id.to_string())
-      .add_attribute("bidder", sender.to_string())
-      .add_attribute("bid_price", format!("{}", bid_price))
-    )
-    } else {
-      return Err(ContractError::NotAuction {})
-    }
+    Ok((*price).clone())
   }
-}
 
-// helper
-impl<'a> MarketContract <'a> {
-  fn execute_order_(
+  // check price is sent. cw20 comes with receive hook, native token comes with funds
+  fn assert_escrowed(
     &self,
-    deps: DepsMut,
-    order: Order,
-    buyer: Addr,
-    price: Asset,
-    is_auction_execute: bool,
-  ) -> Result<(Vec<CosmosMsg>, Uint128), ContractError> {
-    let mut messages: Vec<CosmosMsg> = vec![];
-
-    // refund asset to last bidder
-    let auction_info = order.auction_info;
-
-    if let Some(auction_info) = auction_info {
-      if !is_auction_execute {
-        let bid_price = auction_info.highest_bid;
-        let bidder = auction_info.bidder;
-        
-        if let Some(bidder) = bidder {
-          messages.push(bid_price.into_msg(&deps.querier, bidder)?)
-        }
+    info: &MessageInfo,
+    price: &Asset,
+    asset: Option<Asset>
+  ) -> Result<(), ContractError> {
+    if let Some(asset) = asset {
+      if price != &asset {
+        return Err(ContractError::TokenMismatch {})
+      }
+    } else {
+      if !price.info.is_native_token() {
+        return Err(ContractError::TokenMismatch {})
       }
+
+      price.assert_sent_native_token_balance(info)?;
     }
 
-    // transfer nft to buyer
-    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-      contract_addr: order.nft_address.to_string(),
-      msg: to_binary(&Cw721ExecuteMsg::TransferNft {
-        recipient: buyer.to_string(), 
-        token_id: order.token_id
-      })?,
-      funds: vec![]
-    }));
+    Ok(())
+  }
 
-    // get royalty
-    let collection_info = self.collections.load(deps.storage, order.nft_address.to_string())?;
+  // nothing can be sent when no payment is taken
+  fn assert_no_funds(
+    &self,
+    info: &MessageInfo,
+    asset: &Option<Asset>
+  ) -> Result<(), ContractError> {
+    if asset.is_some() || !info.funds.is_empty() {
+      return Err(ContractError::TokenMismatch {})
+    }
 
-    let mut remain_amount = price.amount;
+    Ok(())
+  }
 
-    // transfer royalty
-    for royalty in collection_info.royalties.iter() {
-      messages.push(
-        (Asset {
-          info: price.info.clone(),
-          amount: price.amount * royalty.royalty_rate
-        }).into_msg(&deps.querier, royalty.address.clone())?
-      );
+  // outbids of closed auction are not shown anymore
+  fn remove_outbids(&self, storage: &mut dyn Storage, order_id: u64) -> StdResult<()> {
+    let bidders: Vec<Addr> = self.outbids.idx.order
+      .prefix(U64Key::new(order_id))
+      .keys(storage, None, None, Ascending)
+      .map(|bidder| Addr::unchecked(String::from_utf8_lossy(&bidder)))
+      .collect();
 
-      remain_amount = remain_amount.checked_sub(price.amount * royalty.royalty_rate)?;
+    for bidder in bidders {
+      self.outbids.remove(storage, (bidder, U64Key::new(order_id)))?;
     }
 
-    // transfer remain amount to seller
-    messages.push(
-      (Asset {
-        info: price.clone().info,
-        amount: remain_amount
-      }).into_msg(&deps.querier, order.seller_address.clone())?
-    );
-
-    // remove order
-    self.orders.remove(deps.storage, U64Key::new(order.id))?;
-
-    Ok((messages, remain_amount))
+    Ok(())
   }
 
   fn refund_bid(
@@ -729,3 +2983,17 @@ impl<'a> MarketContract <'a> {
     Ok(messages)
   }
 }
+
+// count nfts per collection
+fn count_by_collection(nft_addresses: &[Addr]) -> Vec<(Addr, u128)> {
+  let mut collections: Vec<(Addr, u128)> = vec![];
+
+  for nft_address in nft_addresses.iter() {
+    match collections.iter_mut().find(|(address, _)| address == nft_address) {
+      Some((_, count)) => *count += 1,
+      None => collections.push((nft_address.clone(), 1))
+    }
+  }
+
+  collections
+}
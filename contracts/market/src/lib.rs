@@ -4,6 +4,7 @@ mod msgs;
 mod execute;
 mod error;
 mod query;
+mod cw1155;
 
 #[cfg(test)]
 mod testing;
@@ -42,8 +43,8 @@ pub mod entry {
   }
 
   #[entry_point]
-  pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+  pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     let tract = MarketContract::default();
-      tract.query(deps, msg)
+      tract.query(deps, env, msg)
   }
 }
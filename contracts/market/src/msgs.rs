@@ -1,13 +1,17 @@
 use schemars::JsonSchema;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
 use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
 
 use cw721::Cw721ReceiveMsg;
 use cw20::Cw20ReceiveMsg;
 use cw0::Expiration;
-use cosmwasm_std::{Addr, Decimal};
+use cosmwasm_std::{Addr, Binary, Decimal, StdError, Uint128};
 
 use crate::asset::{Asset, AssetInfo};
-use crate::state::Royalty;
+use crate::cw1155::{Cw1155BatchReceiveMsg, Cw1155ReceiveMsg};
+use crate::state::{AuctionExtension, FeeDiscount, FeeTier, OrderType, Royalty, TradeStats, Sweetener, SwapRoyalty, UnrevealedPolicy};
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct InstantiateMsg {
@@ -17,6 +21,12 @@ pub struct InstantiateMsg {
   pub max_auction_duration_block: u64,
   pub max_auction_duration_second: u64,
   pub auction_cancel_fee_rate: Decimal,
+  // soft close window of auction. 0 to disable
+  pub auction_extension_block: u64,
+  pub auction_extension_second: u64,
+  // fee taken from every sale
+  pub protocol_fee_rate: Decimal,
+  pub treasury: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -24,7 +34,10 @@ pub struct InstantiateMsg {
 pub enum ExecuteMsg {
   ReceiveNft(Cw721ReceiveMsg),
 
-  Receive(Cw20ReceiveMsg),
+  // cw20 and cw1155 both use `receive` hook
+  Receive(ReceiveMsg),
+
+  BatchReceive(Cw1155BatchReceiveMsg),
 
   UpdateConfig {
     owner: Option<String>,
@@ -32,12 +45,25 @@ pub enum ExecuteMsg {
     max_auction_duration_block: Option<u64>,
     max_auction_duration_second: Option<u64>,
     auction_cancel_fee_rate: Option<Decimal>,
+    auction_extension_block: Option<u64>,
+    auction_extension_second: Option<u64>,
+    protocol_fee_rate: Option<Decimal>,
+    treasury: Option<String>,
+    // empty tiers to disable
+    fee_discount: Option<FeeDiscount>,
+    referral_share: Option<Decimal>,
   },
 
   AddCollection {
     nft_address: String,
     support_assets: Vec<AssetInfo>,
     royalties: Vec<Royalty>,
+    // override auction extension of config
+    auction_extension: Option<AuctionExtension>,
+    // default: sweetener
+    swap_royalty: Option<SwapRoyalty>,
+    // override protocol fee rate of config
+    protocol_fee_rate: Option<Decimal>,
   },
 
   // if you want to delist/remove the collection, set support_asset = vec![]
@@ -46,11 +72,38 @@ pub enum ExecuteMsg {
     nft_address: String,
     support_assets: Option<Vec<AssetInfo>>,
     royalties: Option<Vec<Royalty>>,
+    auction_extension: Option<AuctionExtension>,
+    // if true, remove auction extension of the collection to use config
+    clear_auction_extension: Option<bool>,
+    swap_royalty: Option<SwapRoyalty>,
+    protocol_fee_rate: Option<Decimal>,
   },
 
   // buy nft at fixed price.
   ExecuteOrder {
-    order_id: u64
+    order_id: u64,
+    // units to buy from cw1155 order. if None, all remaining units
+    amount: Option<Uint128>,
+    // front-end to get referral fee
+    referrer: Option<String>
+  },
+
+  // buy many orders with one payment. max_total is sent and the unused amount is refunded.
+  // each order is bought at its price in max_total asset (all remaining units of cw1155 order)
+  ExecuteOrders {
+    order_ids: Vec<u64>,
+    max_total: Asset,
+    // if true, skip orders that are gone, expired or not affordable within max_total. otherwise fail
+    skip_unavailable: bool
+  },
+
+  // buy the cheapest fixed price orders of the collection in asset. asset is the budget and the unused amount is refunded.
+  // cw1155 order counts its units
+  SweepFloor {
+    nft_address: String,
+    count: u32,
+    max_unit_price: Uint128,
+    asset: Asset
   },
 
   // execute expired auction.
@@ -62,25 +115,260 @@ pub enum ExecuteMsg {
     order_id: u64
   },
 
+  // return expired fixed price orders' nft to the sellers. anyone can execute.
+  // missing, auction and not expired orders are skipped and reported in skipped_order_ids
+  ReclaimExpired {
+    order_ids: Vec<u64>
+  },
+
+  // update the order without relisting. only seller can execute.
+  // price: fixed price of fixed price order or start price of auction
+  // fixed_price: auction only
+  // expiration: expiration of fixed price order or auction
+  // auction can be updated only before the first bid.
+  UpdateOrder {
+    order_id: u64,
+    price: Option<Asset>,
+    fixed_price: Option<Asset>,
+    expiration: Option<Expiration>,
+  },
+
   Bid {
     order_id: u64,
-    bid_price: Asset
+    bid_price: Asset,
+    // front-end to get referral fee if the bid wins
+    referrer: Option<String>
   },
+
+  // offer to buy any token of the collection. price is escrowed until accepted or canceled.
+  MakeCollectionOffer {
+    nft_address: String,
+    price: Asset
+  },
+
+  // offer to buy a specific token. price is escrowed until accepted, canceled or expired.
+  MakeOffer {
+    nft_address: String,
+    token_id: String,
+    price: Asset,
+    expiration: Expiration
+  },
+
+  // cancel offer or withdraw expired offer
+  CancelOffer {
+    offer_id: u64
+  },
+
+  // make order with nfts added by AddToBundle
+  MakeBundleFixedPriceOrder {
+    price: Asset,
+    // if None, never expire
+    expiration: Option<Expiration>,
+    // other assets buyer can pay with. each asset must be supported by the collection
+    other_prices: Option<Vec<Asset>>,
+    // if set, only these addresses can buy or bid (private order)
+    buyers: Option<Vec<String>>,
+  },
+
+  MakeBundleAuctionOrder {
+    start_price: Asset,
+    expiration: Expiration,
+    fixed_price: Option<Asset>,
+    reserve_price: Option<Asset>,
+    // winner pays the second highest bid + min increase instead of own bid. default false
+    second_price: Option<bool>,
+    // if set, only these addresses can buy or bid (private order)
+    buyers: Option<Vec<String>>,
+  },
+
+  // return nfts added by AddToBundle
+  WithdrawBundle {},
+
+  // commit sealed bid with native token deposit. deposit must be higher or equal to the bid
+  // commitment: sha256 of "{order_id}:{bidder}:{amount}:{salt}"
+  CommitBid {
+    order_id: u64,
+    commitment: Binary,
+    deposit: Asset
+  },
+
+  // reveal sealed bid after bid_end
+  RevealBid {
+    order_id: u64,
+    amount: Uint128,
+    salt: String
+  },
+
+  // return deposit of sealed bid after the auction is executed. anyone can execute.
+  // deposit goes to the bidder, or to the seller if it is forfeited
+  WithdrawSealedBid {
+    order_id: u64,
+    // if None, sender
+    bidder: Option<String>
+  },
+
+  // make swap order with nfts added by AddToBundle. sweetener of seller is sent with funds
+  MakeBundleSwapOrder {
+    nft_address: String,
+    // if None, any token of the collection
+    token_id: Option<String>,
+    sweetener: Option<Sweetener>,
+    // if None, never expire
+    expiration: Option<Expiration>,
+  },
+
+  // fulfill swap order with the nft added by AddToBundle. sweetener of counterparty is sent with funds
+  FulfillBundleSwap {
+    order_id: u64
+  },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(try_from = "RawReceiveMsg", into = "RawReceiveMsg")]
+pub enum ReceiveMsg {
+  Cw20(Cw20ReceiveMsg),
+  Cw1155(Cw1155ReceiveMsg)
+}
+
+impl From<Cw20ReceiveMsg> for ReceiveMsg {
+  fn from(msg: Cw20ReceiveMsg) -> Self {
+    ReceiveMsg::Cw20(msg)
+  }
+}
+
+impl From<Cw1155ReceiveMsg> for ReceiveMsg {
+  fn from(msg: Cw1155ReceiveMsg) -> Self {
+    ReceiveMsg::Cw1155(msg)
+  }
+}
+
+impl JsonSchema for ReceiveMsg {
+  fn schema_name() -> String {
+    "ReceiveMsg".to_string()
+  }
+
+  fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+    RawReceiveMsg::json_schema(gen)
+  }
+}
+
+// wire format of `receive` hook. cw20 sets sender, cw1155 sets operator and token_id
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RawReceiveMsg {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub sender: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub operator: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub from: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub token_id: Option<String>,
+  pub amount: Uint128,
+  pub msg: Binary,
+}
+
+impl TryFrom<RawReceiveMsg> for ReceiveMsg {
+  type Error = StdError;
+
+  fn try_from(raw: RawReceiveMsg) -> Result<Self, Self::Error> {
+    match (raw.sender, raw.operator, raw.token_id) {
+      (Some(sender), None, None) => Ok(ReceiveMsg::Cw20(Cw20ReceiveMsg {
+        sender,
+        amount: raw.amount,
+        msg: raw.msg
+      })),
+      (None, Some(operator), Some(token_id)) => Ok(ReceiveMsg::Cw1155(Cw1155ReceiveMsg {
+        operator,
+        from: raw.from,
+        token_id,
+        amount: raw.amount,
+        msg: raw.msg
+      })),
+      _ => Err(StdError::generic_err("unknown receive msg"))
+    }
+  }
+}
+
+impl From<ReceiveMsg> for RawReceiveMsg {
+  fn from(msg: ReceiveMsg) -> Self {
+    match msg {
+      ReceiveMsg::Cw20(msg) => RawReceiveMsg {
+        sender: Some(msg.sender),
+        operator: None,
+        from: None,
+        token_id: None,
+        amount: msg.amount,
+        msg: msg.msg
+      },
+      ReceiveMsg::Cw1155(msg) => RawReceiveMsg {
+        sender: None,
+        operator: Some(msg.operator),
+        from: msg.from,
+        token_id: Some(msg.token_id),
+        amount: msg.amount,
+        msg: msg.msg
+      }
+    }
+  }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum Cw20HookMsg {
   ExecuteOrder {
-    order_id: u64
+    order_id: u64,
+    amount: Option<Uint128>,
+    referrer: Option<String>
+  },
+
+  // sent amount is max_total
+  ExecuteOrders {
+    order_ids: Vec<u64>,
+    skip_unavailable: bool
+  },
+
+  // sent amount is the budget
+  SweepFloor {
+    nft_address: String,
+    count: u32,
+    max_unit_price: Uint128
   },
 
   Bid {
     order_id: u64,
+    referrer: Option<String>
   },
 
   CancelOrder {
     order_id: u64,
+  },
+
+  MakeCollectionOffer {
+    nft_address: String,
+  },
+
+  MakeOffer {
+    nft_address: String,
+    token_id: String,
+    expiration: Expiration
+  },
+
+  // commit sealed bid. sent amount is the deposit
+  CommitBid {
+    order_id: u64,
+    commitment: Binary
+  },
+
+  // make swap order with nfts added by AddToBundle. sent amount is the sweetener of seller
+  MakeBundleSwapOrder {
+    nft_address: String,
+    token_id: Option<String>,
+    expiration: Option<Expiration>,
+  },
+
+  // fulfill swap order with the nft added by AddToBundle. sent amount is the sweetener of counterparty
+  FulfillBundleSwap {
+    order_id: u64
   }
 }
 
@@ -88,16 +376,101 @@ pub enum Cw20HookMsg {
 #[serde(rename_all = "snake_case")]
 pub enum Cw721HookMsg {
   MakeFixedPriceOrder {
-    price: Asset
+    price: Asset,
+    // if None, never expire
+    expiration: Option<Expiration>,
+    // other assets buyer can pay with. each asset must be supported by the collection
+    other_prices: Option<Vec<Asset>>,
+    // if set, only these addresses can buy or bid (private order)
+    buyers: Option<Vec<String>>,
   },
 
   MakeAuctionOrder {
     start_price: Asset,
     expiration: Expiration,
     fixed_price: Option<Asset>,
+    // if highest bid is lower than reserve price, nft returns to the seller when the auction is executed
+    reserve_price: Option<Asset>,
+    // winner pays the second highest bid + min increase instead of own bid. default false
+    second_price: Option<bool>,
+    // if set, only these addresses can buy or bid (private order)
+    buyers: Option<Vec<String>>,
+  },
+
+  // sell the sent nft to the offerer
+  AcceptCollectionOffer {
+    offer_id: u64
+  },
+
+  // sell the sent nft to the offerer of the token offer
+  AcceptOffer {
+    offer_id: u64
+  },
+
+  // price decreases linearly from start_price to end_price between start and end.
+  // start and end must be both height or both time. after end, it is sold at end_price.
+  MakeDutchAuctionOrder {
+    start_price: Asset,
+    end_price: Asset,
+    start: Expiration,
+    end: Expiration,
+  },
+
+  // bid_end and reveal_end must be both height or both time
+  MakeSealedBidAuctionOrder {
+    min_price: Asset,
+    bid_end: Expiration,
+    reveal_end: Expiration,
+    unrevealed_policy: UnrevealedPolicy,
+    // winner pays the second highest bid + min increase instead of own bid. default false
+    second_price: Option<bool>,
+  },
+
+  // deposit nft to make a bundle order
+  AddToBundle {},
+
+  // swap the sent nft for nft_address/token_id. sweetener of seller can not be sent here, use MakeBundleSwapOrder
+  MakeSwapOrder {
+    nft_address: String,
+    // if None, any token of the collection
+    token_id: Option<String>,
+    sweetener: Option<Sweetener>,
+    // if None, never expire
+    expiration: Option<Expiration>,
+  },
+
+  // fulfill swap order with the sent nft. sweetener of counterparty can not be sent here, use FulfillBundleSwap
+  FulfillSwap {
+    order_id: u64
   }
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw1155HookMsg {
+  // list the received amount at per unit price. batch receive makes an order per token id
+  MakeFixedPriceOrder {
+    price: Asset,
+    // if None, never expire
+    expiration: Option<Expiration>,
+  }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderStatus {
+  Active,
+  Expired
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderSort {
+  PriceAsc,
+  PriceDesc,
+  Newest
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
@@ -118,11 +491,216 @@ pub enum QueryMsg {
 
   Orders {
     seller_address: Option<Addr>,
+    nft_address: Option<String>,
+    order_type: Option<OrderType>,
+    // orders that can be paid with the asset
+    asset: Option<AssetInfo>,
+    status: Option<OrderStatus>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    // newest first. default false
+    descending: Option<bool>
+  },
+
+  // returns OrdersCountResponse. number of orders matching the same filters as Orders
+  OrdersCount {
+    seller_address: Option<Addr>,
+    nft_address: Option<String>,
+    order_type: Option<OrderType>,
+    asset: Option<AssetInfo>,
+    status: Option<OrderStatus>,
+    start_after: Option<u64>
+  },
+
+  // private orders the buyer is allowed to buy
+  OrdersByBuyer {
+    buyer: String,
+    start_after: Option<u64>,
+    limit: Option<u32>
+  },
+
+  // auctions the bidder is the highest bidder of
+  OrdersByBidder {
+    bidder: String,
+    start_after: Option<u64>,
+    limit: Option<u32>
+  },
+
+  // auctions the bidder was outbid on, most recent outbid first. closed auctions are not included
+  Outbids {
+    bidder: String,
+    // order id
+    start_after: Option<u64>,
+    limit: Option<u32>
+  },
+
+  // active order that has the token
+  OrderByToken {
+    nft_address: String,
+    token_id: String
+  },
+
+  // orders of the collection. price sorts need asset and list only fixed price orders of single nft sold for it
+  OrdersByCollection {
+    nft_address: String,
+    asset: Option<AssetInfo>,
+    sort: OrderSort,
     start_after: Option<u64>,
     limit: Option<u32>
   },
 
+  // lowest unit price of active public fixed price orders of the collection in the asset
+  FloorPrice {
+    nft_address: String,
+    asset: AssetInfo
+  },
+
   CancelFee {
     order_id: u64
+  },
+
+  Offer {
+    offer_id: u64
+  },
+
+  Offers {
+    nft_address: Option<String>,
+    start_after: Option<u64>,
+    limit: Option<u32>
+  },
+
+  OffersByToken {
+    nft_address: String,
+    token_id: String,
+    start_after: Option<u64>,
+    limit: Option<u32>
+  },
+
+  OffersByOfferer {
+    offerer: String,
+    start_after: Option<u64>,
+    limit: Option<u32>
+  },
+
+  PendingBundle {
+    seller: String
+  },
+
+  // whether the highest bid reaches the reserve price. true if there is no reserve price
+  ReserveMet {
+    order_id: u64
+  },
+
+  SealedBids {
+    order_id: u64,
+    // bidder address
+    start_after: Option<String>,
+    limit: Option<u32>
+  },
+
+  // current price of fixed price order or dutch auction
+  CurrentPrice {
+    order_id: u64
+  },
+
+  // trade history queries return newest trade first. start_after is trade id
+  TradeHistoryByCollection {
+    nft_address: String,
+    start_after: Option<u64>,
+    limit: Option<u32>
+  },
+
+  TradeHistoryByToken {
+    nft_address: String,
+    token_id: String,
+    start_after: Option<u64>,
+    limit: Option<u32>
+  },
+
+  TradeHistoryByBuyer {
+    buyer: String,
+    start_after: Option<u64>,
+    limit: Option<u32>
+  },
+
+  TradeHistoryBySeller {
+    seller: String,
+    start_after: Option<u64>,
+    limit: Option<u32>
+  },
+
+  // latest trade of the token
+  LastSale {
+    nft_address: String,
+    token_id: String
+  },
+
+  // returns CollectionStatsResponse
+  CollectionStats {
+    nft_address: String
+  },
+
+  // returns MarketStatsResponse
+  MarketStats {},
+
+  // protocol fee discount the address gets as buyer or seller
+  FeeQuote {
+    address: String,
+    // if Some, use protocol fee rate of the collection
+    nft_address: Option<String>
+  }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OrdersCountResponse {
+  pub count: u64,
+  // Some if the scan limit is hit. count the rest with start_after
+  pub last_order_id: Option<u64>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AssetStats {
+  pub stats: TradeStats,
+  // lowest unit price of active public fixed price orders
+  pub floor: Option<Uint128>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollectionStatsResponse {
+  pub nft_address: Addr,
+  // active sale listings. swaps, sealed bid auctions and expired orders are not counted
+  // only the earliest 100 expired listings are excluded, later ones are counted until reclaimed
+  pub listed_count: u64,
+  // one per support asset of the collection
+  pub assets: Vec<AssetStats>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MarketStatsResponse {
+  // active sale listings of all collections. expired listings are excluded the same way as collection stats
+  pub listed_count: u64,
+  pub assets: Vec<TradeStats>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeQuoteResponse {
+  pub balance: Uint128,
+  pub tier: Option<FeeTier>,
+  // protocol fee rate before discount
+  pub protocol_fee_rate: Decimal,
+  pub discount_rate: Decimal
+}
+
+// query of staking contract used for fee discount
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StakingQueryMsg {
+  StakedBalance {
+    address: String
   }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakedBalanceResponse {
+  pub balance: Uint128
 }
\ No newline at end of file
@@ -1,62 +1,454 @@
-use cosmwasm_std::{to_binary, Addr, Binary, Deps, StdResult, Order::Ascending as Ascending, Uint128};
-use cw_storage_plus::{Bound, U64Key};
+use cosmwasm_std::{to_binary, Addr, Binary, Deps, Env, StdError, StdResult, Order::Ascending as Ascending, Order::Descending as Descending, Timestamp, Uint128};
+use cw_storage_plus::{Bound, Prefix, U64Key};
+use cw0::Expiration;
 use std::marker::PhantomData;
 
-use crate::state::{MarketContract, CollectionInfo, Order};
-use crate::msgs::QueryMsg;
-use crate::asset::Asset;
+use crate::state::{expiration_key, floor_key, MAX_FLOOR_SCAN, outbid_key, parse_floor_key, parse_outbid_key, MarketContract, CollectionInfo, Offer, Order, OrderType, Outbid, SealedBid, Trade, TradeStats};
+use crate::msgs::{AssetStats, CollectionStatsResponse, FeeQuoteResponse, MarketStatsResponse, OrderSort, OrderStatus, OrdersCountResponse, QueryMsg};
+use crate::asset::{Asset, AssetInfo};
 
 const DEFAULT_LIMIT: u32 = 10;
 const MAX_LIMIT: u32 = 30;
+// expired listings read by a listing count. older ones are counted until reclaimed
+const MAX_EXPIRED_SCAN: usize = 100;
+// orders read by an orders count
+const MAX_COUNT_SCAN: usize = 500;
 
 impl<'a> MarketContract<'a> {
-  fn orders(&self, deps: Deps, seller_address: Option<Addr>, start_after: Option<u64>, limit: Option<u32>) -> StdResult<Vec<Order>> {
+  #[allow(clippy::too_many_arguments)]
+  fn orders(
+    &self,
+    deps: Deps,
+    env: Env,
+    seller_address: Option<Addr>,
+    nft_address: Option<String>,
+    order_type: Option<OrderType>,
+    asset: Option<AssetInfo>,
+    status: Option<OrderStatus>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    descending: Option<bool>
+  ) -> StdResult<Vec<Order>> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let start = if let Some(start_after) = start_after {
-      Some(Bound::exclusive(U64Key::new(start_after)))
+    let nft_address = nft_address.map(|nft_address| deps.api.addr_validate(&nft_address)).transpose()?;
+    let filter = OrderFilter { nft_address, order_type, asset, status };
+    let mut orders: Vec<Order> = vec![];
+
+    for item in self.orders_range(deps, seller_address, &filter.nft_address, start_after, descending.unwrap_or(false)) {
+      if orders.len() >= limit {
+        break
+      }
+
+      let (_, v) = item?;
+
+      if filter.matches(&v, &env) {
+        orders.push(v);
+      }
+    }
+
+    Ok(orders)
+  }
+
+  // scanned orders are capped. if the cap is hit, continue from last_order_id
+  #[allow(clippy::too_many_arguments)]
+  fn orders_count(
+    &self,
+    deps: Deps,
+    env: Env,
+    seller_address: Option<Addr>,
+    nft_address: Option<String>,
+    order_type: Option<OrderType>,
+    asset: Option<AssetInfo>,
+    status: Option<OrderStatus>,
+    start_after: Option<u64>
+  ) -> StdResult<OrdersCountResponse> {
+    let nft_address = nft_address.map(|nft_address| deps.api.addr_validate(&nft_address)).transpose()?;
+    let filter = OrderFilter { nft_address, order_type, asset, status };
+    let mut count = 0u64;
+    let mut scanned = 0usize;
+    let mut last_order_id = None;
+
+    for item in self.orders_range(deps, seller_address, &filter.nft_address, start_after, false) {
+      if scanned >= MAX_COUNT_SCAN {
+        break
+      }
+
+      let (_, v) = item?;
+      scanned += 1;
+
+      if filter.matches(&v, &env) {
+        count += 1;
+      }
+
+      last_order_id = Some(v.id);
+    }
+
+    if scanned < MAX_COUNT_SCAN {
+      last_order_id = None;
+    }
+
+    Ok(OrdersCountResponse { count, last_order_id })
+  }
+
+  // use the narrowest index
+  fn orders_range<'b>(
+    &self,
+    deps: Deps<'b>,
+    seller_address: Option<Addr>,
+    nft_address: &Option<Addr>,
+    start_after: Option<u64>,
+    descending: bool
+  ) -> Box<dyn Iterator<Item = StdResult<(Vec<u8>, Order)>> + 'b> where 'a: 'b {
+    let start = start_after.map(|start_after| Bound::exclusive(U64Key::new(start_after)));
+    let (min, max, order) = if descending { (None, start, Descending) } else { (start, None, Ascending) };
+
+    if let Some(seller_address) = seller_address {
+      self.orders.idx.seller_address.prefix(seller_address).range(deps.storage, min, max, order)
+    } else if let Some(nft_address) = nft_address.clone() {
+      self.orders.idx.nft_address.prefix(nft_address).range(deps.storage, min, max, order)
     } else {
-      None
-    };
+      self.orders.range(deps.storage, min, max, order)
+    }
+  }
+
+  fn orders_by_buyer(&self, deps: Deps, buyer: String, start_after: Option<u64>, limit: Option<u32>) -> StdResult<Vec<Order>> {
+    let buyer = deps.api.addr_validate(&buyer)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|start_after| Bound::exclusive(U64Key::new(start_after)));
 
-    let orders: Vec<Order> = if let Some(seller_address) = seller_address {
-      let pks: Vec<_> = self
-      .orders
+    self.orders
       .idx
-      .seller_address
-      .prefix(seller_address)
+      .buyer
+      .prefix(buyer)
       .keys(deps.storage, start, None, Ascending)
       .take(limit)
-      .collect();
+      .map(|pk| {
+        let restruct_int_key = U64Key {
+          wrapped: pk,
+          data: PhantomData
+        };
+        self.orders.load(deps.storage, restruct_int_key)
+      })
+      .collect()
+  }
 
-      pks.iter().map(|v|  {
+  fn orders_by_bidder(&self, deps: Deps, bidder: String, start_after: Option<u64>, limit: Option<u32>) -> StdResult<Vec<Order>> {
+    let bidder = deps.api.addr_validate(&bidder)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|start_after| Bound::exclusive(U64Key::new(start_after)));
+
+    self.orders
+      .idx
+      .bidder
+      .prefix(bidder)
+      .keys(deps.storage, start, None, Ascending)
+      .take(limit)
+      .map(|pk| {
         let restruct_int_key = U64Key {
-          wrapped: v.clone(),
+          wrapped: pk,
           data: PhantomData
         };
-        let order = self.orders.load(deps.storage, restruct_int_key).unwrap();
-        return order
-      }).collect()
-    } else {
-      self.orders
+        self.orders.load(deps.storage, restruct_int_key)
+      })
+      .collect()
+  }
+
+  fn outbids(&self, deps: Deps, bidder: String, start_after: Option<u64>, limit: Option<u32>) -> StdResult<Vec<Outbid>> {
+    let bidder = deps.api.addr_validate(&bidder)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    // continue after the height of start_after outbid
+    let start = match start_after {
+      Some(start_after) => {
+        let outbid = self.outbids.load(deps.storage, (bidder.clone(), U64Key::new(start_after)))?;
+
+        Some(Bound::exclusive(outbid_key(outbid.height, start_after)))
+      }
+      None => None
+    };
+
+    self.outbids
+      .idx
+      .recent
+      .prefix(bidder.clone())
+      .keys(deps.storage, None, start, Descending)
+      .take(limit)
+      .map(|key| self.outbids.load(deps.storage, (bidder.clone(), U64Key::new(parse_outbid_key(&key)))))
+      .collect()
+  }
+
+  fn order_by_token(&self, deps: Deps, nft_address: String, token_id: String) -> StdResult<Option<Order>> {
+    let nft_address = deps.api.addr_validate(&nft_address)?;
+
+    self.orders.idx.token.may_load_pk(deps.storage, (nft_address, token_id))?
+      .map(|pk| self.orders.load(deps.storage, U64Key { wrapped: pk, data: PhantomData }))
+      .transpose()
+  }
+
+  fn orders_by_collection(
+    &self,
+    deps: Deps,
+    nft_address: String,
+    asset: Option<AssetInfo>,
+    sort: OrderSort,
+    start_after: Option<u64>,
+    limit: Option<u32>
+  ) -> StdResult<Vec<Order>> {
+    let nft_address = deps.api.addr_validate(&nft_address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let pks: Vec<Vec<u8>> = match sort {
+      OrderSort::Newest => {
+        let start = start_after.map(|start_after| Bound::exclusive(U64Key::new(start_after)));
+
+        self.orders
+          .idx
+          .nft_address
+          .prefix(nft_address)
+          .keys(deps.storage, None, start, Descending)
+          .take(limit)
+          .collect()
+      }
+      OrderSort::PriceAsc | OrderSort::PriceDesc => {
+        let asset = asset.ok_or_else(|| StdError::generic_err("asset is required for price sort"))?;
+
+        // continue after the price of start_after order
+        let start = match start_after {
+          Some(start_after) => {
+            let order = self.orders.load(deps.storage, U64Key::new(start_after))?;
+            let price = order.price.iter().chain(order.other_prices.iter())
+              .find(|price| price.info == asset)
+              .ok_or_else(|| StdError::not_found("price"))?;
+
+            Some(Bound::exclusive(floor_key(price.amount, &U64Key::new(start_after).wrapped)))
+          }
+          None => None
+        };
+
+        let (min, max, order) = if sort == OrderSort::PriceAsc {
+          (start, None, Ascending)
+        } else {
+          (None, start, Descending)
+        };
+
+        self.orders
+          .idx
+          .floor
+          .prefix((nft_address, asset.to_string()))
+          .keys(deps.storage, min, max, order)
+          .take(limit)
+          .map(|key| parse_floor_key(&key).1)
+          .collect()
+      }
+    };
+
+    pks.into_iter().map(|pk| {
+      let restruct_int_key = U64Key {
+        wrapped: pk,
+        data: PhantomData
+      };
+      self.orders.load(deps.storage, restruct_int_key)
+    }).collect()
+  }
+
+  fn floor_price(&self, deps: Deps, env: Env, nft_address: String, asset: AssetInfo) -> StdResult<Option<Asset>> {
+    let nft_address = deps.api.addr_validate(&nft_address)?;
+
+    for key in self.orders.idx.floor.prefix((nft_address, asset.to_string())).keys(deps.storage, None, None, Ascending).take(MAX_FLOOR_SCAN) {
+      let (unit_price, pk) = parse_floor_key(&key);
+      let order = self.orders.load(deps.storage, U64Key { wrapped: pk, data: PhantomData })?;
+
+      // skip expired and private orders
+      if order.is_expired(&env.block) || !order.buyers.is_empty() {
+        continue
+      }
+
+      return Ok(Some(Asset { info: asset, amount: unit_price }))
+    }
+
+    Ok(None)
+  }
+
+  fn offers(&self, deps: Deps, nft_address: Option<String>, start_after: Option<u64>, limit: Option<u32>) -> StdResult<Vec<Offer>> {
+    if let Some(nft_address) = nft_address {
+      let nft_address = deps.api.addr_validate(&nft_address)?;
+
+      return self.offers_by_prefix(deps, self.offers.idx.nft_address.prefix(nft_address), start_after, limit)
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|start_after| Bound::exclusive(U64Key::new(start_after)));
+
+    self.offers
       .range(deps.storage, start, None, Ascending)
       .take(limit)
-      .map(|item| {
-        let(_, v) = item.unwrap();
-        v
+      .map(|item| item.map(|(_, v)| v))
+      .collect()
+  }
+
+  fn offers_by_token(&self, deps: Deps, nft_address: String, token_id: String, start_after: Option<u64>, limit: Option<u32>) -> StdResult<Vec<Offer>> {
+    let nft_address = deps.api.addr_validate(&nft_address)?;
+
+    self.offers_by_prefix(deps, self.offers.idx.token.prefix((nft_address, token_id)), start_after, limit)
+  }
+
+  fn offers_by_offerer(&self, deps: Deps, offerer: String, start_after: Option<u64>, limit: Option<u32>) -> StdResult<Vec<Offer>> {
+    let offerer = deps.api.addr_validate(&offerer)?;
+
+    self.offers_by_prefix(deps, self.offers.idx.offerer.prefix(offerer), start_after, limit)
+  }
+
+  fn offers_by_prefix(&self, deps: Deps, prefix: Prefix<Offer>, start_after: Option<u64>, limit: Option<u32>) -> StdResult<Vec<Offer>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|start_after| Bound::exclusive(U64Key::new(start_after)));
+
+    prefix
+      .range(deps.storage, start, None, Ascending)
+      .take(limit)
+      .map(|item| item.map(|(_, v)| v))
+      .collect()
+  }
+
+  fn trades_by_prefix(&self, deps: Deps, prefix: Prefix<Trade>, start_after: Option<u64>, limit: Option<u32>) -> StdResult<Vec<Trade>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|start_after| Bound::exclusive(U64Key::new(start_after)));
+
+    prefix
+      .range(deps.storage, None, start, Descending)
+      .take(limit)
+      .map(|item| item.map(|(_, v)| v))
+      .collect()
+  }
+
+  fn trades_by_token(&self, deps: Deps, nft_address: String, token_id: String, start_after: Option<u64>, limit: Option<u32>) -> StdResult<Vec<Trade>> {
+    let nft_address = deps.api.addr_validate(&nft_address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|start_after| Bound::exclusive(U64Key::new(start_after)));
+
+    self.trades
+      .idx
+      .token
+      .prefix((nft_address, token_id))
+      .keys(deps.storage, None, start, Descending)
+      .take(limit)
+      .map(|pk| {
+        let restruct_int_key = U64Key {
+          wrapped: pk,
+          data: PhantomData
+        };
+        self.trades.load(deps.storage, restruct_int_key)
       })
       .collect()
+  }
+
+  fn collection_stats(&self, deps: Deps, env: Env, nft_address: String) -> StdResult<CollectionStatsResponse> {
+    let collection_info = self.collections.load(deps.storage, nft_address)?;
+    let nft_address = collection_info.nft_address;
+
+    let assets = collection_info.support_assets.into_iter().map(|asset| {
+      let stats = self.collection_stats.may_load(deps.storage, (nft_address.clone(), asset.to_string()))?
+        .unwrap_or(TradeStats { asset: asset.clone(), volume: Uint128::zero(), sales_count: 0, all_time_high: Uint128::zero() });
+      let floor = self.floor_price(deps, env.clone(), nft_address.to_string(), asset)?.map(|floor| floor.amount);
+
+      Ok(AssetStats { stats, floor })
+    }).collect::<StdResult<Vec<AssetStats>>>()?;
+
+    Ok(CollectionStatsResponse {
+      listed_count: self.listed_count(deps, &env, Some(nft_address.clone()))?,
+      nft_address,
+      assets
+    })
+  }
+
+  fn market_stats(&self, deps: Deps, env: Env) -> StdResult<MarketStatsResponse> {
+    let listed_count = self.listed_count(deps, &env, None)?;
+
+    let assets = self.market_stats
+      .range(deps.storage, None, None, Ascending)
+      .map(|item| item.map(|(_, v)| v))
+      .collect::<StdResult<Vec<TradeStats>>>()?;
+
+    Ok(MarketStatsResponse { listed_count, assets })
+  }
+
+  // listings of a collection or all collections. only expired listings not removed yet are read
+  fn listed_count(&self, deps: Deps, env: &Env, nft_address: Option<Addr>) -> StdResult<u64> {
+    let count = match &nft_address {
+      Some(nft_address) => self.orders.idx.count.count(deps.storage, nft_address.clone())?,
+      None => self.orders.idx.count.total(deps.storage)?
     };
 
-    Ok(orders)
+    // listings expired at the block expire before the next height and time
+    let expired_ranges = [
+      (Expiration::AtHeight(0), Expiration::AtHeight(env.block.height + 1)),
+      (Expiration::AtTime(Timestamp::from_nanos(0)), Expiration::AtTime(env.block.time.plus_nanos(1))),
+    ];
+
+    let mut expired_count = 0u64;
+
+    for (min, max) in expired_ranges.iter() {
+      let min = expiration_key(min, &[]).map(Bound::inclusive);
+      let max = expiration_key(max, &[]).map(Bound::exclusive);
+
+      let expired = match &nft_address {
+        Some(nft_address) => self.orders.idx.expiration.prefix(nft_address.clone()),
+        None => self.orders.idx.market_expiration.prefix(())
+      };
+
+      expired_count += expired.keys(deps.storage, min, max, Ascending).take(MAX_EXPIRED_SCAN).count() as u64;
+    }
+
+    Ok(count.saturating_sub(expired_count))
+  }
+
+  fn fee_quote(&self, deps: Deps, address: String, nft_address: Option<String>) -> StdResult<FeeQuoteResponse> {
+    let config = self.config.load(deps.storage)?;
+    let address = deps.api.addr_validate(&address)?;
+
+    let mut protocol_fee_rate = config.protocol_fee_rate;
+
+    if let Some(nft_address) = nft_address {
+      let collection_info = self.collections.load(deps.storage, nft_address)?;
+      protocol_fee_rate = collection_info.protocol_fee_rate.unwrap_or(protocol_fee_rate);
+    }
+
+    let (balance, tier) = match config.fee_discount {
+      Some(fee_discount) => {
+        let balance = fee_discount.query_balance(&deps.querier, &address)?;
+        (balance, fee_discount.tier(balance))
+      }
+      None => (Uint128::zero(), None)
+    };
+
+    Ok(FeeQuoteResponse {
+      balance,
+      discount_rate: tier.clone().map(|tier| tier.discount_rate).unwrap_or_default(),
+      tier,
+      protocol_fee_rate
+    })
+  }
+
+  fn current_price(&self, deps: Deps, env: Env, order_id: u64) -> StdResult<Option<Asset>> {
+    let order = self.orders.load(deps.storage, U64Key::new(order_id))?;
+
+    Ok(order.current_price(&env.block))
+  }
+
+  fn sealed_bids(&self, deps: Deps, order_id: u64, start_after: Option<String>, limit: Option<u32>) -> StdResult<Vec<SealedBid>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    self.sealed_bids
+      .prefix(U64Key::new(order_id))
+      .range(deps.storage, start, None, Ascending)
+      .take(limit)
+      .map(|item| item.map(|(_, v)| v))
+      .collect()
   }
 
   fn collection_infos(&self, deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<Vec<CollectionInfo>> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let start = if let Some(start_after) = start_after {
-      Some(Bound::exclusive(start_after))
-    } else {
-      None
-    };
+    let start = start_after.map(Bound::exclusive);
 
     let collection_infos: Vec<CollectionInfo> = self.collections
       .range(deps.storage, start, None, Ascending)
@@ -86,6 +478,23 @@ impl<'a> MarketContract<'a> {
         amount: auction_info.highest_bid.amount * config.auction_cancel_fee_rate
       }
     // if it is not auction return 0 amount asset
+    } else if let Some(dutch_auction_info) = order.dutch_auction_info {
+      fee = Asset {
+        info: dutch_auction_info.start_price.info,
+        amount: Uint128::zero()
+      }
+    } else if let Some(sealed_bid_auction_info) = order.sealed_bid_auction_info {
+      fee = Asset {
+        info: sealed_bid_auction_info.min_price.info,
+        amount: Uint128::zero()
+      }
+    } else if let Some(swap_info) = order.swap_info {
+      let sweetener = swap_info.sweetener.ok_or_else(|| StdError::generic_err("swap order without sweetener has no fee asset"))?;
+
+      fee = Asset {
+        info: sweetener.asset.info,
+        amount: Uint128::zero()
+      }
     } else {
       fee = Asset {
         info: order.price.unwrap().info,
@@ -98,18 +507,102 @@ impl<'a> MarketContract<'a> {
 }
 
 impl<'a> MarketContract<'a> {
-  pub fn query(&self, deps: Deps, msg: QueryMsg) -> StdResult<Binary> {
+  pub fn query(&self, deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
       QueryMsg::Config {} => to_binary(&self.config.load(deps.storage)?),
       QueryMsg::Order { order_id } => to_binary(&self.orders.load(deps.storage, U64Key::new(order_id))?),
-      QueryMsg::Orders { seller_address, start_after, limit } 
-        => to_binary(&self.orders(deps, seller_address, start_after, limit)?),
+      QueryMsg::Orders { seller_address, nft_address, order_type, asset, status, start_after, limit, descending }
+        => to_binary(&self.orders(deps, env, seller_address, nft_address, order_type, asset, status, start_after, limit, descending)?),
+      QueryMsg::OrdersCount { seller_address, nft_address, order_type, asset, status, start_after }
+        => to_binary(&self.orders_count(deps, env, seller_address, nft_address, order_type, asset, status, start_after)?),
+      QueryMsg::OrdersByBuyer { buyer, start_after, limit }
+        => to_binary(&self.orders_by_buyer(deps, buyer, start_after, limit)?),
+      QueryMsg::OrdersByBidder { bidder, start_after, limit }
+        => to_binary(&self.orders_by_bidder(deps, bidder, start_after, limit)?),
+      QueryMsg::Outbids { bidder, start_after, limit }
+        => to_binary(&self.outbids(deps, bidder, start_after, limit)?),
+      QueryMsg::OrderByToken { nft_address, token_id }
+        => to_binary(&self.order_by_token(deps, nft_address, token_id)?),
+      QueryMsg::OrdersByCollection { nft_address, asset, sort, start_after, limit }
+        => to_binary(&self.orders_by_collection(deps, nft_address, asset, sort, start_after, limit)?),
+      QueryMsg::FloorPrice { nft_address, asset }
+        => to_binary(&self.floor_price(deps, env, nft_address, asset)?),
       QueryMsg::CollectionInfo { nft_address } 
         => to_binary(&self.collections.load(deps.storage, nft_address)?),
       QueryMsg::CollectionInfos { start_after, limit }
         => to_binary(&self.collection_infos(deps, start_after, limit)?),
       QueryMsg::CancelFee { order_id }
-        => to_binary(&self.cancel_fee(deps, order_id)?) 
+        => to_binary(&self.cancel_fee(deps, order_id)?),
+      QueryMsg::Offer { offer_id } => to_binary(&self.offers.load(deps.storage, U64Key::new(offer_id))?),
+      QueryMsg::Offers { nft_address, start_after, limit }
+        => to_binary(&self.offers(deps, nft_address, start_after, limit)?),
+      QueryMsg::OffersByToken { nft_address, token_id, start_after, limit }
+        => to_binary(&self.offers_by_token(deps, nft_address, token_id, start_after, limit)?),
+      QueryMsg::OffersByOfferer { offerer, start_after, limit }
+        => to_binary(&self.offers_by_offerer(deps, offerer, start_after, limit)?),
+      QueryMsg::PendingBundle { seller }
+        => to_binary(&self.bundles.may_load(deps.storage, seller)?.unwrap_or_default()),
+      QueryMsg::ReserveMet { order_id }
+        => to_binary(&self.reserve_met(deps, order_id)?),
+      QueryMsg::SealedBids { order_id, start_after, limit }
+        => to_binary(&self.sealed_bids(deps, order_id, start_after, limit)?),
+      QueryMsg::CurrentPrice { order_id }
+        => to_binary(&self.current_price(deps, env, order_id)?),
+      QueryMsg::TradeHistoryByCollection { nft_address, start_after, limit } => {
+        let nft_address = deps.api.addr_validate(&nft_address)?;
+        to_binary(&self.trades_by_prefix(deps, self.trades.idx.nft_address.prefix(nft_address), start_after, limit)?)
+      }
+      QueryMsg::TradeHistoryByToken { nft_address, token_id, start_after, limit }
+        => to_binary(&self.trades_by_token(deps, nft_address, token_id, start_after, limit)?),
+      QueryMsg::TradeHistoryByBuyer { buyer, start_after, limit } => {
+        let buyer = deps.api.addr_validate(&buyer)?;
+        to_binary(&self.trades_by_prefix(deps, self.trades.idx.buyer.prefix(buyer), start_after, limit)?)
+      }
+      QueryMsg::TradeHistoryBySeller { seller, start_after, limit } => {
+        let seller = deps.api.addr_validate(&seller)?;
+        to_binary(&self.trades_by_prefix(deps, self.trades.idx.seller.prefix(seller), start_after, limit)?)
+      }
+      QueryMsg::LastSale { nft_address, token_id }
+        => to_binary(&self.trades_by_token(deps, nft_address, token_id, None, Some(1))?.pop()),
+      QueryMsg::CollectionStats { nft_address }
+        => to_binary(&self.collection_stats(deps, env, nft_address)?),
+      QueryMsg::MarketStats {} => to_binary(&self.market_stats(deps, env)?),
+      QueryMsg::FeeQuote { address, nft_address }
+        => to_binary(&self.fee_quote(deps, address, nft_address)?)
     }
   }
-}
\ No newline at end of file
+}
+struct OrderFilter {
+  nft_address: Option<Addr>,
+  order_type: Option<OrderType>,
+  asset: Option<AssetInfo>,
+  status: Option<OrderStatus>
+}
+
+impl OrderFilter {
+  fn matches(&self, order: &Order, env: &Env) -> bool {
+    if let Some(nft_address) = &self.nft_address {
+      if &order.nft_address != nft_address {
+        return false
+      }
+    }
+
+    if let Some(order_type) = &self.order_type {
+      if &order.order_type() != order_type {
+        return false
+      }
+    }
+
+    if let Some(asset) = &self.asset {
+      if !order.asset_infos(&env.block).contains(asset) {
+        return false
+      }
+    }
+
+    match self.status {
+      Some(OrderStatus::Active) => !order.is_expired(&env.block),
+      Some(OrderStatus::Expired) => order.is_expired(&env.block),
+      None => true
+    }
+  }
+}
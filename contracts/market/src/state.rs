@@ -1,19 +1,39 @@
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Decimal};
+use cosmwasm_std::{Addr, Binary, BlockInfo, Decimal, QuerierWrapper, StdError, StdResult, Storage, Uint128};
 
-use cw_storage_plus::{Map, MultiIndex, Index, IndexedMap, IndexList, Item, U64Key};
+use cw_storage_plus::{Map, MultiIndex, Index, IndexedMap, IndexList, Item, Prefix, PrimaryKey, U64Key};
 use cw0::Expiration;
+use cw20::{BalanceResponse, Cw20QueryMsg};
 
 use crate::asset::{Asset, AssetInfo};
+use crate::msgs::{StakedBalanceResponse, StakingQueryMsg};
 
 pub struct MarketContract<'a> {
   pub config: Item<'a, Config>,
   pub collections: Map<'a, String, CollectionInfo>,
   // change it to IndexedMap (with seller index)
   pub orders: IndexedMap<'a, U64Key, Order, OrderIndexes<'a>>,
-  pub order_index: Item<'a, u64>
+  pub order_index: Item<'a, u64>,
+  pub offers: IndexedMap<'a, U64Key, Offer, OfferIndexes<'a>>,
+  pub offer_index: Item<'a, u64>,
+  // nfts deposited by seller to make a bundle order
+  pub bundles: Map<'a, String, Vec<BundleItem>>,
+  // kept out of order to not reveal the amount
+  pub reserve_prices: Map<'a, U64Key, Asset>,
+  pub sealed_bids: Map<'a, (U64Key, Addr), SealedBid>,
+  // executed sealed bid auctions with deposits not withdrawn yet
+  pub sealed_bid_settlements: Map<'a, U64Key, SealedBidSettlement>,
+  // last outbid of each bidder on each auction. removed when the auction is closed
+  pub outbids: IndexedMap<'a, (Addr, U64Key), Outbid, OutbidIndexes<'a>>,
+  // completed sales. never removed
+  pub trades: IndexedMap<'a, U64Key, Trade, TradeIndexes<'a>>,
+  pub trade_index: Item<'a, u64>,
+  // sale aggregates per collection and asset
+  pub collection_stats: Map<'a, (Addr, String), TradeStats>,
+  // sale aggregates per asset
+  pub market_stats: Map<'a, String, TradeStats>
 }
 
 impl Default for MarketContract<'static> {
@@ -24,26 +44,119 @@ impl Default for MarketContract<'static> {
       "auctions",
       "order_index",
       "seller_address",
+      "order_buyer",
+      "order_floor",
+      "order_nft_address",
+      "order_token",
+      "order_bidder",
+      "order_expiration",
+      "offers",
+      "offer_index",
+      "offer_nft_address",
+      "offer_token",
+      "offerer",
+      "bundles",
+      "reserve_prices",
+      "sealed_bids",
+      "sealed_bid_settlements",
+      "outbids",
+      "outbid_order",
+      "outbid_recent",
+      "trades",
+      "trade_index",
+      "trade_nft_address",
+      "trade_token",
+      "trade_seller",
+      "trade_buyer",
+      "order_count",
+      "order_count_total",
+      "order_market_expiration",
+      "collection_stats",
+      "market_stats",
     )
   }
 }
 
 impl<'a> MarketContract<'a> {
+  #[allow(clippy::too_many_arguments)]
   fn new(
     config_key: &'a str,
     collections_key: &'a str,
     orders_key: &'a str,
     order_index_key: &'a str,
     seller_address_key: &'a str,
+    order_buyer_key: &'a str,
+    order_floor_key: &'a str,
+    order_nft_address_key: &'a str,
+    order_token_key: &'a str,
+    order_bidder_key: &'a str,
+    order_expiration_key: &'a str,
+    offers_key: &'a str,
+    offer_index_key: &'a str,
+    offer_nft_address_key: &'a str,
+    offer_token_key: &'a str,
+    offerer_key: &'a str,
+    bundles_key: &'a str,
+    reserve_prices_key: &'a str,
+    sealed_bids_key: &'a str,
+    sealed_bid_settlements_key: &'a str,
+    outbids_key: &'a str,
+    outbid_order_key: &'a str,
+    outbid_recent_key: &'a str,
+    trades_key: &'a str,
+    trade_index_key: &'a str,
+    trade_nft_address_key: &'a str,
+    trade_token_key: &'a str,
+    trade_seller_key: &'a str,
+    trade_buyer_key: &'a str,
+    order_count_key: &'a str,
+    order_count_total_key: &'a str,
+    order_market_expiration_key: &'a str,
+    collection_stats_key: &'a str,
+    market_stats_key: &'a str,
   ) -> Self {
     let order_indexes = OrderIndexes {
       seller_address: MultiIndex::new(seller_idx, orders_key, seller_address_key),
+      buyer: MultiValueIndex::new(buyer_idx, order_buyer_key),
+      floor: MultiValueIndex::new(floor_idx, order_floor_key),
+      nft_address: MultiIndex::new(order_nft_address_idx, orders_key, order_nft_address_key),
+      token: UniqueValueIndex::new(order_token_idx, order_token_key),
+      bidder: MultiValueIndex::new(bidder_idx, order_bidder_key),
+      expiration: MultiValueIndex::new(order_expiration_idx, order_expiration_key),
+      market_expiration: MultiValueIndex::new(order_market_expiration_idx, order_market_expiration_key),
+      count: CountIndex::new(order_count_idx, order_count_key, order_count_total_key),
+    };
+    let offer_indexes = OfferIndexes {
+      nft_address: MultiIndex::new(offer_nft_address_idx, offers_key, offer_nft_address_key),
+      token: MultiIndex::new(offer_token_idx, offers_key, offer_token_key),
+      offerer: MultiIndex::new(offerer_idx, offers_key, offerer_key),
+    };
+    let outbid_indexes = OutbidIndexes {
+      order: MultiValueIndex::new(outbid_order_idx, outbid_order_key),
+      recent: MultiValueIndex::new(outbid_recent_idx, outbid_recent_key),
+    };
+    let trade_indexes = TradeIndexes {
+      nft_address: MultiIndex::new(trade_nft_address_idx, trades_key, trade_nft_address_key),
+      token: MultiValueIndex::new(trade_token_idx, trade_token_key),
+      seller: MultiIndex::new(trade_seller_idx, trades_key, trade_seller_key),
+      buyer: MultiIndex::new(trade_buyer_idx, trades_key, trade_buyer_key),
     };
     Self {
       config: Item::new(config_key),
       collections: Map::new(collections_key),
       orders: IndexedMap::new(orders_key, order_indexes),
-      order_index: Item::new(order_index_key)
+      order_index: Item::new(order_index_key),
+      offers: IndexedMap::new(offers_key, offer_indexes),
+      offer_index: Item::new(offer_index_key),
+      bundles: Map::new(bundles_key),
+      reserve_prices: Map::new(reserve_prices_key),
+      sealed_bids: Map::new(sealed_bids_key),
+      sealed_bid_settlements: Map::new(sealed_bid_settlements_key),
+      outbids: IndexedMap::new(outbids_key, outbid_indexes),
+      trades: IndexedMap::new(trades_key, trade_indexes),
+      trade_index: Item::new(trade_index_key),
+      collection_stats: Map::new(collection_stats_key),
+      market_stats: Map::new(market_stats_key)
     }
   }
 }
@@ -55,6 +168,76 @@ pub struct Config {
   pub max_auction_duration_block: u64,
   pub max_auction_duration_second: u64,
   pub auction_cancel_fee_rate: Decimal,
+  // soft close. bid within the window before expiration extends the auction by the window. 0 to disable
+  pub auction_extension_block: u64,
+  pub auction_extension_second: u64,
+  // taken from every sale and sent to treasury
+  pub protocol_fee_rate: Decimal,
+  pub treasury: Addr,
+  // if None, no discount
+  pub fee_discount: Option<FeeDiscount>,
+  // share of the protocol fee paid to the front-end referred the buyer
+  pub referral_share: Decimal,
+}
+
+// protocol fee discount by balance of governance token
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeDiscount {
+  pub source: FeeDiscountSource,
+  pub tiers: Vec<FeeTier>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeDiscountSource {
+  // cw20 balance of the address
+  Cw20 { contract_addr: Addr },
+  // staked balance of the address queried from staking contract
+  Staking { contract_addr: Addr },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeTier {
+  pub min_balance: Uint128,
+  // discounted portion of the protocol fee
+  pub discount_rate: Decimal,
+}
+
+impl FeeDiscount {
+  pub fn query_balance(&self, querier: &QuerierWrapper, address: &Addr) -> StdResult<Uint128> {
+    match &self.source {
+      FeeDiscountSource::Cw20 { contract_addr } => {
+        let res: BalanceResponse = querier.query_wasm_smart(
+          contract_addr.to_string(),
+          &Cw20QueryMsg::Balance { address: address.to_string() }
+        )?;
+
+        Ok(res.balance)
+      }
+      FeeDiscountSource::Staking { contract_addr } => {
+        let res: StakedBalanceResponse = querier.query_wasm_smart(
+          contract_addr.to_string(),
+          &StakingQueryMsg::StakedBalance { address: address.to_string() }
+        )?;
+
+        Ok(res.balance)
+      }
+    }
+  }
+
+  // highest tier the balance reaches
+  pub fn tier(&self, balance: Uint128) -> Option<FeeTier> {
+    self.tiers.iter()
+      .filter(|tier| tier.min_balance <= balance)
+      .max_by_key(|tier| tier.min_balance)
+      .cloned()
+  }
+
+  pub fn discount_rate(&self, querier: &QuerierWrapper, address: &Addr) -> StdResult<Decimal> {
+    let balance = self.query_balance(querier, address)?;
+
+    Ok(self.tier(balance).map(|tier| tier.discount_rate).unwrap_or_default())
+  }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -62,6 +245,27 @@ pub struct CollectionInfo {
   pub nft_address: Addr,
   pub support_assets: Vec<AssetInfo>,
   pub royalties: Vec<Royalty>,
+  // if None, use auction extension of config
+  pub auction_extension: Option<AuctionExtension>,
+  pub swap_royalty: SwapRoyalty,
+  // if None, use protocol fee rate of config
+  pub protocol_fee_rate: Option<Decimal>,
+}
+
+// royalty policy of the collection for nft swaps
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SwapRoyalty {
+  // no royalty on swaps
+  Exempt,
+  // royalties are paid from the sweetener received for the nfts of the collection
+  Sweetener
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AuctionExtension {
+  pub block: u64,
+  pub second: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -71,7 +275,184 @@ pub struct Order {
   pub nft_address: Addr,
   pub token_id: String,
   pub price: Option<Asset>,
-  pub auction_info: Option<AuctionInfo>
+  // if not empty, only these addresses can buy or bid (private order)
+  pub buyers: Vec<Addr>,
+  // other acceptable prices of fixed price order. buyer pays with one of price and other_prices
+  pub other_prices: Vec<Asset>,
+  pub auction_info: Option<AuctionInfo>,
+  // expiration of fixed price order. auction uses auction_info.expiration
+  pub expiration: Option<Expiration>,
+  // other nfts sold together with nft_address/token_id. empty if it is not a bundle order
+  pub bundle: Vec<BundleItem>,
+  pub dutch_auction_info: Option<DutchAuctionInfo>,
+  pub sealed_bid_auction_info: Option<SealedBidAuctionInfo>,
+  pub swap_info: Option<SwapInfo>,
+  // None for cw721 order
+  pub cw1155_info: Option<Cw1155Info>
+}
+
+impl Order {
+  pub fn is_expired(&self, block: &BlockInfo) -> bool {
+    if let Some(auction_info) = &self.auction_info {
+      return auction_info.expiration.is_expired(block)
+    }
+
+    match &self.expiration {
+      Some(expiration) => expiration.is_expired(block),
+      None => false
+    }
+  }
+
+  // price to buy the order now. None if it can not be bought at fixed price (auction without fixed price)
+  pub fn current_price(&self, block: &BlockInfo) -> Option<Asset> {
+    if let Some(dutch_auction_info) = &self.dutch_auction_info {
+      return Some(dutch_auction_info.current_price(block))
+    }
+
+    self.price.clone()
+  }
+
+  // swaps and sealed bid auctions are not listed for sale
+  pub fn is_listing(&self) -> bool {
+    self.swap_info.is_none() && self.sealed_bid_auction_info.is_none()
+  }
+
+  pub fn is_allowed_buyer(&self, buyer: &Addr) -> bool {
+    self.buyers.is_empty() || self.buyers.contains(buyer)
+  }
+
+  // all prices the order can be bought with
+  pub fn prices(&self, block: &BlockInfo) -> Vec<Asset> {
+    match self.current_price(block) {
+      Some(price) => {
+        let mut prices = vec![price];
+        prices.extend(self.other_prices.clone());
+        prices
+      }
+      None => vec![]
+    }
+  }
+
+  pub fn order_type(&self) -> OrderType {
+    if self.auction_info.is_some() {
+      return if self.price.is_some() { OrderType::FixedAuction } else { OrderType::Auction }
+    }
+
+    if self.dutch_auction_info.is_some() {
+      OrderType::DutchAuction
+    } else if self.sealed_bid_auction_info.is_some() {
+      OrderType::SealedBidAuction
+    } else if self.swap_info.is_some() {
+      OrderType::Swap
+    } else {
+      OrderType::Fixed
+    }
+  }
+
+  // assets the order can be paid with
+  pub fn asset_infos(&self, block: &BlockInfo) -> Vec<AssetInfo> {
+    let mut asset_infos: Vec<AssetInfo> = self.prices(block).into_iter().map(|price| price.info).collect();
+
+    if let Some(auction_info) = &self.auction_info {
+      asset_infos.push(auction_info.highest_bid.info.clone());
+    }
+
+    if let Some(sealed_bid_auction_info) = &self.sealed_bid_auction_info {
+      asset_infos.push(sealed_bid_auction_info.min_price.info.clone());
+    }
+
+    asset_infos
+  }
+
+  // all nfts of the order
+  pub fn items(&self) -> Vec<BundleItem> {
+    let mut items = vec![BundleItem { nft_address: self.nft_address.clone(), token_id: self.token_id.clone() }];
+    items.extend(self.bundle.clone());
+    items
+  }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderType {
+  Fixed,
+  Auction,
+  // auction that can be bought at fixed price
+  FixedAuction,
+  DutchAuction,
+  SealedBidAuction,
+  Swap
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BundleItem {
+  pub nft_address: Addr,
+  pub token_id: String,
+}
+
+// buy side order. price is escrowed in the market until the offer is accepted or canceled.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Offer {
+  pub id: u64,
+  pub offerer: Addr,
+  pub nft_address: Addr,
+  // if None, any token of the collection can fill the offer (collection offer)
+  pub token_id: Option<String>,
+  pub price: Asset,
+  pub expiration: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Trade {
+  pub id: u64,
+  pub sale_type: SaleType,
+  // None for offer sale
+  pub order_id: Option<u64>,
+  // Some for offer sale
+  pub offer_id: Option<u64>,
+  pub nft_address: Addr,
+  pub token_id: String,
+  // other nfts of bundle order
+  pub bundle: Vec<BundleItem>,
+  // units of cw1155 order
+  pub amount: Option<Uint128>,
+  pub seller: Addr,
+  pub buyer: Addr,
+  // None for swap without sweetener from the buyer
+  pub price: Option<Asset>,
+  pub royalty_amount: Uint128,
+  pub protocol_fee: Uint128,
+  pub height: u64,
+  pub time: u64
+}
+
+impl Trade {
+  // all nfts of the trade
+  pub fn items(&self) -> Vec<BundleItem> {
+    let mut items = vec![BundleItem { nft_address: self.nft_address.clone(), token_id: self.token_id.clone() }];
+    items.extend(self.bundle.clone());
+    items
+  }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TradeStats {
+  pub asset: AssetInfo,
+  pub volume: Uint128,
+  pub sales_count: u64,
+  // highest price of a nft. bundle price is divided by the number of nfts, cw1155 price by the units
+  pub all_time_high: Uint128
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SaleType {
+  FixedPrice,
+  Auction,
+  DutchAuction,
+  SealedBidAuction,
+  Offer,
+  Swap
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -80,26 +461,478 @@ pub struct Royalty {
   pub royalty_rate: Decimal
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DutchAuctionInfo {
+  pub start_price: Asset,
+  pub end_price: Asset,
+  pub start: Expiration,
+  pub end: Expiration
+}
+
+impl DutchAuctionInfo {
+  pub fn current_price(&self, block: &BlockInfo) -> Asset {
+    let (start, end, now) = match (self.start, self.end) {
+      (Expiration::AtHeight(start), Expiration::AtHeight(end)) => (start, end, block.height),
+      (Expiration::AtTime(start), Expiration::AtTime(end)) => (start.seconds(), end.seconds(), block.time.seconds()),
+      // checked when the order is made
+      _ => return self.end_price.clone()
+    };
+
+    let amount = if now <= start {
+      self.start_price.amount
+    } else if now >= end {
+      self.end_price.amount
+    } else {
+      let decrease = (self.start_price.amount - self.end_price.amount).multiply_ratio(now - start, end - start);
+      self.start_price.amount - decrease
+    };
+
+    Asset {
+      info: self.start_price.info.clone(),
+      amount
+    }
+  }
+}
+
+// bidders commit hash of their bid until bid_end and reveal it until reveal_end
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SealedBidAuctionInfo {
+  pub min_price: Asset,
+  pub bid_end: Expiration,
+  pub reveal_end: Expiration,
+  pub unrevealed_policy: UnrevealedPolicy,
+  // highest revealed bid. None if no valid reveal yet
+  pub highest_bid: Option<Asset>,
+  pub bidder: Option<Addr>,
+  // second highest revealed bid
+  pub runner_up_bid: Option<Asset>,
+  // if true, winner pays runner_up_bid + min_increase (at least min_price)
+  pub second_price: bool
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SealedBidSettlement {
+  // receives forfeited deposits
+  pub seller: Addr,
+  pub unrevealed_policy: UnrevealedPolicy
+}
+
+// what happens to the deposit of unrevealed (or invalid) commitments
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UnrevealedPolicy {
+  // return deposit to the bidder
+  Refund,
+  // deposit goes to the seller
+  Forfeit
+}
+
+// semi-fungible token order. price of the order is per unit and it can be partially filled
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw1155Info {
+  // listed units
+  pub amount: Uint128,
+  // units not sold yet
+  pub remaining: Uint128
+}
+
+// nft the seller wants in exchange for the order nfts
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SwapInfo {
+  pub nft_address: Addr,
+  // if None, any token of the collection
+  pub token_id: Option<String>,
+  pub sweetener: Option<Sweetener>
+}
+
+// asset paid together with the nft. sweetener of seller is escrowed when the order is made
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Sweetener {
+  pub asset: Asset,
+  pub payer: SweetenerPayer
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SweetenerPayer {
+  Seller,
+  Counterparty
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SealedBid {
+  pub order_id: u64,
+  pub bidder: Addr,
+  // sha256 of "{order_id}:{bidder}:{amount}:{salt}"
+  pub commitment: Binary,
+  // upper bound of the bid. escrowed until it is withdrawn after the auction is executed
+  pub deposit: Asset,
+  // Some after valid reveal
+  pub amount: Option<Uint128>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Outbid {
+  pub order_id: u64,
+  pub bidder: Addr,
+  // refunded bid of the bidder
+  pub bid: Asset,
+  // bid that outbid it
+  pub highest_bid: Asset,
+  pub height: u64,
+  pub time: u64
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct AuctionInfo {
   pub highest_bid: Asset,
   // if None, no bid yet.
   pub bidder: Option<Addr>,
-  pub expiration: Expiration
+  pub expiration: Expiration,
+  // bid outbid by the highest bid. start price if there is only one bid
+  pub runner_up_bid: Option<Asset>,
+  // if true, winner pays runner_up_bid + min_increase and the rest is refunded
+  pub second_price: bool,
+  // front-end referred the highest bid
+  pub referrer: Option<Addr>
 }
 
 pub struct OrderIndexes<'a> {
-  pub seller_address: MultiIndex<'a, (Addr, Vec<u8>), Order>
+  pub seller_address: MultiIndex<'a, (Addr, Vec<u8>), Order>,
+  pub buyer: MultiValueIndex<'a, (Addr, Vec<u8>), Order>,
+  pub floor: MultiValueIndex<'a, (Addr, String, Vec<u8>), Order>,
+  pub nft_address: MultiIndex<'a, (Addr, Vec<u8>), Order>,
+  pub token: UniqueValueIndex<'a, (Addr, String)>,
+  pub bidder: MultiValueIndex<'a, (Addr, Vec<u8>), Order>,
+  pub expiration: MultiValueIndex<'a, (Addr, Vec<u8>), Order>,
+  pub market_expiration: MultiValueIndex<'a, Vec<u8>, Order>,
+  pub count: CountIndex<'a, Addr>
 }
 
 pub fn seller_idx(d: &Order, k: Vec<u8>) -> (Addr, Vec<u8>) {
   (d.seller_address.clone(), k)
 }
 
+// private order is indexed once per buyer
+pub fn buyer_idx(d: &Order, k: Vec<u8>) -> Vec<(Addr, Vec<u8>)> {
+  d.buyers.iter().map(|buyer| (buyer.clone(), k.clone())).collect()
+}
+
+// fixed price order of single nft is indexed once per price asset.
+// last key is big endian unit price + pk, so orders of the collection and asset are sorted by price
+pub fn floor_idx(d: &Order, k: Vec<u8>) -> Vec<(Addr, String, Vec<u8>)> {
+  if d.auction_info.is_some() || d.dutch_auction_info.is_some() || !d.bundle.is_empty() {
+    return vec![]
+  }
+
+  let mut prices: Vec<Asset> = d.price.clone().into_iter().collect();
+  prices.extend(d.other_prices.clone());
+
+  prices.iter().map(|price| (d.nft_address.clone(), price.info.to_string(), floor_key(price.amount, &k))).collect()
+}
+
+pub fn order_nft_address_idx(d: &Order, k: Vec<u8>) -> (Addr, Vec<u8>) {
+  (d.nft_address.clone(), k)
+}
+
+// highest bidder of english auction
+pub fn bidder_idx(d: &Order, k: Vec<u8>) -> Vec<(Addr, Vec<u8>)> {
+  d.auction_info.iter()
+    .filter_map(|auction_info| auction_info.bidder.clone())
+    .map(|bidder| (bidder, k.clone()))
+    .collect()
+}
+
+// sale listings expiring at height or time
+fn listing_expiration_key(d: &Order, k: &[u8]) -> Option<Vec<u8>> {
+  if !d.is_listing() {
+    return None
+  }
+
+  let expiration = match &d.auction_info {
+    Some(auction_info) => Some(auction_info.expiration),
+    None => d.expiration
+  };
+
+  expiration.and_then(|expiration| expiration_key(&expiration, k))
+}
+
+pub fn order_expiration_idx(d: &Order, k: Vec<u8>) -> Vec<(Addr, Vec<u8>)> {
+  listing_expiration_key(d, &k).map(|key| (d.nft_address.clone(), key)).into_iter().collect()
+}
+
+// expiring listings of all collections
+pub fn order_market_expiration_idx(d: &Order, k: Vec<u8>) -> Vec<Vec<u8>> {
+  listing_expiration_key(d, &k).into_iter().collect()
+}
+
+// sale listings are counted per collection
+pub fn order_count_idx(d: &Order) -> Option<Addr> {
+  if !d.is_listing() {
+    return None
+  }
+
+  Some(d.nft_address.clone())
+}
+
+// every cw721 of the order. cw1155 tokens are fungible, so the same token id can be in many orders
+pub fn order_token_idx(d: &Order) -> Vec<(Addr, String)> {
+  if d.cw1155_info.is_some() {
+    return vec![]
+  }
+
+  d.items().into_iter().map(|item| (item.nft_address, item.token_id)).collect()
+}
+
+// floor index entries read by a sweep or floor price query. skipped expired and private orders are counted too
+pub const MAX_FLOOR_SCAN: usize = 100;
+
+pub fn floor_key(unit_price: Uint128, pk: &[u8]) -> Vec<u8> {
+  let mut key = unit_price.u128().to_be_bytes().to_vec();
+  key.extend(pk);
+
+  key
+}
+
+// height and time expirations are kept apart, so listings expired at a block are a range of each
+pub fn expiration_key(expiration: &Expiration, pk: &[u8]) -> Option<Vec<u8>> {
+  let mut key = match expiration {
+    Expiration::AtHeight(height) => [vec![0u8], height.to_be_bytes().to_vec()].concat(),
+    Expiration::AtTime(time) => [vec![1u8], time.nanos().to_be_bytes().to_vec()].concat(),
+    Expiration::Never {} => return None
+  };
+  key.extend(pk);
+
+  Some(key)
+}
+
+// split last key of floor index into unit price and pk
+pub fn parse_floor_key(key: &[u8]) -> (Uint128, Vec<u8>) {
+  let mut price = [0u8; 16];
+  price.copy_from_slice(&key[..16]);
+
+  (Uint128::from(u128::from_be_bytes(price)), key[16..].to_vec())
+}
+
+// MultiIndex allows only one index value per item. this indexes an item with all values of the index function
+pub struct MultiValueIndex<'a, K, T> {
+  index: fn(&T, Vec<u8>) -> Vec<K>,
+  idx_map: Map<'a, K, u32>
+}
+
+impl<'a, K: PrimaryKey<'a>, T> MultiValueIndex<'a, K, T> {
+  pub fn new(idx_fn: fn(&T, Vec<u8>) -> Vec<K>, idx_namespace: &'a str) -> Self {
+    Self { index: idx_fn, idx_map: Map::new(idx_namespace) }
+  }
+
+  // keys of the prefix are the rest of the index key (pk for buyer index)
+  pub fn prefix(&self, p: K::Prefix) -> Prefix<u32> {
+    self.idx_map.prefix(p)
+  }
+}
+
+impl<'a, K: PrimaryKey<'a>, T: Serialize + DeserializeOwned + Clone> Index<T> for MultiValueIndex<'a, K, T> {
+  fn save(&self, store: &mut dyn Storage, pk: &[u8], data: &T) -> StdResult<()> {
+    for idx in (self.index)(data, pk.to_vec()) {
+      self.idx_map.save(store, idx, &(pk.len() as u32))?;
+    }
+
+    Ok(())
+  }
+
+  fn remove(&self, store: &mut dyn Storage, pk: &[u8], old_data: &T) -> StdResult<()> {
+    for idx in (self.index)(old_data, pk.to_vec()) {
+      self.idx_map.remove(store, idx);
+    }
+
+    Ok(())
+  }
+}
+
+// number of orders per index value and of all indexed orders. replacing an order removes and saves it, so the count is kept
+pub struct CountIndex<'a, K> {
+  index: fn(&Order) -> Option<K>,
+  idx_map: Map<'a, K, u64>,
+  total: Item<'a, u64>
+}
+
+impl<'a, K: PrimaryKey<'a>> CountIndex<'a, K> {
+  pub fn new(idx_fn: fn(&Order) -> Option<K>, idx_namespace: &'a str, total_namespace: &'a str) -> Self {
+    Self { index: idx_fn, idx_map: Map::new(idx_namespace), total: Item::new(total_namespace) }
+  }
+
+  pub fn count(&self, store: &dyn Storage, key: K) -> StdResult<u64> {
+    Ok(self.idx_map.may_load(store, key)?.unwrap_or_default())
+  }
+
+  pub fn total(&self, store: &dyn Storage) -> StdResult<u64> {
+    Ok(self.total.may_load(store)?.unwrap_or_default())
+  }
+}
+
+impl<'a, K: PrimaryKey<'a>> Index<Order> for CountIndex<'a, K> {
+  fn save(&self, store: &mut dyn Storage, _pk: &[u8], data: &Order) -> StdResult<()> {
+    if let Some(key) = (self.index)(data) {
+      self.idx_map.update(store, key, |count| -> StdResult<u64> { Ok(count.unwrap_or_default() + 1) })?;
+      let total = self.total(store)?;
+      self.total.save(store, &(total + 1))?;
+    }
+
+    Ok(())
+  }
+
+  fn remove(&self, store: &mut dyn Storage, _pk: &[u8], old_data: &Order) -> StdResult<()> {
+    let key = match (self.index)(old_data) {
+      Some(key) => key,
+      None => return Ok(())
+    };
+    let count = self.idx_map.may_load(store, key.clone())?.unwrap_or_default().saturating_sub(1);
+    let total = self.total(store)?;
+    self.total.save(store, &total.saturating_sub(1))?;
+
+    if count == 0 {
+      self.idx_map.remove(store, key);
+      Ok(())
+    } else {
+      self.idx_map.save(store, key, &count)
+    }
+  }
+}
+
+// UniqueIndex with many values per order. value is pk of the order
+pub struct UniqueValueIndex<'a, K> {
+  index: fn(&Order) -> Vec<K>,
+  idx_map: Map<'a, K, Vec<u8>>
+}
+
+impl<'a, K: PrimaryKey<'a>> UniqueValueIndex<'a, K> {
+  pub fn new(idx_fn: fn(&Order) -> Vec<K>, idx_namespace: &'a str) -> Self {
+    Self { index: idx_fn, idx_map: Map::new(idx_namespace) }
+  }
+
+  pub fn may_load_pk(&self, store: &dyn Storage, key: K) -> StdResult<Option<Vec<u8>>> {
+    self.idx_map.may_load(store, key)
+  }
+}
+
+impl<'a, K: PrimaryKey<'a>> Index<Order> for UniqueValueIndex<'a, K> {
+  fn save(&self, store: &mut dyn Storage, pk: &[u8], data: &Order) -> StdResult<()> {
+    for idx in (self.index)(data) {
+      self.idx_map.update(store, idx, |existing| match existing {
+        Some(existing) if existing != pk => Err(StdError::generic_err("Violates unique constraint on index")),
+        _ => Ok(pk.to_vec())
+      })?;
+    }
+
+    Ok(())
+  }
+
+  fn remove(&self, store: &mut dyn Storage, _pk: &[u8], old_data: &Order) -> StdResult<()> {
+    for idx in (self.index)(old_data) {
+      self.idx_map.remove(store, idx);
+    }
+
+    Ok(())
+  }
+}
 
 impl<'a> IndexList<Order> for OrderIndexes<'a> {
   fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Order>> + '_> {
-    let v: Vec<&dyn Index<Order>> = vec![&self.seller_address];
+    let v: Vec<&dyn Index<Order>> = vec![&self.seller_address, &self.buyer, &self.floor, &self.nft_address, &self.token, &self.bidder, &self.expiration, &self.market_expiration, &self.count];
+    Box::new(v.into_iter())
+  }
+}
+
+pub struct OfferIndexes<'a> {
+  pub nft_address: MultiIndex<'a, (Addr, Vec<u8>), Offer>,
+  pub token: MultiIndex<'a, (Addr, String, Vec<u8>), Offer>,
+  pub offerer: MultiIndex<'a, (Addr, Vec<u8>), Offer>
+}
+
+pub fn offer_nft_address_idx(d: &Offer, k: Vec<u8>) -> (Addr, Vec<u8>) {
+  (d.nft_address.clone(), k)
+}
+
+// collection offers are indexed with empty token id
+pub fn offer_token_idx(d: &Offer, k: Vec<u8>) -> (Addr, String, Vec<u8>) {
+  (d.nft_address.clone(), d.token_id.clone().unwrap_or_default(), k)
+}
+
+pub fn offerer_idx(d: &Offer, k: Vec<u8>) -> (Addr, Vec<u8>) {
+  (d.offerer.clone(), k)
+}
+
+impl<'a> IndexList<Offer> for OfferIndexes<'a> {
+  fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Offer>> + '_> {
+    let v: Vec<&dyn Index<Offer>> = vec![&self.nft_address, &self.token, &self.offerer];
+    Box::new(v.into_iter())
+  }
+}
+
+pub struct OutbidIndexes<'a> {
+  pub order: MultiValueIndex<'a, (U64Key, Addr), Outbid>,
+  pub recent: MultiValueIndex<'a, (Addr, Vec<u8>), Outbid>
+}
+
+// bidders outbid on the auction
+pub fn outbid_order_idx(d: &Outbid, _k: Vec<u8>) -> Vec<(U64Key, Addr)> {
+  vec![(U64Key::new(d.order_id), d.bidder.clone())]
+}
+
+// outbids of the bidder sorted by height
+pub fn outbid_recent_idx(d: &Outbid, _k: Vec<u8>) -> Vec<(Addr, Vec<u8>)> {
+  vec![(d.bidder.clone(), outbid_key(d.height, d.order_id))]
+}
+
+pub fn outbid_key(height: u64, order_id: u64) -> Vec<u8> {
+  let mut key = height.to_be_bytes().to_vec();
+  key.extend(order_id.to_be_bytes());
+
+  key
+}
+
+// order id is the last 8 bytes of recent index key
+pub fn parse_outbid_key(key: &[u8]) -> u64 {
+  let mut order_id = [0u8; 8];
+  order_id.copy_from_slice(&key[8..]);
+
+  u64::from_be_bytes(order_id)
+}
+
+impl<'a> IndexList<Outbid> for OutbidIndexes<'a> {
+  fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Outbid>> + '_> {
+    let v: Vec<&dyn Index<Outbid>> = vec![&self.order, &self.recent];
+    Box::new(v.into_iter())
+  }
+}
+
+pub struct TradeIndexes<'a> {
+  pub nft_address: MultiIndex<'a, (Addr, Vec<u8>), Trade>,
+  pub token: MultiValueIndex<'a, (Addr, String, Vec<u8>), Trade>,
+  pub seller: MultiIndex<'a, (Addr, Vec<u8>), Trade>,
+  pub buyer: MultiIndex<'a, (Addr, Vec<u8>), Trade>
+}
+
+pub fn trade_nft_address_idx(d: &Trade, k: Vec<u8>) -> (Addr, Vec<u8>) {
+  (d.nft_address.clone(), k)
+}
+
+// every nft of the trade
+pub fn trade_token_idx(d: &Trade, k: Vec<u8>) -> Vec<(Addr, String, Vec<u8>)> {
+  d.items().into_iter().map(|item| (item.nft_address, item.token_id, k.clone())).collect()
+}
+
+pub fn trade_seller_idx(d: &Trade, k: Vec<u8>) -> (Addr, Vec<u8>) {
+  (d.seller.clone(), k)
+}
+
+pub fn trade_buyer_idx(d: &Trade, k: Vec<u8>) -> (Addr, Vec<u8>) {
+  (d.buyer.clone(), k)
+}
+
+impl<'a> IndexList<Trade> for TradeIndexes<'a> {
+  fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Trade>> + '_> {
+    let v: Vec<&dyn Index<Trade>> = vec![&self.nft_address, &self.token, &self.seller, &self.buyer];
     Box::new(v.into_iter())
   }
 }
\ No newline at end of file
@@ -6,8 +6,8 @@ use cw20::Cw20ReceiveMsg;
 use cw0::Expiration;
 
 use crate::{
-  state::{AuctionInfo, MarketContract, Royalty},
-  msgs::{InstantiateMsg, ExecuteMsg, Cw721HookMsg, QueryMsg},
+  state::{AuctionExtension, AuctionInfo, MarketContract, Order, Outbid, Royalty},
+  msgs::{InstantiateMsg, ExecuteMsg, Cw721HookMsg, Cw20HookMsg, QueryMsg},
   error::ContractError,
   asset::{Asset, AssetInfo},
   testing::mock_querier::mock_dependencies
@@ -28,6 +28,10 @@ fn only_auction_order_test() {
     max_auction_duration_block: 100,
     max_auction_duration_second: 1000,
     auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
   };
 
   let info = mock_info("owner", &[]);
@@ -62,6 +66,9 @@ fn only_auction_order_test() {
     nft_address: "spaceship".to_string(),
     support_assets: vec![uusd.clone(), mir.clone()],
     royalties: vec![nft_designer_royalty.clone(), nft_pm_royalty.clone()],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
   };
 
   let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
@@ -81,7 +88,10 @@ fn only_auction_order_test() {
   let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder { 
     start_price: start_price.clone(),
     expiration: Expiration::AtHeight(12_000),
-    fixed_price: None
+    fixed_price: None,
+    reserve_price: None,
+    second_price: None,
+    buyers: None
   };
 
   let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
@@ -95,7 +105,7 @@ fn only_auction_order_test() {
   let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
 
   match res {
-    Err(ContractError::Expired {}) => assert!(true),
+    Err(ContractError::Expired {}) => {}
     _ => panic!("Must return token mismatch error"),
   }
 
@@ -103,7 +113,10 @@ fn only_auction_order_test() {
   let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder { 
     start_price: start_price.clone(),
     expiration: Expiration::AtTime(Timestamp::from_nanos(1_571_797_419_879_000_000)),
-    fixed_price: None
+    fixed_price: None,
+    reserve_price: None,
+    second_price: None,
+    buyers: None
   };
 
   let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
@@ -117,7 +130,7 @@ fn only_auction_order_test() {
   let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
 
   match res {
-    Err(ContractError::Expired {}) => assert!(true),
+    Err(ContractError::Expired {}) => {}
     _ => panic!("Must return token mismatch error"),
   }
 
@@ -130,7 +143,10 @@ fn only_auction_order_test() {
   let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder { 
     start_price: start_price.clone(),
     expiration: Expiration::AtHeight(13_000),
-    fixed_price: None
+    fixed_price: None,
+    reserve_price: None,
+    second_price: None,
+    buyers: None
   };
 
   let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
@@ -144,7 +160,7 @@ fn only_auction_order_test() {
   let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
 
   match res {
-    Err(ContractError::Unsupport {}) => assert!(true),
+    Err(ContractError::Unsupport {}) => {}
     _ => panic!("Must return unsupport error"),
   }
 
@@ -157,7 +173,10 @@ fn only_auction_order_test() {
   let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder { 
     start_price: start_price.clone(),
     expiration: Expiration::AtHeight(13_000),
-    fixed_price: None
+    fixed_price: None,
+    reserve_price: None,
+    second_price: None,
+    buyers: None
   };
 
   let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
@@ -171,7 +190,7 @@ fn only_auction_order_test() {
   let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
   
   match res {
-    Err(ContractError::MaxDuration {}) => assert!(true),
+    Err(ContractError::MaxDuration {}) => {}
     _ => panic!("Must return max duration error"),
   }
 
@@ -184,7 +203,10 @@ fn only_auction_order_test() {
   let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder { 
     start_price: start_price.clone(),
     expiration: Expiration::AtTime(Timestamp::from_nanos(1_571_799_419_879_305_533)),
-    fixed_price: None
+    fixed_price: None,
+    reserve_price: None,
+    second_price: None,
+    buyers: None
   };
 
   let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
@@ -198,7 +220,7 @@ fn only_auction_order_test() {
   let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
 
   match res {
-    Err(ContractError::MaxDuration {}) => assert!(true),
+    Err(ContractError::MaxDuration {}) => {}
     _ => panic!("Must return max duration error"),
   }
 
@@ -211,7 +233,10 @@ fn only_auction_order_test() {
   let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder { 
     start_price: start_price.clone(),
     expiration: Expiration::AtHeight(12_400),
-    fixed_price: None
+    fixed_price: None,
+    reserve_price: None,
+    second_price: None,
+    buyers: None
   };
 
   let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
@@ -237,6 +262,9 @@ fn only_auction_order_test() {
       highest_bid: start_price.clone(),
       bidder: None,
       expiration: Expiration::AtHeight(12_400),
+      runner_up_bid: None,
+      second_price: false,
+      referrer: None,
     }),
     order.auction_info
   );
@@ -252,7 +280,8 @@ fn only_auction_order_test() {
 
   let bid_msg = ExecuteMsg::Bid {
     order_id: 1,
-    bid_price: bid_price,
+    bid_price,
+    referrer: None
   };
 
   let info = mock_info("bidder", &[Coin{ denom: "uusd".to_string(), amount: Uint128::from(101000000u128)}]);
@@ -262,7 +291,7 @@ fn only_auction_order_test() {
   let res = market.execute(deps.as_mut(), mock_env.clone(), info, bid_msg);
 
   match res {
-    Err(ContractError::MinPrice { min_bid_amount: _ }) => assert!(true),
+    Err(ContractError::MinPrice { min_bid_amount: _ }) => {}
     _ => panic!("Must return min price error"),
   }
 
@@ -274,7 +303,8 @@ fn only_auction_order_test() {
 
   let bid_msg = ExecuteMsg::Bid {
     order_id: 1,
-    bid_price: bid_price,
+    bid_price,
+    referrer: None
   };
 
   let info = mock_info("bidder", &[Coin{ denom: "uluna".to_string(), amount:  Uint128::from(120000000u128)}]);
@@ -284,7 +314,7 @@ fn only_auction_order_test() {
   let res = market.execute(deps.as_mut(), mock_env.clone(), info, bid_msg);
 
   match res {
-    Err(ContractError::AssetInfoMismatch {}) => assert!(true),
+    Err(ContractError::AssetInfoMismatch {}) => {}
     _ => panic!("Must return asset info mismatch error"),
   }
 
@@ -296,7 +326,8 @@ fn only_auction_order_test() {
 
   let bid_msg = ExecuteMsg::Bid {
     order_id: 1,
-    bid_price: bid_price,
+    bid_price,
+    referrer: None
   };
 
   let info = mock_info("bidder", &[Coin{ denom: "uusd".to_string(), amount:  Uint128::from(120000000u128)}]);
@@ -306,7 +337,7 @@ fn only_auction_order_test() {
   let res = market.execute(deps.as_mut(), mock_env.clone(), info, bid_msg);
 
   match res {
-    Err(ContractError::Std(_) ) => assert!(true),
+    Err(ContractError::Std(_) ) => {}
     _ => panic!("Must return std error"),
   }
 
@@ -318,7 +349,8 @@ fn only_auction_order_test() {
 
   let bid_msg = ExecuteMsg::Bid {
     order_id: 1,
-    bid_price: bid_price,
+    bid_price,
+    referrer: None
   };
 
   let info = mock_info("bidder", &[Coin{ denom: "uusd".to_string(), amount:  Uint128::from(120000000u128)}]);
@@ -328,7 +360,7 @@ fn only_auction_order_test() {
   let res = market.execute(deps.as_mut(), mock_env.clone(), info, bid_msg);
 
   match res {
-    Err(ContractError::Expired {} ) => assert!(true),
+    Err(ContractError::Expired {} ) => {}
     _ => panic!("Must return expired error"),
   }
 
@@ -341,6 +373,7 @@ fn only_auction_order_test() {
   let bid_msg = ExecuteMsg::Bid {
     order_id: 1,
     bid_price: bid_price.clone(),
+    referrer: None
   };
 
   let info = mock_info("bidder1", &[Coin{ denom: "uusd".to_string(), amount:  Uint128::from(120000000u128)}]);
@@ -360,6 +393,9 @@ fn only_auction_order_test() {
       highest_bid: bid_price.clone(),
       bidder: Some(Addr::unchecked("bidder1")),
       expiration: Expiration::AtHeight(12_400),
+      runner_up_bid: Some(start_price.clone()),
+      second_price: false,
+      referrer: None,
     }),
     order.auction_info
   );
@@ -379,6 +415,7 @@ fn only_auction_order_test() {
   let bid_msg = ExecuteMsg::Bid {
     order_id: 1,
     bid_price: bid_price.clone(),
+    referrer: None
   };
 
   let info = mock_info("bidder2", &[Coin{ denom: "uusd".to_string(), amount:  Uint128::from(150000000u128)}]);
@@ -401,6 +438,9 @@ fn only_auction_order_test() {
       highest_bid: bid_price.clone(),
       bidder: Some(Addr::unchecked("bidder2")),
       expiration: Expiration::AtHeight(12_400),
+      runner_up_bid: Some(Asset { info: uusd.clone(), amount: Uint128::from(120000000u128) }),
+      second_price: false,
+      referrer: None,
     }),
     order.auction_info
   );
@@ -414,6 +454,7 @@ fn only_auction_order_test() {
   let bid_msg = ExecuteMsg::Bid {
     order_id: 1,
     bid_price: bid_price.clone(),
+    referrer: None
   };
 
   let info = mock_info("bidder2", &[Coin{ denom: "uusd".to_string(), amount:  Uint128::from(200000000u128)}]);
@@ -423,7 +464,7 @@ fn only_auction_order_test() {
   let res = market.execute(deps.as_mut(), mock_env.clone(), info, bid_msg);
 
   match res {
-    Err(ContractError::Expired {} ) => assert!(true),
+    Err(ContractError::Expired {} ) => {}
     _ => panic!("Must return expired error"),
   }
 
@@ -439,7 +480,7 @@ fn only_auction_order_test() {
   let res = market.execute(deps.as_mut(), mock_env.clone(), info, execute_auction_msg);
 
   match res {
-    Err(ContractError::NotExpired {} ) => assert!(true),
+    Err(ContractError::NotExpired {} ) => {}
     _ => panic!("Must return not expired error"),
   }
 
@@ -506,7 +547,10 @@ fn only_auction_order_test() {
   let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder { 
     start_price: start_price.clone(),
     expiration: Expiration::AtHeight(12_400),
-    fixed_price: None
+    fixed_price: None,
+    reserve_price: None,
+    second_price: None,
+    buyers: None
   };
 
   let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
@@ -558,7 +602,7 @@ fn only_auction_order_test() {
     amount: Uint128::from(100000000u128)
   };
 
-  let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder { price: price.clone() };
+  let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder { price: price.clone(), expiration: None, other_prices: None, buyers: None };
 
   let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
     sender: seller.clone(),
@@ -579,6 +623,7 @@ fn only_auction_order_test() {
   let bid_msg = ExecuteMsg::Bid {
     order_id: 3,
     bid_price: bid_price.clone(),
+    referrer: None
   };
 
   let info = mock_info("bidder1", &[Coin{ denom: "uusd".to_string(), amount:  Uint128::from(120000000u128)}]);
@@ -586,7 +631,7 @@ fn only_auction_order_test() {
   let res = market.execute(deps.as_mut(), mock_env.clone(), info, bid_msg);
   
   match res {
-    Err(ContractError::NotAuction {} ) => assert!(true),
+    Err(ContractError::NotAuction {} ) => {}
     _ => panic!("Must return not auction error"),
   }
 
@@ -600,7 +645,7 @@ fn only_auction_order_test() {
   let res = market.execute(deps.as_mut(), mock_env.clone(), info, execute_auction_msg);
 
   match res {
-    Err(ContractError::NotAuction {} ) => assert!(true),
+    Err(ContractError::NotAuction {} ) => {}
     _ => panic!("Must return not auction error"),
   }
 }
@@ -618,6 +663,10 @@ fn auction_cancel_test() {
     max_auction_duration_block: 100,
     max_auction_duration_second: 1000,
     auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
   };
 
   let info = mock_info("owner", &[]);
@@ -651,6 +700,9 @@ fn auction_cancel_test() {
     nft_address: "spaceship".to_string(),
     support_assets: vec![uusd.clone(), mir.clone()],
     royalties: vec![nft_designer_royalty.clone(), nft_pm_royalty.clone()],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
   };
 
   let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
@@ -675,7 +727,10 @@ fn auction_cancel_test() {
   let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder { 
     start_price: start_price.clone(),
     expiration: Expiration::AtHeight(12_400),
-    fixed_price: Some(fixed_price)
+    fixed_price: Some(fixed_price),
+    reserve_price: None,
+    second_price: None,
+    buyers: None
   };
 
   let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
@@ -733,7 +788,10 @@ fn auction_cancel_test() {
   let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder { 
     start_price: start_price.clone(),
     expiration: Expiration::AtHeight(12_400),
-    fixed_price: Some(fixed_price)
+    fixed_price: Some(fixed_price),
+    reserve_price: None,
+    second_price: None,
+    buyers: None
   };
 
   let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
@@ -757,6 +815,7 @@ fn auction_cancel_test() {
   let bid_msg = ExecuteMsg::Bid {
     order_id: 2,
     bid_price: bid_price.clone(),
+    referrer: None
   };
 
   let info = mock_info("bidder1", &[Coin{ denom: "uusd".to_string(), amount:  Uint128::from(120000000u128)}]);
@@ -775,7 +834,7 @@ fn auction_cancel_test() {
   let res = market.execute(deps.as_mut(), mock_env.clone(), info, cancel_msg);
   
   match res {
-    Err(ContractError::CancelFeeMismatch { fee_asset: _ } ) => assert!(true),
+    Err(ContractError::CancelFeeMismatch { fee_asset: _ } ) => {}
     _ => panic!("Must return cancel fee mismatch error"),
   }
 
@@ -819,7 +878,10 @@ fn auction_cancel_test() {
   let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder { 
     start_price: start_price.clone(),
     expiration: Expiration::AtHeight(12_400),
-    fixed_price: Some(fixed_price)
+    fixed_price: Some(fixed_price),
+    reserve_price: None,
+    second_price: None,
+    buyers: None
   };
 
   let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
@@ -841,10 +903,9 @@ fn auction_cancel_test() {
   let bid_msg = ExecuteMsg::Bid {
     order_id: 3,
     bid_price: bid_price.clone(),
+    referrer: None
   };
 
-  let mut mock_env = mock_env;
-
   mock_env.block.height = 12370;
 
   let info = mock_info("mir_addr", &[]);
@@ -855,7 +916,7 @@ fn auction_cancel_test() {
     msg: to_binary(&bid_msg).unwrap()
   };
 
-  let _res = market.execute(deps.as_mut(), mock_env.clone(), info, ExecuteMsg::Receive(receive_msg)).unwrap();
+  let _res = market.execute(deps.as_mut(), mock_env.clone(), info, ExecuteMsg::Receive(receive_msg.into())).unwrap();
 
   // cancel auction
   let cancel_msg = ExecuteMsg::CancelOrder {
@@ -891,7 +952,13 @@ fn auction_cancel_test() {
     min_increase: None,
     max_auction_duration_block: None,
     max_auction_duration_second: None,
-    auction_cancel_fee_rate: Some(Decimal::from_ratio(5u128, 1000u128))
+    auction_cancel_fee_rate: Some(Decimal::from_ratio(5u128, 1000u128)),
+    auction_extension_block: None,
+    auction_extension_second: None,
+    protocol_fee_rate: None,
+    treasury: None,
+    fee_discount: None,
+    referral_share: None,
   };
 
   let _res = market.execute(deps.as_mut(), mock_env.clone(), info, update_config_msg).unwrap();
@@ -910,7 +977,10 @@ fn auction_cancel_test() {
   let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder { 
     start_price: start_price.clone(),
     expiration: Expiration::AtHeight(12_400),
-    fixed_price: Some(fixed_price)
+    fixed_price: Some(fixed_price),
+    reserve_price: None,
+    second_price: None,
+    buyers: None
   };
 
   let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
@@ -932,6 +1002,7 @@ fn auction_cancel_test() {
   let bid_msg = ExecuteMsg::Bid {
     order_id: 4,
     bid_price: bid_price.clone(),
+    referrer: None
   };
 
   let info = mock_info("bidder1", &[Coin{ denom: "uusd".to_string(), amount:  Uint128::from(120000000u128)}]);
@@ -950,7 +1021,7 @@ fn auction_cancel_test() {
   let res = market.execute(deps.as_mut(), mock_env.clone(), info, cancel_msg);
   
   match res {
-    Err(ContractError::CancelFeeMismatch { fee_asset: _ } ) => assert!(true),
+    Err(ContractError::CancelFeeMismatch { fee_asset: _ } ) => {}
     _ => panic!("Must return cancel fee mismatch error"),
   }
 
@@ -959,7 +1030,7 @@ fn auction_cancel_test() {
     order_id: 4
   };
 
-  let fee_asset: Asset = from_binary(&market.query(deps.as_ref(), QueryMsg::CancelFee { order_id: 4 }).unwrap()).unwrap();
+  let fee_asset: Asset = from_binary(&market.query(deps.as_ref(), mock_env.clone(), QueryMsg::CancelFee { order_id: 4 }).unwrap()).unwrap();
 
   let refund_asset: Asset = Asset {
     info: fee_asset.info.clone(),
@@ -1001,7 +1072,10 @@ fn auction_cancel_test() {
   let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder { 
     start_price: start_price.clone(),
     expiration: Expiration::AtHeight(12_400),
-    fixed_price: Some(fixed_price)
+    fixed_price: Some(fixed_price),
+    reserve_price: None,
+    second_price: None,
+    buyers: None
   };
 
   let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
@@ -1023,10 +1097,9 @@ fn auction_cancel_test() {
   let bid_msg = ExecuteMsg::Bid {
     order_id: 5,
     bid_price: bid_price.clone(),
+    referrer: None
   };
 
-  let mut mock_env = mock_env;
-
   mock_env.block.height = 12370;
 
   let info = mock_info("mir_addr", &[]);
@@ -1037,7 +1110,7 @@ fn auction_cancel_test() {
     msg: to_binary(&bid_msg).unwrap()
   };
 
-  let _res = market.execute(deps.as_mut(), mock_env.clone(), info, ExecuteMsg::Receive(receive_msg)).unwrap();
+  let _res = market.execute(deps.as_mut(), mock_env.clone(), info, ExecuteMsg::Receive(receive_msg.into())).unwrap();
 
   // try cancel auction without send
   let cancel_msg = ExecuteMsg::CancelOrder {
@@ -1049,7 +1122,7 @@ fn auction_cancel_test() {
   let res = market.execute(deps.as_mut(), mock_env.clone(), info, cancel_msg);
 
   match res {
-    Err(ContractError::CancelFeeMismatch { fee_asset: _ } ) => assert!(true),
+    Err(ContractError::CancelFeeMismatch { fee_asset: _ } ) => {}
     _ => panic!("Must return cancel fee mismatch error"),
   }
 
@@ -1057,7 +1130,7 @@ fn auction_cancel_test() {
     order_id: 5
   };
 
-  let fee_asset: Asset = from_binary(&market.query(deps.as_ref(), QueryMsg::CancelFee { order_id: 5 }).unwrap()).unwrap();
+  let fee_asset: Asset = from_binary(&market.query(deps.as_ref(), mock_env.clone(), QueryMsg::CancelFee { order_id: 5 }).unwrap()).unwrap();
 
   let refund_asset: Asset = Asset {
     info: fee_asset.info.clone(),
@@ -1072,7 +1145,7 @@ fn auction_cancel_test() {
 
   let info = mock_info("mir_addr", &[]);
 
-  let res = market.execute(deps.as_mut(), mock_env.clone(), info, ExecuteMsg::Receive(receive_msg)).unwrap();
+  let res = market.execute(deps.as_mut(), mock_env.clone(), info, ExecuteMsg::Receive(receive_msg.into())).unwrap();
 
   assert_eq!(
     res.messages,
@@ -1104,7 +1177,11 @@ fn auction_with_fixed_price_order_test() {
     min_increase: Decimal::from_ratio(10u128, 100u128),
     max_auction_duration_block: 100,
     max_auction_duration_second: 1000,
-    auction_cancel_fee_rate: Decimal::from_ratio(5u128, 1000u128)
+    auction_cancel_fee_rate: Decimal::from_ratio(5u128, 1000u128),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
   };
 
   let info = mock_info("owner", &[]);
@@ -1138,6 +1215,9 @@ fn auction_with_fixed_price_order_test() {
     nft_address: "spaceship".to_string(),
     support_assets: vec![uusd.clone(), mir.clone()],
     royalties: vec![nft_designer_royalty.clone(), nft_pm_royalty.clone()],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
   };
 
   let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
@@ -1162,7 +1242,10 @@ fn auction_with_fixed_price_order_test() {
   let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder { 
     start_price: start_price.clone(),
     expiration: Expiration::AtHeight(12_400),
-    fixed_price: Some(fixed_price)
+    fixed_price: Some(fixed_price),
+    reserve_price: None,
+    second_price: None,
+    buyers: None
   };
 
   let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
@@ -1176,7 +1259,7 @@ fn auction_with_fixed_price_order_test() {
   let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
 
   match res {
-    Err(ContractError::AssetInfoMismatch {} ) => assert!(true),
+    Err(ContractError::AssetInfoMismatch {} ) => {}
     _ => panic!("Must return asset info mismatch error"),
   }
 
@@ -1194,7 +1277,10 @@ fn auction_with_fixed_price_order_test() {
   let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder { 
     start_price: start_price.clone(),
     expiration: Expiration::AtHeight(12_400),
-    fixed_price: Some(fixed_price.clone())
+    fixed_price: Some(fixed_price.clone()),
+    reserve_price: None,
+    second_price: None,
+    buyers: None
   };
 
   let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
@@ -1220,12 +1306,15 @@ fn auction_with_fixed_price_order_test() {
       highest_bid: start_price.clone(),
       bidder: None,
       expiration: Expiration::AtHeight(12_400),
+      runner_up_bid: None,
+      second_price: false,
+      referrer: None,
     }),
     order.auction_info
   );
 
   // execute order before bid
-  let execute_msg = ExecuteMsg::ExecuteOrder { order_id: 1 };
+  let execute_msg = ExecuteMsg::ExecuteOrder { order_id: 1, amount: None, referrer: None };
 
   let info = mock_info("mir_addr", &[]);
 
@@ -1235,7 +1324,7 @@ fn auction_with_fixed_price_order_test() {
     msg: to_binary(&execute_msg).unwrap()
   };
 
-  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg)).unwrap();
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into())).unwrap();
 
     // royalty amounts
     let designer_royalty_asset = Asset {
@@ -1288,7 +1377,10 @@ fn auction_with_fixed_price_order_test() {
   let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder { 
     start_price: start_price.clone(),
     expiration: Expiration::AtHeight(12_400),
-    fixed_price: Some(fixed_price.clone())
+    fixed_price: Some(fixed_price.clone()),
+    reserve_price: None,
+    second_price: None,
+    buyers: None
   };
 
   let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
@@ -1310,6 +1402,7 @@ fn auction_with_fixed_price_order_test() {
   let bid_msg = ExecuteMsg::Bid {
     order_id: 2,
     bid_price: bid_price.clone(),
+    referrer: None
   };
 
   let mut mock_env = mock_env();
@@ -1324,10 +1417,10 @@ fn auction_with_fixed_price_order_test() {
     msg: to_binary(&bid_msg).unwrap()
   };
 
-  let _res = market.execute(deps.as_mut(), mock_env.clone(), info, ExecuteMsg::Receive(receive_msg)).unwrap();
+  let _res = market.execute(deps.as_mut(), mock_env.clone(), info, ExecuteMsg::Receive(receive_msg.into())).unwrap();
 
   // execute order
-  let execute_msg = ExecuteMsg::ExecuteOrder { order_id: 2 };
+  let execute_msg = ExecuteMsg::ExecuteOrder { order_id: 2, amount: None, referrer: None };
 
   let info = mock_info("mir_addr", &[]);
 
@@ -1337,7 +1430,7 @@ fn auction_with_fixed_price_order_test() {
     msg: to_binary(&execute_msg).unwrap()
   };
 
-  let res = market.execute(deps.as_mut(), mock_env, info, ExecuteMsg::Receive(receive_msg)).unwrap();
+  let res = market.execute(deps.as_mut(), mock_env, info, ExecuteMsg::Receive(receive_msg.into())).unwrap();
 
     // royalty amounts
     let designer_royalty_asset = Asset {
@@ -1377,4 +1470,750 @@ fn auction_with_fixed_price_order_test() {
       SubMsg::new(remain_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("seller")).unwrap()),
     ]
   );
+}
+#[test]
+fn auction_soft_close_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  // bid within 10 blocks or 60 seconds before expiration extends the auction
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 10,
+    auction_extension_second: 60,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  // some assetinfos
+  let uusd: AssetInfo = AssetInfo::NativeToken { denom: "uusd".to_string()};
+
+  // add collections
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: vec![uusd.clone()],
+    royalties: vec![],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  // planet overrides extension of config
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "planet".to_string(),
+    support_assets: vec![uusd.clone()],
+    royalties: vec![],
+    auction_extension: Some(AuctionExtension { block: 20, second: 0 }),
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  let seller = "seller".to_string();
+
+  let start_price = Asset{
+    info: uusd.clone(),
+    amount: Uint128::from(100000000u128)
+  };
+
+  // make height based auction
+  let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder {
+    start_price: start_price.clone(),
+    expiration: Expiration::AtHeight(12_400),
+    fixed_price: None,
+    reserve_price: None,
+    second_price: None,
+    buyers: None
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_auction_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  // bid before the window. not extended
+  let bid_msg = ExecuteMsg::Bid {
+    order_id: 1,
+    bid_price: Asset { info: uusd.clone(), amount: Uint128::from(110000000u128) },
+    referrer: None
+  };
+
+  let info = mock_info("bidder1", &[Coin{ denom: "uusd".to_string(), amount: Uint128::from(110000000u128) }]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, bid_msg).unwrap();
+
+  let order = market.orders.load(&deps.storage, U64Key::new(1)).unwrap();
+
+  assert_eq!(Expiration::AtHeight(12_400), order.auction_info.unwrap().expiration);
+
+  // bid in the window. extended to 10 blocks from now
+  let mut mock_env = mock_env();
+  mock_env.block.height = 12_395;
+
+  let bid_msg = ExecuteMsg::Bid {
+    order_id: 1,
+    bid_price: Asset { info: uusd.clone(), amount: Uint128::from(121000000u128) },
+    referrer: None
+  };
+
+  let info = mock_info("bidder2", &[Coin{ denom: "uusd".to_string(), amount: Uint128::from(121000000u128) }]);
+
+  let _res = market.execute(deps.as_mut(), mock_env.clone(), info, bid_msg).unwrap();
+
+  let order = market.orders.load(&deps.storage, U64Key::new(1)).unwrap();
+
+  assert_eq!(Expiration::AtHeight(12_405), order.auction_info.unwrap().expiration);
+
+  // can not execute auction at the original expiration
+  mock_env.block.height = 12_400;
+
+  let res = market.execute(deps.as_mut(), mock_env.clone(), mock_info("seller", &[]), ExecuteMsg::ExecuteAuction { order_id: 1 });
+
+  match res {
+    Err(ContractError::NotExpired {}) => {}
+    _ => panic!("Must return not expired error"),
+  }
+
+  // make time based auction
+  mock_env.block.height = 12_345;
+  let now = mock_env.block.time.seconds();
+
+  let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder {
+    start_price: start_price.clone(),
+    expiration: Expiration::AtTime(Timestamp::from_seconds(now + 500)),
+    fixed_price: None,
+    reserve_price: None,
+    second_price: None,
+    buyers: None
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no2".to_string(),
+    msg: to_binary(&make_auction_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env.clone(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  // bid 20 seconds before expiration. extended to 60 seconds from now
+  mock_env.block.time = Timestamp::from_seconds(now + 480);
+
+  let bid_msg = ExecuteMsg::Bid {
+    order_id: 2,
+    bid_price: Asset { info: uusd.clone(), amount: Uint128::from(110000000u128) },
+    referrer: None
+  };
+
+  let info = mock_info("bidder1", &[Coin{ denom: "uusd".to_string(), amount: Uint128::from(110000000u128) }]);
+
+  let _res = market.execute(deps.as_mut(), mock_env.clone(), info, bid_msg).unwrap();
+
+  let order = market.orders.load(&deps.storage, U64Key::new(2)).unwrap();
+
+  assert_eq!(Expiration::AtTime(Timestamp::from_seconds(now + 540)), order.auction_info.unwrap().expiration);
+
+  // make auction of collection that overrides extension
+  mock_env.block.time = Timestamp::from_seconds(now);
+  let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder {
+    start_price: start_price.clone(),
+    expiration: Expiration::AtHeight(12_400),
+    fixed_price: None,
+    reserve_price: None,
+    second_price: None,
+    buyers: None
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_auction_order_msg).unwrap(),
+  };
+
+  let info = mock_info("planet", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env.clone(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  // bid 15 blocks before expiration. extended to 20 blocks from now
+  mock_env.block.height = 12_385;
+
+  let bid_msg = ExecuteMsg::Bid {
+    order_id: 3,
+    bid_price: Asset { info: uusd.clone(), amount: Uint128::from(110000000u128) },
+    referrer: None
+  };
+
+  let info = mock_info("bidder1", &[Coin{ denom: "uusd".to_string(), amount: Uint128::from(110000000u128) }]);
+
+  let _res = market.execute(deps.as_mut(), mock_env, info, bid_msg).unwrap();
+
+  let order = market.orders.load(&deps.storage, U64Key::new(3)).unwrap();
+
+  assert_eq!(Expiration::AtHeight(12_405), order.auction_info.unwrap().expiration);
+}
+
+#[test]
+fn auction_reserve_price_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  // need tax querier
+  deps.querier.with_tax(
+    Decimal::from_ratio(1u128, 100u128),
+    &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+  );
+
+  // some assetinfos
+  let uusd: AssetInfo = AssetInfo::NativeToken { denom: "uusd".to_string()};
+  let mir: AssetInfo = AssetInfo::Token { contract_addr: "mir_addr".to_string()};
+
+  // add collection
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: vec![uusd.clone(), mir.clone()],
+    royalties: vec![],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  let seller = "seller".to_string();
+
+  let start_price = Asset{
+    info: uusd.clone(),
+    amount: Uint128::from(100000000u128)
+  };
+
+  let reserve_price = Asset{
+    info: uusd.clone(),
+    amount: Uint128::from(150000000u128)
+  };
+
+  // try to make auction with reserve price of another asset
+  let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder {
+    start_price: start_price.clone(),
+    expiration: Expiration::AtHeight(12_400),
+    fixed_price: None,
+    reserve_price: Some(Asset { info: mir.clone(), amount: Uint128::from(150000000u128) }),
+    second_price: None,
+    buyers: None
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_auction_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
+
+  match res {
+    Err(ContractError::AssetInfoMismatch {}) => {}
+    _ => panic!("Must return asset info mismatch error"),
+  }
+
+  // try to make auction with reserve price higher than fixed price
+  let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder {
+    start_price: start_price.clone(),
+    expiration: Expiration::AtHeight(12_400),
+    fixed_price: Some(Asset { info: uusd.clone(), amount: Uint128::from(120000000u128) }),
+    reserve_price: Some(reserve_price.clone()),
+    second_price: None,
+    buyers: None
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_auction_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
+
+  match res {
+    Err(ContractError::InvalidReservePrice {}) => {}
+    _ => panic!("Must return invalid reserve price error"),
+  }
+
+  // make auction with reserve price
+  let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder {
+    start_price: start_price.clone(),
+    expiration: Expiration::AtHeight(12_400),
+    fixed_price: None,
+    reserve_price: Some(reserve_price.clone()),
+    second_price: None,
+    buyers: None
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_auction_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  // try to move start price to another asset than reserve price
+  let update_order_msg = ExecuteMsg::UpdateOrder {
+    order_id: 1,
+    price: Some(Asset { info: mir.clone(), amount: Uint128::from(100000000u128) }),
+    fixed_price: None,
+    expiration: None
+  };
+
+  let info = mock_info("seller", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, update_order_msg);
+
+  match res {
+    Err(ContractError::AssetInfoMismatch {}) => {}
+    _ => panic!("Must return asset info mismatch error"),
+  }
+
+  // no bid yet
+  let reserve_met: bool = from_binary(&market.query(deps.as_ref(), mock_env(), QueryMsg::ReserveMet { order_id: 1 }).unwrap()).unwrap();
+
+  assert!(!reserve_met);
+
+  // bid lower than reserve price
+  let bid_msg = ExecuteMsg::Bid {
+    order_id: 1,
+    bid_price: Asset { info: uusd.clone(), amount: Uint128::from(110000000u128) },
+    referrer: None
+  };
+
+  let info = mock_info("bidder1", &[Coin{ denom: "uusd".to_string(), amount: Uint128::from(110000000u128) }]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, bid_msg).unwrap();
+
+  let reserve_met: bool = from_binary(&market.query(deps.as_ref(), mock_env(), QueryMsg::ReserveMet { order_id: 1 }).unwrap()).unwrap();
+
+  assert!(!reserve_met);
+
+  // execute auction. nft returns to seller and bid is refunded
+  let mut mock_env = mock_env();
+  mock_env.block.height = 12_400;
+
+  let res = market.execute(deps.as_mut(), mock_env.clone(), mock_info("anyone", &[]), ExecuteMsg::ExecuteAuction { order_id: 1 }).unwrap();
+
+  let refund_asset = Asset {
+    info: uusd.clone(),
+    amount: Uint128::from(110000000u128)
+  };
+
+  assert_eq!(
+    res.messages,
+    vec![
+      // return nft to seller
+      SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "spaceship".to_string(),
+        msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+          recipient: "seller".to_string(),
+          token_id: "no1".to_string()
+        }).unwrap(),
+        funds: vec![]
+      })),
+      // refund bid
+      SubMsg::new(refund_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("bidder1")).unwrap()),
+    ]
+  );
+
+  let order = market.orders.may_load(&deps.storage, U64Key::new(1));
+
+  assert_eq!(order, Ok(None));
+
+  let reserve = market.reserve_prices.may_load(&deps.storage, U64Key::new(1));
+
+  assert_eq!(reserve, Ok(None));
+
+  // make auction with reserve price again
+  mock_env.block.height = 12_345;
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_auction_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env.clone(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  // bid higher than reserve price
+  let bid_msg = ExecuteMsg::Bid {
+    order_id: 2,
+    bid_price: Asset { info: uusd.clone(), amount: Uint128::from(160000000u128) },
+    referrer: None
+  };
+
+  let info = mock_info("bidder2", &[Coin{ denom: "uusd".to_string(), amount: Uint128::from(160000000u128) }]);
+
+  let _res = market.execute(deps.as_mut(), mock_env.clone(), info, bid_msg).unwrap();
+
+  let reserve_met: bool = from_binary(&market.query(deps.as_ref(), mock_env.clone(), QueryMsg::ReserveMet { order_id: 2 }).unwrap()).unwrap();
+
+  assert!(reserve_met);
+
+  // execute auction
+  mock_env.block.height = 12_400;
+
+  let res = market.execute(deps.as_mut(), mock_env, mock_info("anyone", &[]), ExecuteMsg::ExecuteAuction { order_id: 2 }).unwrap();
+
+  let remain_asset = Asset {
+    info: uusd.clone(),
+    amount: Uint128::from(160000000u128)
+  };
+
+  assert_eq!(
+    res.messages,
+    vec![
+      // transfer nft to bidder
+      SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "spaceship".to_string(),
+        msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+          recipient: "bidder2".to_string(),
+          token_id: "no1".to_string()
+        }).unwrap(),
+        funds: vec![]
+      })),
+      // transfer to seller
+      SubMsg::new(remain_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("seller")).unwrap()),
+    ]
+  );
+
+  let reserve = market.reserve_prices.may_load(&deps.storage, U64Key::new(2));
+
+  assert_eq!(reserve, Ok(None));
+}
+
+#[test]
+fn second_price_auction_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  // some assetinfos
+  let mir: AssetInfo = AssetInfo::Token { contract_addr: "mir_addr".to_string()};
+
+  // some royalties
+  let nft_designer_royalty: Royalty = Royalty {
+    address: Addr::unchecked("nft_designer"),
+    royalty_rate: Decimal::from_ratio(2u128, 100u128)
+  };
+
+  // add collection
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: vec![mir.clone()],
+    royalties: vec![nft_designer_royalty],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  let seller = "seller".to_string();
+
+  let start_price = Asset{
+    info: mir.clone(),
+    amount: Uint128::from(100000000u128)
+  };
+
+  // make second price auction
+  let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder {
+    start_price: start_price.clone(),
+    expiration: Expiration::AtHeight(12_400),
+    fixed_price: None,
+    reserve_price: None,
+    second_price: Some(true),
+    buyers: None,
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_auction_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  // bids
+  for (bidder, amount) in [("bidder1", 120000000u128), ("bidder2", 200000000u128)].iter() {
+    let receive_msg: Cw20ReceiveMsg = Cw20ReceiveMsg {
+      sender: bidder.to_string(),
+      amount: Uint128::from(*amount),
+      msg: to_binary(&Cw20HookMsg::Bid { order_id: 1, referrer: None }).unwrap()
+    };
+
+    let info = mock_info("mir_addr", &[]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into())).unwrap();
+  }
+
+  let order = market.orders.load(&deps.storage, U64Key::new(1)).unwrap();
+
+  assert_eq!(
+    Some(Asset { info: mir.clone(), amount: Uint128::from(120000000u128) }),
+    order.auction_info.unwrap().runner_up_bid
+  );
+
+  // execute auction. winner pays runner-up bid + 10%
+  let mut mock_env = mock_env();
+  mock_env.block.height = 12_400;
+
+  let res = market.execute(deps.as_mut(), mock_env, mock_info("anyone", &[]), ExecuteMsg::ExecuteAuction { order_id: 1 }).unwrap();
+
+  let mir_asset = |amount: u128| Asset { info: mir.clone(), amount: Uint128::from(amount) };
+
+  assert_eq!(
+    res.messages,
+    vec![
+      // transfer nft to winner
+      SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "spaceship".to_string(),
+        msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+          recipient: "bidder2".to_string(),
+          token_id: "no1".to_string()
+        }).unwrap(),
+        funds: vec![]
+      })),
+      // royalty of clearing price
+      SubMsg::new(mir_asset(2640000).into_msg(&deps.as_mut().querier, Addr::unchecked("nft_designer")).unwrap()),
+      // transfer remain to seller
+      SubMsg::new(mir_asset(129360000).into_msg(&deps.as_mut().querier, Addr::unchecked("seller")).unwrap()),
+      // refund difference to winner
+      SubMsg::new(mir_asset(68000000).into_msg(&deps.as_mut().querier, Addr::unchecked("bidder2")).unwrap()),
+    ]
+  );
+}
+
+
+#[test]
+fn bidder_query_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  let uusd: AssetInfo = AssetInfo::NativeToken { denom: "uusd".to_string()};
+
+  // add collection
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: vec![uusd.clone()],
+    royalties: vec![],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  // make 2 auctions
+  for token_id in ["no1", "no2"] {
+    let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder {
+      start_price: Asset { info: uusd.clone(), amount: Uint128::from(100000000u128) },
+      expiration: Expiration::AtHeight(12_400),
+      fixed_price: None,
+      reserve_price: None,
+      second_price: None,
+      buyers: None
+    };
+
+    let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+      sender: "seller".to_string(),
+      token_id: token_id.to_string(),
+      msg: to_binary(&make_auction_order_msg).unwrap(),
+    };
+
+    let info = mock_info("spaceship", &[]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+  }
+
+  let bid = |amount: u128| Asset { info: uusd.clone(), amount: Uint128::from(amount) };
+
+  // bidder1 bids on both, bidder2 outbids bidder1 on order 1
+  let bids = [("bidder1", 1, 110000000u128), ("bidder1", 2, 110000000u128), ("bidder2", 1, 121000000u128)];
+
+  for (bidder, order_id, amount) in bids {
+    let info = mock_info(bidder, &[Coin{ denom: "uusd".to_string(), amount: Uint128::from(amount) }]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Bid { order_id, bid_price: bid(amount), referrer: None }).unwrap();
+  }
+
+  let query_msg = QueryMsg::OrdersByBidder { bidder: "bidder1".to_string(), start_after: None, limit: None };
+  let orders: Vec<Order> = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(orders.iter().map(|order| order.id).collect::<Vec<u64>>(), vec![2]);
+
+  let query_msg = QueryMsg::OrdersByBidder { bidder: "bidder2".to_string(), start_after: None, limit: None };
+  let orders: Vec<Order> = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(orders.iter().map(|order| order.id).collect::<Vec<u64>>(), vec![1]);
+
+  let query_msg = QueryMsg::Outbids { bidder: "bidder1".to_string(), start_after: None, limit: None };
+  let outbids: Vec<Outbid> = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(
+    outbids,
+    vec![Outbid {
+      order_id: 1,
+      bidder: Addr::unchecked("bidder1"),
+      bid: bid(110000000u128),
+      highest_bid: bid(121000000u128),
+      height: mock_env().block.height,
+      time: mock_env().block.time.seconds()
+    }]
+  );
+
+  // bidder1 is the highest bidder again
+  let info = mock_info("bidder1", &[Coin{ denom: "uusd".to_string(), amount: Uint128::from(140000000u128) }]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Bid { order_id: 1, bid_price: bid(140000000u128), referrer: None }).unwrap();
+
+  let query_msg = QueryMsg::Outbids { bidder: "bidder1".to_string(), start_after: None, limit: None };
+  let outbids: Vec<Outbid> = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(outbids, vec![]);
+
+  let query_msg = QueryMsg::Outbids { bidder: "bidder2".to_string(), start_after: None, limit: None };
+  let outbids: Vec<Outbid> = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(outbids.iter().map(|outbid| outbid.order_id).collect::<Vec<u64>>(), vec![1]);
+
+  // bidder2 outbids bidder1 on order 2, then on order 1
+  let bids = [(12_346, 2, 121000000u128), (12_347, 1, 154000000u128)];
+
+  for (height, order_id, amount) in bids {
+    let mut env = mock_env();
+    env.block.height = height;
+
+    let info = mock_info("bidder2", &[Coin{ denom: "uusd".to_string(), amount: Uint128::from(amount) }]);
+
+    let _res = market.execute(deps.as_mut(), env, info, ExecuteMsg::Bid { order_id, bid_price: bid(amount), referrer: None }).unwrap();
+  }
+
+  // most recent outbid first
+  let query_msg = QueryMsg::Outbids { bidder: "bidder1".to_string(), start_after: None, limit: None };
+  let outbids: Vec<Outbid> = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(outbids.iter().map(|outbid| (outbid.order_id, outbid.height)).collect::<Vec<(u64, u64)>>(), vec![(1, 12_347), (2, 12_346)]);
+
+  let query_msg = QueryMsg::Outbids { bidder: "bidder1".to_string(), start_after: Some(1), limit: None };
+  let outbids: Vec<Outbid> = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(outbids.iter().map(|outbid| outbid.order_id).collect::<Vec<u64>>(), vec![2]);
+
+  // executed auction is removed from the indexes
+  let mut env = mock_env();
+  env.block.height = 12_400;
+
+  let _res = market.execute(deps.as_mut(), env, mock_info("seller", &[]), ExecuteMsg::ExecuteAuction { order_id: 2 }).unwrap();
+
+  let query_msg = QueryMsg::OrdersByBidder { bidder: "bidder2".to_string(), start_after: None, limit: None };
+  let orders: Vec<Order> = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(orders.iter().map(|order| order.id).collect::<Vec<u64>>(), vec![1]);
+
+  let query_msg = QueryMsg::Outbids { bidder: "bidder1".to_string(), start_after: None, limit: None };
+  let outbids: Vec<Outbid> = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(outbids.iter().map(|outbid| outbid.order_id).collect::<Vec<u64>>(), vec![1]);
+
+  // canceled auction is removed from the indexes
+  let _res = market.execute(deps.as_mut(), mock_env(), mock_info("seller", &[]), ExecuteMsg::CancelOrder { order_id: 1 }).unwrap();
+
+  for bidder in ["bidder1", "bidder2"] {
+    let query_msg = QueryMsg::Outbids { bidder: bidder.to_string(), start_after: None, limit: None };
+    let outbids: Vec<Outbid> = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+    assert_eq!(outbids, vec![]);
+  }
 }
\ No newline at end of file
@@ -0,0 +1,300 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{from_binary, to_binary, Addr, CosmosMsg, Decimal, SubMsg, WasmMsg, Uint128};
+use cw_storage_plus::U64Key;
+use cw721::{Cw721ExecuteMsg, Cw721ReceiveMsg};
+use cw20::Cw20ReceiveMsg;
+
+use crate::{
+  state::{BundleItem, MarketContract, Royalty},
+  msgs::{InstantiateMsg, ExecuteMsg, Cw721HookMsg, Cw20HookMsg, QueryMsg},
+  error::ContractError,
+  asset::{Asset, AssetInfo},
+  testing::mock_querier::mock_dependencies
+};
+
+#[test]
+fn bundle_order_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  // some assetinfos
+  let uusd: AssetInfo = AssetInfo::NativeToken { denom: "uusd".to_string()};
+  let mir: AssetInfo = AssetInfo::Token { contract_addr: "mir_addr".to_string()};
+
+  // some royalties
+  let nft_designer_royalty: Royalty = Royalty {
+    address: Addr::unchecked("nft_designer"),
+    royalty_rate: Decimal::from_ratio(2u128, 100u128)
+  };
+
+  let nft_pm_royalty: Royalty = Royalty {
+    address: Addr::unchecked("nft_pm"),
+    royalty_rate: Decimal::from_ratio(4u128, 100u128)
+  };
+
+  // add collections
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: vec![uusd.clone(), mir.clone()],
+    royalties: vec![nft_designer_royalty],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "planet".to_string(),
+    support_assets: vec![mir.clone()],
+    royalties: vec![nft_pm_royalty],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  let seller = "seller".to_string();
+
+  // try to make bundle order without nft
+  let make_bundle_order_msg = ExecuteMsg::MakeBundleFixedPriceOrder {
+    price: Asset { info: mir.clone(), amount: Uint128::from(300000000u128) },
+    expiration: None,
+    other_prices: None,
+    buyers: None
+  };
+
+  let info = mock_info("seller", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, make_bundle_order_msg);
+
+  match res {
+    Err(ContractError::EmptyBundle {}) => {}
+    _ => panic!("Must return empty bundle error"),
+  }
+
+  // try to add nft of unlisted collection
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&Cw721HookMsg::AddToBundle {}).unwrap(),
+  };
+
+  let info = mock_info("unlisted", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
+
+  match res {
+    Err(ContractError::Std(_)) => {}
+    _ => panic!("Must return error"),
+  }
+
+  // add nfts to bundle
+  let items = vec![
+    BundleItem { nft_address: Addr::unchecked("spaceship"), token_id: "no1".to_string() },
+    BundleItem { nft_address: Addr::unchecked("spaceship"), token_id: "no2".to_string() },
+    BundleItem { nft_address: Addr::unchecked("planet"), token_id: "no1".to_string() },
+  ];
+
+  for item in items.iter() {
+    let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+      sender: seller.clone(),
+      token_id: item.token_id.clone(),
+      msg: to_binary(&Cw721HookMsg::AddToBundle {}).unwrap(),
+    };
+
+    let info = mock_info(item.nft_address.as_str(), &[]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+  }
+
+  // check pending bundle
+  let res = market.query(deps.as_ref(), mock_env(), QueryMsg::PendingBundle { seller: seller.clone() }).unwrap();
+  let pending: Vec<BundleItem> = from_binary(&res).unwrap();
+
+  assert_eq!(items, pending);
+
+  // withdraw bundle
+  let info = mock_info("seller", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::WithdrawBundle {}).unwrap();
+
+  let transfer_msgs: Vec<SubMsg> = items.iter().map(|item| {
+    SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+      contract_addr: item.nft_address.to_string(),
+      msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+        recipient: "seller".to_string(),
+        token_id: item.token_id.clone()
+      }).unwrap(),
+      funds: vec![]
+    }))
+  }).collect();
+
+  assert_eq!(res.messages, transfer_msgs);
+
+  let bundle = market.bundles.may_load(&deps.storage, seller.clone()).unwrap();
+
+  assert_eq!(None, bundle);
+
+  // add nfts again and make bundle order
+  for item in items.iter() {
+    let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+      sender: seller.clone(),
+      token_id: item.token_id.clone(),
+      msg: to_binary(&Cw721HookMsg::AddToBundle {}).unwrap(),
+    };
+
+    let info = mock_info(item.nft_address.as_str(), &[]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+  }
+
+  let price = Asset {
+    info: mir.clone(),
+    amount: Uint128::from(300000000u128)
+  };
+
+  let make_bundle_order_msg = ExecuteMsg::MakeBundleFixedPriceOrder {
+    price: price.clone(),
+    expiration: None,
+    other_prices: None,
+    buyers: None
+  };
+
+  let info = mock_info("seller", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, make_bundle_order_msg).unwrap();
+
+  // check order
+  let order = market.orders.load(&deps.storage, U64Key::new(1)).unwrap();
+
+  assert_eq!(Addr::unchecked("spaceship"), order.nft_address);
+  assert_eq!("no1".to_string(), order.token_id);
+  assert_eq!(items[1..].to_vec(), order.bundle);
+  assert_eq!(items, order.items());
+  assert_eq!(Some(price.clone()), order.price);
+
+  let bundle = market.bundles.may_load(&deps.storage, seller.clone()).unwrap();
+
+  assert_eq!(None, bundle);
+
+  // cancel order returns all nfts
+  let info = mock_info("seller", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::CancelOrder { order_id: 1 }).unwrap();
+
+  assert_eq!(res.messages, transfer_msgs);
+
+  // remake bundle order
+  for item in items.iter() {
+    let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+      sender: seller.clone(),
+      token_id: item.token_id.clone(),
+      msg: to_binary(&Cw721HookMsg::AddToBundle {}).unwrap(),
+    };
+
+    let info = mock_info(item.nft_address.as_str(), &[]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+  }
+
+  let make_bundle_order_msg = ExecuteMsg::MakeBundleFixedPriceOrder {
+    price: price.clone(),
+    expiration: None,
+    other_prices: None,
+    buyers: None
+  };
+
+  let info = mock_info("seller", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, make_bundle_order_msg).unwrap();
+
+  // try to update price with asset that planet does not support
+  let update_order_msg = ExecuteMsg::UpdateOrder {
+    order_id: 2,
+    price: Some(Asset { info: uusd.clone(), amount: Uint128::from(300000000u128) }),
+    fixed_price: None,
+    expiration: None,
+  };
+
+  let info = mock_info("seller", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, update_order_msg);
+
+  match res {
+    Err(ContractError::Unsupport {}) => {}
+    _ => panic!("Must return unsupport error"),
+  }
+
+  // execute bundle order
+  let execute_msg = Cw20HookMsg::ExecuteOrder { order_id: 2, amount: None, referrer: None };
+
+  let info = mock_info("mir_addr", &[]);
+
+  let receive_msg: Cw20ReceiveMsg = Cw20ReceiveMsg {
+    sender: "buyer".to_string(),
+    amount: Uint128::from(300000000u128),
+    msg: to_binary(&execute_msg).unwrap()
+  };
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into())).unwrap();
+
+  // royalties are paid pro rata. spaceship 2/3 of price, planet 1/3 of price
+  let designer_royalty_asset = Asset {
+    info: mir.clone(),
+    amount: Uint128::from(4000000u128)
+  };
+
+  let pm_royalty_asset = Asset {
+    info: mir.clone(),
+    amount: Uint128::from(4000000u128)
+  };
+
+  let remain_asset = Asset {
+    info: mir.clone(),
+    amount: Uint128::from(292000000u128)
+  };
+
+  let mut messages: Vec<SubMsg> = items.iter().map(|item| {
+    SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+      contract_addr: item.nft_address.to_string(),
+      msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+        recipient: "buyer".to_string(),
+        token_id: item.token_id.clone()
+      }).unwrap(),
+      funds: vec![]
+    }))
+  }).collect();
+
+  messages.push(SubMsg::new(designer_royalty_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("nft_designer")).unwrap()));
+  messages.push(SubMsg::new(pm_royalty_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("nft_pm")).unwrap()));
+  messages.push(SubMsg::new(remain_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("seller")).unwrap()));
+
+  assert_eq!(res.messages, messages);
+
+  // check order removed
+  let order = market.orders.may_load(&deps.storage, U64Key::new(2));
+
+  assert_eq!(order, Ok(None));
+}
@@ -2,7 +2,7 @@ use cosmwasm_std::testing::{mock_env, mock_info};
 use cosmwasm_std::{Addr, Decimal};
 
 use crate::{
-  state::{MarketContract, Royalty},
+  state::{AuctionExtension, MarketContract, Royalty},
   msgs::{InstantiateMsg, ExecuteMsg},
   error::ContractError,
   asset::AssetInfo,
@@ -22,6 +22,10 @@ fn update_config_test() {
     max_auction_duration_block: 100,
     max_auction_duration_second: 1000,
     auction_cancel_fee_rate: Decimal::from_ratio(5u128, 1000u128),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
   };
 
   let info = mock_info("owner", &[]);
@@ -35,6 +39,12 @@ fn update_config_test() {
     max_auction_duration_block: None,
     max_auction_duration_second: None,
     auction_cancel_fee_rate: None,
+    auction_extension_block: None,
+    auction_extension_second: None,
+    protocol_fee_rate: None,
+    treasury: None,
+    fee_discount: None,
+    referral_share: None,
   };
 
   let _res = market.execute(deps.as_mut(), mock_env(), info.clone(), update_config_msg).unwrap();
@@ -52,12 +62,18 @@ fn update_config_test() {
     max_auction_duration_block: None,
     max_auction_duration_second: None,
     auction_cancel_fee_rate: None,
+    auction_extension_block: None,
+    auction_extension_second: None,
+    protocol_fee_rate: None,
+    treasury: None,
+    fee_discount: None,
+    referral_share: None,
   };
 
   let res = market.execute(deps.as_mut(), mock_env(), info, update_config_msg);
 
   match res {
-    Err(ContractError::Unauthorized {}) => assert!(true),
+    Err(ContractError::Unauthorized {}) => {}
     _ => panic!("Must return unauthorized error"),
   }
 
@@ -68,6 +84,12 @@ fn update_config_test() {
     max_auction_duration_block: Some(123),
     max_auction_duration_second: Some(1234),
     auction_cancel_fee_rate: Some(Decimal::from_ratio(3u128, 1000u128)),
+    auction_extension_block: Some(10),
+    auction_extension_second: Some(60),
+    protocol_fee_rate: None,
+    treasury: None,
+    fee_discount: None,
+    referral_share: None,
   };
 
   let info = mock_info("next_owner", &[]);
@@ -79,6 +101,8 @@ fn update_config_test() {
   assert_eq!(Decimal::from_ratio(5u128, 100u128), config.min_increase);
   assert_eq!(123, config.max_auction_duration_block);
   assert_eq!(1234, config.max_auction_duration_second);
+  assert_eq!(10, config.auction_extension_block);
+  assert_eq!(60, config.auction_extension_second);
 }
 
 #[test]
@@ -94,6 +118,10 @@ fn add_and_update_collection_test() {
     max_auction_duration_block: 100,
     max_auction_duration_second: 1000,
     auction_cancel_fee_rate: Decimal::from_ratio(5u128, 1000u128),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
   };
 
   let info = mock_info("owner", &[]);
@@ -136,12 +164,15 @@ fn add_and_update_collection_test() {
     nft_address: "spaceship".to_string(),
     support_assets: vec![uusd.clone()],
     royalties: vec![normal_user_royalty],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
   };
 
   let res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg);
 
   match res {
-    Err(ContractError::Unauthorized {}) => assert!(true),
+    Err(ContractError::Unauthorized {}) => {}
     _ => panic!("Must return unauthorized error"),
   }
 
@@ -151,12 +182,15 @@ fn add_and_update_collection_test() {
     nft_address: "spaceship".to_string(),
     support_assets: vec![uusd.clone(), mir.clone()],
     royalties: vec![nft_designer_royalty_invalid.clone(), nft_pm_royalty_invalid.clone()],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
   };
 
   let res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg);
 
   match res {
-    Err(ContractError::InvalidRoyaltyRate {}) => assert!(true),
+    Err(ContractError::InvalidRoyaltyRate {}) => {}
     _ => panic!("Must return invalid royalty rate error"),
   }
 
@@ -166,6 +200,9 @@ fn add_and_update_collection_test() {
     nft_address: "spaceship".to_string(),
     support_assets: vec![uusd.clone(), mir.clone()],
     royalties: vec![nft_designer_royalty.clone(), nft_pm_royalty.clone()],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
   };
 
   let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
@@ -183,12 +220,15 @@ fn add_and_update_collection_test() {
     nft_address: "spaceship".to_string(),
     support_assets: vec![uusd.clone(), mir.clone()],
     royalties: vec![nft_designer_royalty.clone(), nft_pm_royalty.clone()],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
   };
 
   let res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg);
 
   match res {
-    Err(ContractError::CollectionExist {}) => assert!(true),
+    Err(ContractError::CollectionExist {}) => {}
     _ => panic!("Must return collection exist error"),
   }
 
@@ -198,12 +238,16 @@ fn add_and_update_collection_test() {
     nft_address: "spaceship".to_string(),
     support_assets: Some(vec![uusd.clone(), mir.clone()]),
     royalties: Some(vec![nft_designer_royalty.clone()]),
+    auction_extension: None,
+    clear_auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
   };
 
   let res = market.execute(deps.as_mut(), mock_env(), info, update_collection_msg);
 
   match res {
-    Err(ContractError::Unauthorized {}) => assert!(true),
+    Err(ContractError::Unauthorized {}) => {}
     _ => panic!("Must return unauthorized error"),
   }
 
@@ -213,12 +257,16 @@ fn add_and_update_collection_test() {
     nft_address: "spaceship".to_string(),
     support_assets: None,
     royalties: Some(vec![nft_designer_royalty_invalid.clone(), nft_pm_royalty_invalid.clone()]),
+    auction_extension: None,
+    clear_auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
   };
 
   let res = market.execute(deps.as_mut(), mock_env(), info.clone(), update_collection_msg);
 
   match res {
-    Err(ContractError::InvalidRoyaltyRate {}) => assert!(true),
+    Err(ContractError::InvalidRoyaltyRate {}) => {}
     _ => panic!("Must return invalid royalty rate error"),
   }
 
@@ -227,6 +275,10 @@ fn add_and_update_collection_test() {
     nft_address: "spaceship".to_string(),
     support_assets: Some(vec![uusd.clone()]),
     royalties: Some(vec![nft_designer_royalty.clone()]),
+    auction_extension: None,
+    clear_auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
   };
 
   let _res = market.execute(deps.as_mut(), mock_env(), info, update_collection_msg);
@@ -236,4 +288,41 @@ fn add_and_update_collection_test() {
   assert_eq!(Addr::unchecked("spaceship"), collection.nft_address);
   assert_eq!(vec![uusd], collection.support_assets);
   assert_eq!(vec![nft_designer_royalty], collection.royalties);
+
+  // set auction extension of the collection, then clear it to use config
+  let update_collection_msg = ExecuteMsg::UpdateCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: None,
+    royalties: None,
+    auction_extension: Some(AuctionExtension { block: 10, second: 60 }),
+    clear_auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, update_collection_msg).unwrap();
+
+  let collection = market.collections.load(&deps.storage, "spaceship".to_string()).unwrap();
+
+  assert_eq!(Some(AuctionExtension { block: 10, second: 60 }), collection.auction_extension);
+
+  let update_collection_msg = ExecuteMsg::UpdateCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: None,
+    royalties: None,
+    auction_extension: None,
+    clear_auction_extension: Some(true),
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, update_collection_msg).unwrap();
+
+  let collection = market.collections.load(&deps.storage, "spaceship".to_string()).unwrap();
+
+  assert_eq!(None, collection.auction_extension);
 }
@@ -0,0 +1,213 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{from_binary, to_binary, Addr, Coin, CosmosMsg, Decimal, SubMsg, WasmMsg, Uint128};
+use cw_storage_plus::U64Key;
+use cw20::Cw20ReceiveMsg;
+
+use crate::{
+  state::{Cw1155Info, MarketContract, Order, Royalty},
+  msgs::{InstantiateMsg, ExecuteMsg, Cw20HookMsg, Cw1155HookMsg, QueryMsg},
+  cw1155::{Cw1155BatchReceiveMsg, Cw1155ExecuteMsg, Cw1155ReceiveMsg},
+  error::ContractError,
+  asset::{Asset, AssetInfo},
+  testing::mock_querier::mock_dependencies
+};
+
+fn send_from_msg(token_id: &str, recipient: &str, value: u128) -> SubMsg {
+  SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+    contract_addr: "potion".to_string(),
+    msg: to_binary(&Cw1155ExecuteMsg::SendFrom {
+      from: mock_env().contract.address.to_string(),
+      to: recipient.to_string(),
+      token_id: token_id.to_string(),
+      value: Uint128::from(value),
+      msg: None
+    }).unwrap(),
+    funds: vec![]
+  }))
+}
+
+#[test]
+fn receive_msg_test() {
+  // cw20 and cw1155 receive hooks share `receive` key
+  let cw20_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+    sender: "buyer".to_string(),
+    amount: Uint128::from(100u128),
+    msg: to_binary(&Cw20HookMsg::ExecuteOrder { order_id: 1, amount: None, referrer: None }).unwrap()
+  }.into());
+
+  let cw1155_msg = ExecuteMsg::Receive(Cw1155ReceiveMsg {
+    operator: "seller".to_string(),
+    from: None,
+    token_id: "hp".to_string(),
+    amount: Uint128::from(100u128),
+    msg: to_binary(&Cw1155HookMsg::MakeFixedPriceOrder { price: Asset {
+      info: AssetInfo::NativeToken { denom: "uusd".to_string() },
+      amount: Uint128::from(100u128)
+    }, expiration: None }).unwrap()
+  }.into());
+
+  assert_eq!(cw20_msg, from_binary(&to_binary(&cw20_msg).unwrap()).unwrap());
+  assert_eq!(cw1155_msg, from_binary(&to_binary(&cw1155_msg).unwrap()).unwrap());
+}
+
+#[test]
+fn cw1155_order_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  // need tax querier
+  deps.querier.with_tax(
+    Decimal::from_ratio(1u128, 100u128),
+    &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+  );
+
+  let uusd: AssetInfo = AssetInfo::NativeToken { denom: "uusd".to_string()};
+
+  // add cw1155 collection
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "potion".to_string(),
+    support_assets: vec![uusd.clone()],
+    royalties: vec![Royalty { address: Addr::unchecked("nft_designer"), royalty_rate: Decimal::from_ratio(2u128, 100u128) }],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  // price per unit
+  let price = Asset { info: uusd.clone(), amount: Uint128::from(1000000u128) };
+
+  let make_fixed_price_order_msg = to_binary(&Cw1155HookMsg::MakeFixedPriceOrder { price: price.clone(), expiration: None }).unwrap();
+
+  // try to list zero amount
+  let receive_msg = Cw1155ReceiveMsg {
+    operator: "seller".to_string(),
+    from: Some("seller".to_string()),
+    token_id: "hp".to_string(),
+    amount: Uint128::zero(),
+    msg: make_fixed_price_order_msg.clone()
+  };
+
+  let info = mock_info("potion", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into()));
+
+  match res {
+    Err(ContractError::InvalidAmount {}) => {}
+    _ => panic!("Must return invalid amount error"),
+  }
+
+  // list 10 hp
+  let receive_msg = Cw1155ReceiveMsg {
+    operator: "seller".to_string(),
+    from: Some("seller".to_string()),
+    token_id: "hp".to_string(),
+    amount: Uint128::from(10u128),
+    msg: make_fixed_price_order_msg.clone()
+  };
+
+  let info = mock_info("potion", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into())).unwrap();
+
+  // list 5 mp and 3 sp with batch
+  let batch_receive_msg = Cw1155BatchReceiveMsg {
+    operator: "seller".to_string(),
+    from: Some("seller".to_string()),
+    batch: vec![("mp".to_string(), Uint128::from(5u128)), ("sp".to_string(), Uint128::from(3u128))],
+    msg: make_fixed_price_order_msg
+  };
+
+  let info = mock_info("potion", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::BatchReceive(batch_receive_msg)).unwrap();
+
+  let order = market.orders.load(&deps.storage, U64Key::new(3)).unwrap();
+
+  assert_eq!(Addr::unchecked("seller"), order.seller_address);
+  assert_eq!("sp".to_string(), order.token_id);
+  assert_eq!(Some(price.clone()), order.price);
+  assert_eq!(Some(Cw1155Info { amount: Uint128::from(3u128), remaining: Uint128::from(3u128) }), order.cw1155_info);
+
+  // try to buy more than remaining
+  let info = mock_info("buyer", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(11000000u128) }]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ExecuteOrder { order_id: 1, amount: Some(Uint128::from(11u128)), referrer: None });
+
+  match res {
+    Err(ContractError::InvalidAmount {}) => {}
+    _ => panic!("Must return invalid amount error"),
+  }
+
+  // try to pay for less units
+  let info = mock_info("buyer", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(2000000u128) }]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ExecuteOrder { order_id: 1, amount: Some(Uint128::from(3u128)), referrer: None });
+
+  match res {
+    Err(ContractError::Std(_)) => {}
+    _ => panic!("Must return error"),
+  }
+
+  // buy 3 hp
+  let info = mock_info("buyer", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(3000000u128) }]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ExecuteOrder { order_id: 1, amount: Some(Uint128::from(3u128)), referrer: None }).unwrap();
+
+  let designer_royalty_asset = Asset { info: uusd.clone(), amount: Uint128::from(60000u128) };
+  let remain_asset = Asset { info: uusd.clone(), amount: Uint128::from(2940000u128) };
+
+  assert_eq!(
+    res.messages,
+    vec![
+      send_from_msg("hp", "buyer", 3),
+      SubMsg::new(designer_royalty_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("nft_designer")).unwrap()),
+      SubMsg::new(remain_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("seller")).unwrap()),
+    ]
+  );
+
+  // remaining quantity
+  let order: Order = from_binary(&market.query(deps.as_ref(), mock_env(), QueryMsg::Order { order_id: 1 }).unwrap()).unwrap();
+
+  assert_eq!(Some(Cw1155Info { amount: Uint128::from(10u128), remaining: Uint128::from(7u128) }), order.cw1155_info);
+
+  // buy all remaining
+  let info = mock_info("buyer", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(7000000u128) }]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ExecuteOrder { order_id: 1, amount: None, referrer: None }).unwrap();
+
+  assert_eq!(res.messages[0], send_from_msg("hp", "buyer", 7));
+  assert_eq!(market.orders.may_load(&deps.storage, U64Key::new(1)), Ok(None));
+
+  // buy 1 mp and cancel the rest
+  let info = mock_info("buyer", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(1000000u128) }]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ExecuteOrder { order_id: 2, amount: Some(Uint128::from(1u128)), referrer: None }).unwrap();
+
+  let info = mock_info("seller", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::CancelOrder { order_id: 2 }).unwrap();
+
+  assert_eq!(res.messages, vec![send_from_msg("mp", "seller", 4)]);
+  assert_eq!(market.orders.may_load(&deps.storage, U64Key::new(2)), Ok(None));
+}
@@ -0,0 +1,416 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{from_binary, to_binary, Addr, Coin, CosmosMsg, Decimal, SubMsg, Timestamp, WasmMsg, Uint128};
+use cw_storage_plus::U64Key;
+use cw721::{Cw721ExecuteMsg, Cw721ReceiveMsg};
+use cw20::Cw20ReceiveMsg;
+use cw0::Expiration;
+
+use crate::{
+  state::{MarketContract, Royalty},
+  msgs::{InstantiateMsg, ExecuteMsg, Cw721HookMsg, Cw20HookMsg, QueryMsg},
+  error::ContractError,
+  asset::{Asset, AssetInfo},
+  testing::mock_querier::mock_dependencies
+};
+
+#[test]
+fn dutch_auction_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  // need tax querier
+  deps.querier.with_tax(
+    Decimal::from_ratio(1u128, 100u128),
+    &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+  );
+
+  // some assetinfos
+  let uusd: AssetInfo = AssetInfo::NativeToken { denom: "uusd".to_string()};
+  let mir: AssetInfo = AssetInfo::Token { contract_addr: "mir_addr".to_string()};
+
+  // some royalties
+  let nft_designer_royalty: Royalty = Royalty {
+    address: Addr::unchecked("nft_designer"),
+    royalty_rate: Decimal::from_ratio(2u128, 100u128)
+  };
+
+  // add collection
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: vec![uusd.clone(), mir.clone()],
+    royalties: vec![nft_designer_royalty],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  let seller = "seller".to_string();
+
+  let start_price = Asset {
+    info: uusd.clone(),
+    amount: Uint128::from(100000000u128)
+  };
+
+  let end_price = Asset {
+    info: uusd.clone(),
+    amount: Uint128::from(50000000u128)
+  };
+
+  // try to make dutch auction with end price higher than start price
+  let make_dutch_auction_order_msg = Cw721HookMsg::MakeDutchAuctionOrder {
+    start_price: end_price.clone(),
+    end_price: start_price.clone(),
+    start: Expiration::AtHeight(12_345),
+    end: Expiration::AtHeight(12_445),
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_dutch_auction_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
+
+  match res {
+    Err(ContractError::InvalidPriceRange {}) => {}
+    _ => panic!("Must return invalid price range error"),
+  }
+
+  // try to make dutch auction with different asset
+  let make_dutch_auction_order_msg = Cw721HookMsg::MakeDutchAuctionOrder {
+    start_price: start_price.clone(),
+    end_price: Asset { info: mir.clone(), amount: Uint128::from(50000000u128) },
+    start: Expiration::AtHeight(12_345),
+    end: Expiration::AtHeight(12_445),
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_dutch_auction_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
+
+  match res {
+    Err(ContractError::AssetInfoMismatch {}) => {}
+    _ => panic!("Must return asset info mismatch error"),
+  }
+
+  // try to make dutch auction with mixed period type
+  let make_dutch_auction_order_msg = Cw721HookMsg::MakeDutchAuctionOrder {
+    start_price: start_price.clone(),
+    end_price: end_price.clone(),
+    start: Expiration::AtHeight(12_345),
+    end: Expiration::AtTime(Timestamp::from_seconds(1_571_797_519)),
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_dutch_auction_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
+
+  match res {
+    Err(ContractError::InvalidPeriod {}) => {}
+    _ => panic!("Must return invalid period error"),
+  }
+
+  // try to make dutch auction with start later than end
+  let make_dutch_auction_order_msg = Cw721HookMsg::MakeDutchAuctionOrder {
+    start_price: start_price.clone(),
+    end_price: end_price.clone(),
+    start: Expiration::AtHeight(12_445),
+    end: Expiration::AtHeight(12_345),
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_dutch_auction_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
+
+  match res {
+    Err(ContractError::InvalidPeriod {}) => {}
+    _ => panic!("Must return invalid period error"),
+  }
+
+  // try to make dutch auction already ended
+  let make_dutch_auction_order_msg = Cw721HookMsg::MakeDutchAuctionOrder {
+    start_price: start_price.clone(),
+    end_price: end_price.clone(),
+    start: Expiration::AtHeight(12_000),
+    end: Expiration::AtHeight(12_100),
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_dutch_auction_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
+
+  match res {
+    Err(ContractError::Expired {}) => {}
+    _ => panic!("Must return expired error"),
+  }
+
+  // make dutch auction. price decreases 500000uusd per block
+  let make_dutch_auction_order_msg = Cw721HookMsg::MakeDutchAuctionOrder {
+    start_price: start_price.clone(),
+    end_price: end_price.clone(),
+    start: Expiration::AtHeight(12_345),
+    end: Expiration::AtHeight(12_445),
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_dutch_auction_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  let order = market.orders.load(&deps.storage, U64Key::new(1)).unwrap();
+
+  assert_eq!(None, order.price);
+  assert_eq!(None, order.auction_info);
+
+  // try to update dutch auction
+  let update_order_msg = ExecuteMsg::UpdateOrder {
+    order_id: 1,
+    price: Some(start_price.clone()),
+    fixed_price: None,
+    expiration: None,
+  };
+
+  let info = mock_info("seller", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, update_order_msg);
+
+  match res {
+    Err(ContractError::DutchAuction {}) => {}
+    _ => panic!("Must return dutch auction error"),
+  }
+
+  // check current price
+  let mut mock_env = mock_env();
+  mock_env.block.height = 12_395;
+
+  let current_price: Option<Asset> = from_binary(
+    &market.query(deps.as_ref(), mock_env.clone(), QueryMsg::CurrentPrice { order_id: 1 }).unwrap()
+  ).unwrap();
+
+  let price = Asset {
+    info: uusd.clone(),
+    amount: Uint128::from(75000000u128)
+  };
+
+  assert_eq!(Some(price.clone()), current_price);
+
+  // try to execute with lower than current price
+  let execute_msg = ExecuteMsg::ExecuteOrder { order_id: 1, amount: None, referrer: None };
+
+  let info = mock_info("buyer", &[Coin{ denom: "uusd".to_string(), amount: Uint128::from(70000000u128) }]);
+
+  let res = market.execute(deps.as_mut(), mock_env.clone(), info, execute_msg);
+
+  match res {
+    Err(ContractError::InsufficientPayment { price: _ }) => {}
+    _ => panic!("Must return insufficient payment error"),
+  }
+
+  // execute with overpayment
+  let execute_msg = ExecuteMsg::ExecuteOrder { order_id: 1, amount: None, referrer: None };
+
+  let info = mock_info("buyer", &[Coin{ denom: "uusd".to_string(), amount: Uint128::from(80000000u128) }]);
+
+  let res = market.execute(deps.as_mut(), mock_env.clone(), info, execute_msg).unwrap();
+
+  let designer_royalty_asset = Asset {
+    info: uusd.clone(),
+    amount: Uint128::from(1500000u128)
+  };
+
+  let remain_asset = Asset {
+    info: uusd.clone(),
+    amount: Uint128::from(73500000u128)
+  };
+
+  let refund_asset = Asset {
+    info: uusd.clone(),
+    amount: Uint128::from(5000000u128)
+  };
+
+  assert_eq!(
+    res.messages,
+    vec![
+      // transfer nft to buyer
+      SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "spaceship".to_string(),
+        msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+          recipient: "buyer".to_string(),
+          token_id: "no1".to_string()
+        }).unwrap(),
+        funds: vec![]
+      })),
+      // transfer royalty
+      SubMsg::new(designer_royalty_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("nft_designer")).unwrap()),
+      // transfer remain to seller
+      SubMsg::new(remain_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("seller")).unwrap()),
+      // refund overpayment
+      SubMsg::new(refund_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("buyer")).unwrap()),
+    ]
+  );
+
+  let order = market.orders.may_load(&deps.storage, U64Key::new(1));
+
+  assert_eq!(order, Ok(None));
+
+  // time based dutch auction with cw20
+  let start_price = Asset {
+    info: mir.clone(),
+    amount: Uint128::from(100000000u128)
+  };
+
+  let end_price = Asset {
+    info: mir.clone(),
+    amount: Uint128::from(50000000u128)
+  };
+
+  let now = mock_env.block.time.seconds();
+
+  let make_dutch_auction_order_msg = Cw721HookMsg::MakeDutchAuctionOrder {
+    start_price: start_price.clone(),
+    end_price: end_price.clone(),
+    start: Expiration::AtTime(Timestamp::from_seconds(now)),
+    end: Expiration::AtTime(Timestamp::from_seconds(now + 1000)),
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no2".to_string(),
+    msg: to_binary(&make_dutch_auction_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env.clone(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  // after 250 seconds
+  mock_env.block.time = Timestamp::from_seconds(now + 250);
+
+  let current_price: Option<Asset> = from_binary(
+    &market.query(deps.as_ref(), mock_env.clone(), QueryMsg::CurrentPrice { order_id: 2 }).unwrap()
+  ).unwrap();
+
+  let price = Asset {
+    info: mir.clone(),
+    amount: Uint128::from(87500000u128)
+  };
+
+  assert_eq!(Some(price.clone()), current_price);
+
+  // after end, price stays at end price
+  let mut ended_env = mock_env.clone();
+  ended_env.block.time = Timestamp::from_seconds(now + 2000);
+
+  let current_price: Option<Asset> = from_binary(
+    &market.query(deps.as_ref(), ended_env, QueryMsg::CurrentPrice { order_id: 2 }).unwrap()
+  ).unwrap();
+
+  assert_eq!(Some(end_price), current_price);
+
+  // try to execute cw20 order with native token
+  let execute_msg = ExecuteMsg::ExecuteOrder { order_id: 2, amount: None, referrer: None };
+
+  let info = mock_info("buyer", &[Coin{ denom: "uusd".to_string(), amount: Uint128::from(87500000u128) }]);
+
+  let res = market.execute(deps.as_mut(), mock_env.clone(), info, execute_msg);
+
+  match res {
+    Err(ContractError::TokenMismatch {}) => {}
+    _ => panic!("Must return token mismatch error"),
+  }
+
+  // execute with exact current price
+  let execute_msg = Cw20HookMsg::ExecuteOrder { order_id: 2, amount: None, referrer: None };
+
+  let info = mock_info("mir_addr", &[]);
+
+  let receive_msg: Cw20ReceiveMsg = Cw20ReceiveMsg {
+    sender: "buyer".to_string(),
+    amount: Uint128::from(87500000u128),
+    msg: to_binary(&execute_msg).unwrap()
+  };
+
+  let res = market.execute(deps.as_mut(), mock_env, info, ExecuteMsg::Receive(receive_msg.into())).unwrap();
+
+  let designer_royalty_asset = Asset {
+    info: mir.clone(),
+    amount: Uint128::from(1750000u128)
+  };
+
+  let remain_asset = Asset {
+    info: mir.clone(),
+    amount: Uint128::from(85750000u128)
+  };
+
+  assert_eq!(
+    res.messages,
+    vec![
+      // transfer nft to buyer
+      SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "spaceship".to_string(),
+        msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+          recipient: "buyer".to_string(),
+          token_id: "no2".to_string()
+        }).unwrap(),
+        funds: vec![]
+      })),
+      // transfer royalty
+      SubMsg::new(designer_royalty_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("nft_designer")).unwrap()),
+      // transfer remain to seller
+      SubMsg::new(remain_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("seller")).unwrap()),
+    ]
+  );
+}
@@ -1,8 +1,9 @@
 use cosmwasm_std::testing::{mock_env, mock_info};
-use cosmwasm_std::{to_binary, Addr, Coin, CosmosMsg, Decimal, SubMsg, WasmMsg, Uint128};
+use cosmwasm_std::{attr, to_binary, Addr, Coin, CosmosMsg, Decimal, SubMsg, WasmMsg, Uint128};
 use cw_storage_plus::U64Key;
 use cw721::{Cw721ExecuteMsg, Cw721ReceiveMsg};
 use cw20::Cw20ReceiveMsg;
+use cw0::Expiration;
 
 use crate::{
   state::{MarketContract, Royalty},
@@ -25,6 +26,10 @@ fn fixed_price_order_test() {
     max_auction_duration_block: 100,
     max_auction_duration_second: 1000,
     auction_cancel_fee_rate: Decimal::from_ratio(5u128, 1000u128),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
   };
 
   let info = mock_info("owner", &[]);
@@ -59,6 +64,9 @@ fn fixed_price_order_test() {
     nft_address: "spaceship".to_string(),
     support_assets: vec![uusd.clone(), mir.clone()],
     royalties: vec![nft_designer_royalty.clone(), nft_pm_royalty.clone()],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
   };
 
   let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
@@ -71,7 +79,7 @@ fn fixed_price_order_test() {
   };
 
   // make fixed_price order
-  let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder { price: price.clone() };
+  let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder { price: price.clone(), expiration: None, other_prices: None, buyers: None };
 
   let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
     sender: seller.clone(),
@@ -104,7 +112,7 @@ fn fixed_price_order_test() {
     amount: Uint128::from(999999u128)
   };
 
-  let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder { price: price.clone() };
+  let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder { price: price.clone(), expiration: None, other_prices: None, buyers: None };
 
   let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
     sender: seller.clone(),
@@ -117,7 +125,7 @@ fn fixed_price_order_test() {
   let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
 
   match res {
-    Err(ContractError::Unsupport {}) => assert!(true),
+    Err(ContractError::Unsupport {}) => {}
     _ => panic!("Must return unsupport error"),
   }
 
@@ -131,7 +139,7 @@ fn fixed_price_order_test() {
   let res = market.execute(deps.as_mut(), mock_env(), info, cancel_msg);
 
   match res {
-    Err(ContractError::Unauthorized {}) => assert!(true),
+    Err(ContractError::Unauthorized {}) => {}
     _ => panic!("Must return unauthorized error"),
   }
 
@@ -156,7 +164,7 @@ fn fixed_price_order_test() {
   };
 
   // remake order for test
-  let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder { price: price.clone() };
+  let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder { price: price.clone(), expiration: None, other_prices: None, buyers: None };
 
   let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
     sender: seller.clone(),
@@ -169,31 +177,31 @@ fn fixed_price_order_test() {
   let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
 
   // try to execute order with balance missmatch
-  let execute_msg = ExecuteMsg::ExecuteOrder { order_id: 2 };
+  let execute_msg = ExecuteMsg::ExecuteOrder { order_id: 2, amount: None, referrer: None };
 
   let info = mock_info("buyer", &[Coin{ denom: "uusd".to_string(), amount: Uint128::from(12312412u128) }]);
 
   let res = market.execute(deps.as_mut(), mock_env(), info, execute_msg);
 
   match res {
-    Err(ContractError::Std(_)) => assert!(true),
+    Err(ContractError::Std(_)) => {}
     _ => panic!("Must return error"),
   }
 
   // try to execute order with another native token
-  let execute_msg = ExecuteMsg::ExecuteOrder { order_id: 2 };
+  let execute_msg = ExecuteMsg::ExecuteOrder { order_id: 2, amount: None, referrer: None };
 
   let info = mock_info("buyer", &[Coin{ denom: "uluna".to_string(), amount: Uint128::from(100000000u128) }]);
 
   let res = market.execute(deps.as_mut(), mock_env(), info, execute_msg);
 
   match res {
-    Err(ContractError::Std(_)) => assert!(true),
+    Err(ContractError::Std(_)) => {}
     _ => panic!("Must return error"),
   }
 
   // execute order
-  let execute_msg = ExecuteMsg::ExecuteOrder { order_id: 2 };
+  let execute_msg = ExecuteMsg::ExecuteOrder { order_id: 2, amount: None, referrer: None };
 
   let info = mock_info("buyer", &[Coin{ denom: "uusd".to_string(), amount: Uint128::from(100000000u128) }]);
 
@@ -249,7 +257,7 @@ fn fixed_price_order_test() {
   };
 
   // remake order for test
-  let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder { price: price.clone() };
+  let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder { price: price.clone(), expiration: None, other_prices: None, buyers: None };
 
   let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
     sender: seller.clone(),
@@ -262,7 +270,7 @@ fn fixed_price_order_test() {
   let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
 
   // try to execute order with balance missmatch
-  let execute_msg = Cw20HookMsg::ExecuteOrder { order_id: 3 };
+  let execute_msg = Cw20HookMsg::ExecuteOrder { order_id: 3, amount: None, referrer: None };
 
   let info = mock_info("mir_addr", &[]);
 
@@ -272,15 +280,15 @@ fn fixed_price_order_test() {
     msg: to_binary(&execute_msg).unwrap()
   };
 
-  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg));
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into()));
 
   match res {
-    Err(ContractError::TokenMismatch {}) => assert!(true),
+    Err(ContractError::TokenMismatch {}) => {}
     _ => panic!("Must return token mismatch error"),
   }
 
   // try to execute order with another token
-  let execute_msg = Cw20HookMsg::ExecuteOrder { order_id: 3 };
+  let execute_msg = Cw20HookMsg::ExecuteOrder { order_id: 3, amount: None, referrer: None };
 
   let info = mock_info("shib_addr", &[]);
 
@@ -290,15 +298,15 @@ fn fixed_price_order_test() {
     msg: to_binary(&execute_msg).unwrap()
   };
 
-  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg));
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into()));
 
   match res {
-    Err(ContractError::TokenMismatch {}) => assert!(true),
+    Err(ContractError::TokenMismatch {}) => {}
     _ => panic!("Must return token mismatch error"),
   }
 
   // execute order
-  let execute_msg = Cw20HookMsg::ExecuteOrder { order_id: 3 };
+  let execute_msg = Cw20HookMsg::ExecuteOrder { order_id: 3, amount: None, referrer: None };
 
   let info = mock_info("mir_addr", &[]);
 
@@ -308,7 +316,7 @@ fn fixed_price_order_test() {
     msg: to_binary(&execute_msg).unwrap()
   };
 
-  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg)).unwrap();
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into())).unwrap();
 
   // royalty amounts
   let designer_royalty_asset = Asset {
@@ -353,3 +361,384 @@ fn fixed_price_order_test() {
 
   assert_eq!(order, Ok(None));
 }
+
+#[test]
+fn fixed_price_order_expiration_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::from_ratio(5u128, 1000u128),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  // some assetinfos
+  let uusd: AssetInfo = AssetInfo::NativeToken { denom: "uusd".to_string()};
+
+  // add collection
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: vec![uusd.clone()],
+    royalties: vec![],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  let seller = "seller".to_string();
+
+  let price = Asset{
+    info: uusd.clone(),
+    amount: Uint128::from(100000000u128)
+  };
+
+  // mock_env's info
+  // height: 12_345,
+  // time: Timestamp::from_nanos(1_571_797_419_879_305_533),
+
+  // try to make order with already expired expiration
+  let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder {
+    price: price.clone(),
+    expiration: Some(Expiration::AtHeight(12_000)),
+    other_prices: None,
+    buyers: None
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_fixed_price_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
+
+  match res {
+    Err(ContractError::Expired {}) => {}
+    _ => panic!("Must return expired error"),
+  }
+
+  // make orders
+  for token_id in ["no1", "no2"] {
+    let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder {
+      price: price.clone(),
+      expiration: Some(Expiration::AtHeight(12_400)),
+      other_prices: None,
+      buyers: None
+    };
+
+    let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+      sender: seller.clone(),
+      token_id: token_id.to_string(),
+      msg: to_binary(&make_fixed_price_order_msg).unwrap(),
+    };
+
+    let info = mock_info("spaceship", &[]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+  }
+
+  let order = market.orders.load(&deps.storage, U64Key::new(1)).unwrap();
+
+  assert_eq!(Some(Expiration::AtHeight(12_400)), order.expiration);
+
+  // orders that are not expired are skipped
+  let reclaim_msg = ExecuteMsg::ReclaimExpired { order_ids: vec![1, 2] };
+
+  let info = mock_info("anyone", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, reclaim_msg).unwrap();
+
+  assert_eq!(res.messages, vec![]);
+  assert_eq!(res.attributes[2], attr("order_ids", ""));
+  assert_eq!(res.attributes[3], attr("skipped_order_ids", "1,2"));
+
+  // try to execute expired order
+  let mut mock_env = mock_env();
+  mock_env.block.height = 12_400;
+
+  let execute_msg = ExecuteMsg::ExecuteOrder { order_id: 1, amount: None, referrer: None };
+
+  let info = mock_info("buyer", &[Coin{ denom: "uusd".to_string(), amount: Uint128::from(100000000u128) }]);
+
+  let res = market.execute(deps.as_mut(), mock_env.clone(), info, execute_msg);
+
+  match res {
+    Err(ContractError::Expired {}) => {}
+    _ => panic!("Must return expired error"),
+  }
+
+  // reclaim expired orders. missing order is skipped
+  let reclaim_msg = ExecuteMsg::ReclaimExpired { order_ids: vec![1, 2, 3] };
+
+  let info = mock_info("anyone", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env, info, reclaim_msg).unwrap();
+
+  assert_eq!(res.attributes[2], attr("order_ids", "1,2"));
+  assert_eq!(res.attributes[3], attr("skipped_order_ids", "3"));
+
+  assert_eq!(
+    res.messages,
+    vec![
+      // return nfts to seller
+      SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "spaceship".to_string(),
+        msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+          recipient: "seller".to_string(),
+          token_id: "no1".to_string()
+        }).unwrap(),
+        funds: vec![]
+      })),
+      SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "spaceship".to_string(),
+        msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+          recipient: "seller".to_string(),
+          token_id: "no2".to_string()
+        }).unwrap(),
+        funds: vec![]
+      })),
+    ]
+  );
+
+  // check orders removed
+  assert_eq!(market.orders.may_load(&deps.storage, U64Key::new(1)), Ok(None));
+  assert_eq!(market.orders.may_load(&deps.storage, U64Key::new(2)), Ok(None));
+}
+
+#[test]
+fn multi_asset_fixed_price_order_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::from_ratio(5u128, 1000u128),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  // need tax querier
+  deps.querier.with_tax(
+    Decimal::from_ratio(1u128, 100u128),
+    &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+  );
+
+  let uusd: AssetInfo = AssetInfo::NativeToken { denom: "uusd".to_string()};
+  let mir: AssetInfo = AssetInfo::Token { contract_addr: "mir_addr".to_string()};
+  let shib: AssetInfo = AssetInfo::Token { contract_addr: "shib_addr".to_string()};
+
+  let nft_designer_royalty: Royalty = Royalty {
+    address: Addr::unchecked("nft_designer"),
+    royalty_rate: Decimal::from_ratio(2u128, 100u128)
+  };
+
+  // add collection
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: vec![uusd.clone(), mir.clone()],
+    royalties: vec![nft_designer_royalty],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  let seller = "seller".to_string();
+
+  let uusd_price = Asset { info: uusd.clone(), amount: Uint128::from(100000000u128) };
+  let mir_price = Asset { info: mir.clone(), amount: Uint128::from(50000000u128) };
+
+  // try to make order with unsupport asset in other prices
+  let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder {
+    price: uusd_price.clone(),
+    expiration: None,
+    other_prices: Some(vec![Asset { info: shib, amount: Uint128::from(1u128) }]),
+    buyers: None
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_fixed_price_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
+
+  match res {
+    Err(ContractError::Unsupport {}) => {}
+    _ => panic!("Must return unsupport error"),
+  }
+
+  // try to make order with same asset twice
+  let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder {
+    price: uusd_price.clone(),
+    expiration: None,
+    other_prices: Some(vec![Asset { info: uusd.clone(), amount: Uint128::from(1u128) }]),
+    buyers: None
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_fixed_price_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
+
+  match res {
+    Err(ContractError::DuplicateAsset {}) => {}
+    _ => panic!("Must return duplicate asset error"),
+  }
+
+  // make orders priced in uusd or mir
+  for token_id in ["no1", "no2"] {
+    let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder {
+      price: uusd_price.clone(),
+      expiration: None,
+      other_prices: Some(vec![mir_price.clone()]),
+      buyers: None
+    };
+
+    let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+      sender: seller.clone(),
+      token_id: token_id.to_string(),
+      msg: to_binary(&make_fixed_price_order_msg).unwrap(),
+    };
+
+    let info = mock_info("spaceship", &[]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+  }
+
+  let order = market.orders.load(&deps.storage, U64Key::new(1)).unwrap();
+
+  assert_eq!(Some(uusd_price.clone()), order.price);
+  assert_eq!(vec![mir_price.clone()], order.other_prices);
+
+  // try to update price to the asset of other prices
+  let update_order_msg = ExecuteMsg::UpdateOrder {
+    order_id: 1,
+    price: Some(Asset { info: mir.clone(), amount: Uint128::from(1u128) }),
+    fixed_price: None,
+    expiration: None
+  };
+
+  let info = mock_info("seller", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, update_order_msg);
+
+  match res {
+    Err(ContractError::DuplicateAsset {}) => {}
+    _ => panic!("Must return duplicate asset error"),
+  }
+
+  // try to pay less mir
+  let execute_msg = Cw20HookMsg::ExecuteOrder { order_id: 1, amount: None, referrer: None };
+
+  let info = mock_info("mir_addr", &[]);
+
+  let receive_msg: Cw20ReceiveMsg = Cw20ReceiveMsg {
+    sender: "buyer".to_string(),
+    amount: Uint128::from(49999999u128),
+    msg: to_binary(&execute_msg).unwrap()
+  };
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into()));
+
+  match res {
+    Err(ContractError::TokenMismatch {}) => {}
+    _ => panic!("Must return token mismatch error"),
+  }
+
+  // execute order with mir
+  let execute_msg = Cw20HookMsg::ExecuteOrder { order_id: 1, amount: None, referrer: None };
+
+  let info = mock_info("mir_addr", &[]);
+
+  let receive_msg: Cw20ReceiveMsg = Cw20ReceiveMsg {
+    sender: "buyer".to_string(),
+    amount: Uint128::from(50000000u128),
+    msg: to_binary(&execute_msg).unwrap()
+  };
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into())).unwrap();
+
+  // royalty and remain are paid in mir
+  let designer_royalty_asset = Asset { info: mir.clone(), amount: Uint128::from(1000000u128) };
+  let remain_asset = Asset { info: mir, amount: Uint128::from(49000000u128) };
+
+  assert_eq!(
+    res.messages,
+    vec![
+      SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "spaceship".to_string(),
+        msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+          recipient: "buyer".to_string(),
+          token_id: "no1".to_string()
+        }).unwrap(),
+        funds: vec![]
+      })),
+      SubMsg::new(designer_royalty_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("nft_designer")).unwrap()),
+      SubMsg::new(remain_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("seller")).unwrap()),
+    ]
+  );
+
+  // execute order with uusd
+  let info = mock_info("buyer", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(100000000u128) }]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ExecuteOrder { order_id: 2, amount: None, referrer: None }).unwrap();
+
+  let designer_royalty_asset = Asset { info: uusd.clone(), amount: Uint128::from(2000000u128) };
+  let remain_asset = Asset { info: uusd, amount: Uint128::from(98000000u128) };
+
+  assert_eq!(
+    res.messages,
+    vec![
+      SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "spaceship".to_string(),
+        msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+          recipient: "buyer".to_string(),
+          token_id: "no2".to_string()
+        }).unwrap(),
+        funds: vec![]
+      })),
+      SubMsg::new(designer_royalty_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("nft_designer")).unwrap()),
+      SubMsg::new(remain_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("seller")).unwrap()),
+    ]
+  );
+}
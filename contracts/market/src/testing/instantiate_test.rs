@@ -19,6 +19,10 @@ fn instantiate_test() {
     max_auction_duration_block: 100,
     max_auction_duration_second: 1000,
     auction_cancel_fee_rate: Decimal::from_ratio(5u128, 1000u128),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
   };
 
   let info = mock_info("owner", &[]);
@@ -26,7 +30,7 @@ fn instantiate_test() {
   let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
 
   let config: Config = from_binary(
-    &market.query(deps.as_ref(), QueryMsg::Config {}).unwrap()
+    &market.query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()
   ).unwrap();
 
   assert_eq!("owner".to_string(), config.owner);
@@ -1,7 +1,7 @@
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-  from_slice, to_binary, Coin, ContractResult, Decimal, OwnedDeps, Querier,
-  QuerierResult, QueryRequest, SystemError, SystemResult, Uint128,
+  from_binary, from_slice, to_binary, Coin, ContractResult, Decimal, OwnedDeps, Querier,
+  QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -29,6 +29,44 @@ pub fn mock_dependencies(
 pub struct WasmMockQuerier {
   base: MockQuerier<TerraQueryWrapper>,
   tax_querier: TaxQuerier,
+  token_querier: TokenQuerier,
+}
+
+#[derive(Clone, Default)]
+pub struct TokenQuerier {
+  // this lets us iterate over all pairs that match the first string
+  balances: HashMap<String, HashMap<String, Uint128>>,
+}
+
+impl TokenQuerier {
+  pub fn new(balances: &[(&String, &[(&String, &Uint128)])]) -> Self {
+    TokenQuerier {
+      balances: balances_to_map(balances),
+    }
+  }
+}
+
+pub(crate) fn balances_to_map(
+  balances: &[(&String, &[(&String, &Uint128)])],
+) -> HashMap<String, HashMap<String, Uint128>> {
+  let mut balances_map: HashMap<String, HashMap<String, Uint128>> = HashMap::new();
+  for (contract_addr, balances) in balances.iter() {
+    balances_map.insert(contract_addr.to_string(), caps_to_map(balances));
+  }
+  balances_map
+}
+
+// cw20 balance query and staked balance query of staking contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum BalanceQueryMsg {
+  Balance { address: String },
+  StakedBalance { address: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+struct BalanceResponse {
+  balance: Uint128,
 }
 
 #[derive(Clone, Default)]
@@ -72,12 +110,6 @@ impl Querier for WasmMockQuerier {
   }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-#[serde(rename_all = "snake_case")]
-pub enum MockQueryMsg {
-  Price {},
-}
-
 impl WasmMockQuerier {
   pub fn handle_query(&self, request: &QueryRequest<TerraQueryWrapper>) -> QuerierResult {
     match &request {
@@ -119,6 +151,22 @@ impl WasmMockQuerier {
           panic!("DO NOT ENTER HERE")
         }
       }
+      QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+        let address = match from_binary(msg).unwrap() {
+          BalanceQueryMsg::Balance { address } => address,
+          BalanceQueryMsg::StakedBalance { address } => address,
+        };
+
+        // contract without balances does not exist
+        let balances = match self.token_querier.balances.get(contract_addr) {
+          Some(balances) => balances,
+          None => return SystemResult::Err(SystemError::NoSuchContract { addr: contract_addr.clone() })
+        };
+
+        let balance = balances.get(&address).copied().unwrap_or_default();
+
+        SystemResult::Ok(ContractResult::from(to_binary(&BalanceResponse { balance })))
+      }
       _ => self.base.handle_query(request),
     }
   }
@@ -129,10 +177,15 @@ impl WasmMockQuerier {
     WasmMockQuerier {
       base,
       tax_querier: TaxQuerier::default(),
+      token_querier: TokenQuerier::default(),
     }
   }
 
   pub fn with_tax(&mut self, rate: Decimal, caps: &[(&String, &Uint128)]) {
     self.tax_querier = TaxQuerier::new(rate, caps);
   }
+
+  pub fn with_token_balances(&mut self, balances: &[(&String, &[(&String, &Uint128)])]) {
+    self.token_querier = TokenQuerier::new(balances);
+  }
 }
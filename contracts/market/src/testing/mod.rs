@@ -2,4 +2,17 @@ mod mock_querier;
 mod instantiate_test;
 mod config_collection_test;
 mod fixed_price_test;
-mod auction_test;
\ No newline at end of file
+mod auction_test;
+mod offer_test;
+mod update_order_test;
+mod bundle_test;
+mod dutch_auction_test;
+mod sealed_bid_test;
+mod private_order_test;
+mod swap_test;
+mod cw1155_test;
+mod sweep_test;
+mod order_query_test;
+mod trade_history_test;
+mod stats_test;
+mod protocol_fee_test;
\ No newline at end of file
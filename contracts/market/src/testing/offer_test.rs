@@ -0,0 +1,587 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{to_binary, from_binary, Addr, Coin, CosmosMsg, Decimal, SubMsg, WasmMsg, Uint128};
+use cw_storage_plus::U64Key;
+use cw721::{Cw721ExecuteMsg, Cw721ReceiveMsg};
+use cw20::Cw20ReceiveMsg;
+use cw0::Expiration;
+
+use crate::{
+  state::{MarketContract, Offer, Royalty},
+  msgs::{InstantiateMsg, ExecuteMsg, Cw721HookMsg, Cw20HookMsg, QueryMsg},
+  error::ContractError,
+  asset::{Asset, AssetInfo},
+  testing::mock_querier::mock_dependencies
+};
+
+#[test]
+fn collection_offer_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  // need tax querier
+  deps.querier.with_tax(
+    Decimal::from_ratio(1u128, 100u128),
+    &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+  );
+
+  // some assetinfos
+  let uusd: AssetInfo = AssetInfo::NativeToken { denom: "uusd".to_string()};
+  let mir: AssetInfo = AssetInfo::Token { contract_addr: "mir_addr".to_string()};
+
+  // some royalties
+  let nft_designer_royalty: Royalty = Royalty {
+    address: Addr::unchecked("nft_designer"),
+    royalty_rate: Decimal::from_ratio(2u128, 100u128)
+  };
+
+  let nft_pm_royalty: Royalty = Royalty {
+    address: Addr::unchecked("nft_pm"),
+    royalty_rate: Decimal::from_ratio(3u128, 100u128)
+  };
+
+  // add collections
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: vec![uusd.clone(), mir.clone()],
+    royalties: vec![nft_designer_royalty.clone(), nft_pm_royalty.clone()],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "rocket".to_string(),
+    support_assets: vec![uusd.clone()],
+    royalties: vec![],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  let price = Asset {
+    info: uusd.clone(),
+    amount: Uint128::from(100000000u128)
+  };
+
+  // try to make offer with balance mismatch
+  let make_offer_msg = ExecuteMsg::MakeCollectionOffer {
+    nft_address: "spaceship".to_string(),
+    price: price.clone()
+  };
+
+  let info = mock_info("buyer", &[Coin{ denom: "uusd".to_string(), amount: Uint128::from(12312412u128) }]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, make_offer_msg);
+
+  match res {
+    Err(ContractError::Std(_)) => {}
+    _ => panic!("Must return std error"),
+  }
+
+  // try to make offer with cw20 price without sending token
+  let make_offer_msg = ExecuteMsg::MakeCollectionOffer {
+    nft_address: "spaceship".to_string(),
+    price: Asset { info: mir.clone(), amount: Uint128::from(100000000u128) }
+  };
+
+  let info = mock_info("buyer", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, make_offer_msg);
+
+  match res {
+    Err(ContractError::TokenMismatch {}) => {}
+    _ => panic!("Must return token mismatch error"),
+  }
+
+  // try to make offer with unsupport asset
+  let make_offer_msg = Cw20HookMsg::MakeCollectionOffer { nft_address: "spaceship".to_string() };
+
+  let info = mock_info("shib_addr", &[]);
+
+  let receive_msg: Cw20ReceiveMsg = Cw20ReceiveMsg {
+    sender: "buyer".to_string(),
+    amount: Uint128::from(100000000u128),
+    msg: to_binary(&make_offer_msg).unwrap()
+  };
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into()));
+
+  match res {
+    Err(ContractError::Unsupport {}) => {}
+    _ => panic!("Must return unsupport error"),
+  }
+
+  // make offer (native)
+  let make_offer_msg = ExecuteMsg::MakeCollectionOffer {
+    nft_address: "spaceship".to_string(),
+    price: price.clone()
+  };
+
+  let info = mock_info("buyer", &[Coin{ denom: "uusd".to_string(), amount: Uint128::from(100000000u128) }]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, make_offer_msg).unwrap();
+
+  let offer = market.offers.load(&deps.storage, U64Key::new(1)).unwrap();
+
+  assert_eq!(
+    Offer {
+      id: 1,
+      offerer: Addr::unchecked("buyer"),
+      nft_address: Addr::unchecked("spaceship"),
+      token_id: None,
+      price: price.clone(),
+      expiration: Expiration::Never {},
+    },
+    offer
+  );
+
+  // make offer (cw20)
+  let make_offer_msg = Cw20HookMsg::MakeCollectionOffer { nft_address: "spaceship".to_string() };
+
+  let info = mock_info("mir_addr", &[]);
+
+  let receive_msg: Cw20ReceiveMsg = Cw20ReceiveMsg {
+    sender: "buyer2".to_string(),
+    amount: Uint128::from(100000000u128),
+    msg: to_binary(&make_offer_msg).unwrap()
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into())).unwrap();
+
+  let offer_index = market.offer_index.load(&deps.storage).unwrap();
+
+  assert_eq!(3, offer_index);
+
+  // query offers by collection
+  let offers: Vec<Offer> = from_binary(
+    &market.query(deps.as_ref(), mock_env(), QueryMsg::Offers {
+      nft_address: Some("spaceship".to_string()),
+      start_after: None,
+      limit: None
+    }).unwrap()
+  ).unwrap();
+
+  assert_eq!(2, offers.len());
+
+  let offers: Vec<Offer> = from_binary(
+    &market.query(deps.as_ref(), mock_env(), QueryMsg::Offers {
+      nft_address: Some("rocket".to_string()),
+      start_after: None,
+      limit: None
+    }).unwrap()
+  ).unwrap();
+
+  assert_eq!(0, offers.len());
+
+  // who is not offerer try to cancel offer
+  let cancel_msg = ExecuteMsg::CancelOffer { offer_id: 2 };
+
+  let info = mock_info("buyer", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, cancel_msg);
+
+  match res {
+    Err(ContractError::Unauthorized {}) => {}
+    _ => panic!("Must return unauthorized error"),
+  }
+
+  // cancel offer
+  let cancel_msg = ExecuteMsg::CancelOffer { offer_id: 2 };
+
+  let info = mock_info("buyer2", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, cancel_msg).unwrap();
+
+  let refund_asset = Asset {
+    info: mir.clone(),
+    amount: Uint128::from(100000000u128)
+  };
+
+  assert_eq!(
+    res.messages,
+    vec![SubMsg::new(refund_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("buyer2")).unwrap())]
+  );
+
+  let offer = market.offers.may_load(&deps.storage, U64Key::new(2));
+
+  assert_eq!(offer, Ok(None));
+
+  // try to accept offer with nft of another collection
+  let accept_msg = Cw721HookMsg::AcceptCollectionOffer { offer_id: 1 };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "seller".to_string(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&accept_msg).unwrap(),
+  };
+
+  let info = mock_info("rocket", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
+
+  match res {
+    Err(ContractError::CollectionMismatch {}) => {}
+    _ => panic!("Must return collection mismatch error"),
+  }
+
+  // accept offer
+  let accept_msg = Cw721HookMsg::AcceptCollectionOffer { offer_id: 1 };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "seller".to_string(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&accept_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  // royalty amounts
+  let designer_royalty_asset = Asset {
+    info: uusd.clone(),
+    amount: Uint128::from(2000000u128)
+  };
+
+  let pm_royalty_asset = Asset {
+    info: uusd.clone(),
+    amount: Uint128::from(3000000u128)
+  };
+
+  // remain asset
+  let remain_asset = Asset {
+    info: uusd.clone(),
+    amount: Uint128::from(95000000u128)
+  };
+
+  assert_eq!(
+    res.messages,
+    vec![
+      // transfer nft to offerer
+      SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "spaceship".to_string(),
+        msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+          recipient: "buyer".to_string(),
+          token_id: "no1".to_string()
+        }).unwrap(),
+        funds: vec![]
+      })),
+      // transfer royalties
+      SubMsg::new(designer_royalty_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("nft_designer")).unwrap()),
+      SubMsg::new(pm_royalty_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("nft_pm")).unwrap()),
+      // transfer remain to seller
+      SubMsg::new(remain_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("seller")).unwrap()),
+    ]
+  );
+
+  // check offer removed
+  let offer = market.offers.may_load(&deps.storage, U64Key::new(1));
+
+  assert_eq!(offer, Ok(None));
+}
+
+#[test]
+fn token_offer_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  // some assetinfos
+  let uusd: AssetInfo = AssetInfo::NativeToken { denom: "uusd".to_string()};
+  let mir: AssetInfo = AssetInfo::Token { contract_addr: "mir_addr".to_string()};
+
+  // some royalties
+  let nft_designer_royalty: Royalty = Royalty {
+    address: Addr::unchecked("nft_designer"),
+    royalty_rate: Decimal::from_ratio(2u128, 100u128)
+  };
+
+  // add collection
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: vec![uusd.clone(), mir.clone()],
+    royalties: vec![nft_designer_royalty.clone()],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  // mock_env's info
+  // height: 12_345,
+  // time: Timestamp::from_nanos(1_571_797_419_879_305_533),
+
+  // try to make offer with already expired expiration
+  let make_offer_msg = Cw20HookMsg::MakeOffer {
+    nft_address: "spaceship".to_string(),
+    token_id: "no1".to_string(),
+    expiration: Expiration::AtHeight(12_000)
+  };
+
+  let info = mock_info("mir_addr", &[]);
+
+  let receive_msg: Cw20ReceiveMsg = Cw20ReceiveMsg {
+    sender: "buyer".to_string(),
+    amount: Uint128::from(100000000u128),
+    msg: to_binary(&make_offer_msg).unwrap()
+  };
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into()));
+
+  match res {
+    Err(ContractError::Expired {}) => {}
+    _ => panic!("Must return expired error"),
+  }
+
+  // make offers
+  let make_offer_msg = Cw20HookMsg::MakeOffer {
+    nft_address: "spaceship".to_string(),
+    token_id: "no1".to_string(),
+    expiration: Expiration::AtHeight(12_400)
+  };
+
+  let info = mock_info("mir_addr", &[]);
+
+  let receive_msg: Cw20ReceiveMsg = Cw20ReceiveMsg {
+    sender: "buyer".to_string(),
+    amount: Uint128::from(100000000u128),
+    msg: to_binary(&make_offer_msg).unwrap()
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into())).unwrap();
+
+  let make_offer_msg = Cw20HookMsg::MakeOffer {
+    nft_address: "spaceship".to_string(),
+    token_id: "no2".to_string(),
+    expiration: Expiration::AtHeight(12_400)
+  };
+
+  let info = mock_info("mir_addr", &[]);
+
+  let receive_msg: Cw20ReceiveMsg = Cw20ReceiveMsg {
+    sender: "buyer".to_string(),
+    amount: Uint128::from(50000000u128),
+    msg: to_binary(&make_offer_msg).unwrap()
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into())).unwrap();
+
+  let price = Asset {
+    info: mir.clone(),
+    amount: Uint128::from(100000000u128)
+  };
+
+  let offer = market.offers.load(&deps.storage, U64Key::new(1)).unwrap();
+
+  assert_eq!(
+    Offer {
+      id: 1,
+      offerer: Addr::unchecked("buyer"),
+      nft_address: Addr::unchecked("spaceship"),
+      token_id: Some("no1".to_string()),
+      price: price.clone(),
+      expiration: Expiration::AtHeight(12_400),
+    },
+    offer
+  );
+
+  // query offers by token
+  let offers: Vec<Offer> = from_binary(
+    &market.query(deps.as_ref(), mock_env(), QueryMsg::OffersByToken {
+      nft_address: "spaceship".to_string(),
+      token_id: "no1".to_string(),
+      start_after: None,
+      limit: None
+    }).unwrap()
+  ).unwrap();
+
+  assert_eq!(vec![offer.clone()], offers);
+
+  // query offers by offerer
+  let offers: Vec<Offer> = from_binary(
+    &market.query(deps.as_ref(), mock_env(), QueryMsg::OffersByOfferer {
+      offerer: "buyer".to_string(),
+      start_after: Some(1),
+      limit: None
+    }).unwrap()
+  ).unwrap();
+
+  assert_eq!(1, offers.len());
+  assert_eq!(2, offers[0].id);
+
+  // try to accept token offer as collection offer
+  let accept_msg = Cw721HookMsg::AcceptCollectionOffer { offer_id: 2 };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "owner_of_no3".to_string(),
+    token_id: "no3".to_string(),
+    msg: to_binary(&accept_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
+
+  match res {
+    Err(ContractError::TokenIdMismatch {}) => {}
+    _ => panic!("Must return token id mismatch error"),
+  }
+
+  // try to accept offer with another token
+  let accept_msg = Cw721HookMsg::AcceptOffer { offer_id: 1 };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "owner_of_no3".to_string(),
+    token_id: "no3".to_string(),
+    msg: to_binary(&accept_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
+
+  match res {
+    Err(ContractError::TokenIdMismatch {}) => {}
+    _ => panic!("Must return token id mismatch error"),
+  }
+
+  // accept offer
+  let accept_msg = Cw721HookMsg::AcceptOffer { offer_id: 1 };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "owner_of_no1".to_string(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&accept_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  // royalty amounts
+  let designer_royalty_asset = Asset {
+    info: mir.clone(),
+    amount: Uint128::from(2000000u128)
+  };
+
+  // remain asset
+  let remain_asset = Asset {
+    info: mir.clone(),
+    amount: Uint128::from(98000000u128)
+  };
+
+  assert_eq!(
+    res.messages,
+    vec![
+      // transfer nft to offerer
+      SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "spaceship".to_string(),
+        msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+          recipient: "buyer".to_string(),
+          token_id: "no1".to_string()
+        }).unwrap(),
+        funds: vec![]
+      })),
+      // transfer royalties
+      SubMsg::new(designer_royalty_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("nft_designer")).unwrap()),
+      // transfer remain to seller
+      SubMsg::new(remain_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("owner_of_no1")).unwrap()),
+    ]
+  );
+
+  // check offer removed
+  let offer = market.offers.may_load(&deps.storage, U64Key::new(1));
+
+  assert_eq!(offer, Ok(None));
+
+  // try to accept expired offer
+  let mut mock_env = mock_env();
+  mock_env.block.height = 12_400;
+
+  let accept_msg = Cw721HookMsg::AcceptOffer { offer_id: 2 };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "owner_of_no2".to_string(),
+    token_id: "no2".to_string(),
+    msg: to_binary(&accept_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env.clone(), info, ExecuteMsg::ReceiveNft(receive_msg));
+
+  match res {
+    Err(ContractError::Expired {}) => {}
+    _ => panic!("Must return expired error"),
+  }
+
+  // withdraw expired offer
+  let cancel_msg = ExecuteMsg::CancelOffer { offer_id: 2 };
+
+  let info = mock_info("buyer", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env.clone(), info, cancel_msg).unwrap();
+
+  let refund_asset = Asset {
+    info: mir.clone(),
+    amount: Uint128::from(50000000u128)
+  };
+
+  assert_eq!(
+    res.messages,
+    vec![SubMsg::new(refund_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("buyer")).unwrap())]
+  );
+
+  let offers: Vec<Offer> = from_binary(
+    &market.query(deps.as_ref(), mock_env, QueryMsg::OffersByOfferer {
+      offerer: "buyer".to_string(),
+      start_after: None,
+      limit: None
+    }).unwrap()
+  ).unwrap();
+
+  assert_eq!(0, offers.len());
+}
@@ -0,0 +1,390 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{from_binary, to_binary, Addr, Decimal, Deps, Env, Uint128};
+use cw0::Expiration;
+use cw721::Cw721ReceiveMsg;
+
+use crate::{
+  state::{MarketContract, Order, OrderType},
+  msgs::{InstantiateMsg, ExecuteMsg, Cw721HookMsg, OrderSort, OrderStatus, OrdersCountResponse, QueryMsg},
+  error::ContractError,
+  asset::{Asset, AssetInfo},
+  testing::mock_querier::mock_dependencies
+};
+
+#[test]
+fn orders_by_collection_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  let uusd: AssetInfo = AssetInfo::NativeToken { denom: "uusd".to_string()};
+  let mir: AssetInfo = AssetInfo::Token { contract_addr: "mir_addr".to_string()};
+
+  // add collections
+  for nft_address in ["spaceship", "alien"] {
+    let info = mock_info("owner", &[]);
+    let add_collection_msg = ExecuteMsg::AddCollection {
+      nft_address: nft_address.to_string(),
+      support_assets: vec![uusd.clone(), mir.clone()],
+      royalties: vec![],
+      auction_extension: None,
+      swap_royalty: None,
+      protocol_fee_rate: None,
+    };
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+  }
+
+  // make orders. order 2 is private and order 4 expires at height 12400
+  let orders = [
+    ("spaceship", "no1", 30000000u128, None, None),
+    ("spaceship", "no2", 10000000u128, Some(vec!["friend".to_string()]), None),
+    ("spaceship", "no3", 20000000u128, None, None),
+    ("spaceship", "no4", 15000000u128, None, Some(Expiration::AtHeight(12400))),
+    ("alien", "no1", 1000000u128, None, None),
+  ];
+
+  for (nft_address, token_id, amount, buyers, expiration) in orders.iter() {
+    let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder {
+      price: Asset { info: uusd.clone(), amount: Uint128::from(*amount) },
+      expiration: *expiration,
+      other_prices: None,
+      buyers: buyers.clone()
+    };
+
+    let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+      sender: "seller".to_string(),
+      token_id: token_id.to_string(),
+      msg: to_binary(&make_fixed_price_order_msg).unwrap(),
+    };
+
+    let info = mock_info(nft_address, &[]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+  }
+
+  let order_ids = |sort: OrderSort, asset: Option<AssetInfo>, start_after: Option<u64>, limit: Option<u32>| -> Vec<u64> {
+    let query_msg = QueryMsg::OrdersByCollection { nft_address: "spaceship".to_string(), asset, sort, start_after, limit };
+    let orders: Vec<Order> = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+    orders.iter().map(|order| order.id).collect()
+  };
+
+  // sort by price
+  assert_eq!(order_ids(OrderSort::PriceAsc, Some(uusd.clone()), None, None), vec![2, 4, 3, 1]);
+  assert_eq!(order_ids(OrderSort::PriceAsc, Some(uusd.clone()), Some(4), Some(2)), vec![3, 1]);
+  assert_eq!(order_ids(OrderSort::PriceDesc, Some(uusd.clone()), None, None), vec![1, 3, 4, 2]);
+  assert_eq!(order_ids(OrderSort::PriceDesc, Some(uusd.clone()), Some(3), None), vec![4, 2]);
+  assert_eq!(order_ids(OrderSort::PriceAsc, Some(mir.clone()), None, None), Vec::<u64>::new());
+
+  // sort by newest
+  assert_eq!(order_ids(OrderSort::Newest, None, None, None), vec![4, 3, 2, 1]);
+  assert_eq!(order_ids(OrderSort::Newest, None, Some(3), None), vec![2, 1]);
+
+  // price sort needs asset
+  let query_msg = QueryMsg::OrdersByCollection {
+    nft_address: "spaceship".to_string(),
+    asset: None,
+    sort: OrderSort::PriceAsc,
+    start_after: None,
+    limit: None
+  };
+
+  assert!(market.query(deps.as_ref(), mock_env(), query_msg).is_err());
+
+  // floor price ignores private order
+  let floor_price_msg = QueryMsg::FloorPrice { nft_address: "spaceship".to_string(), asset: uusd.clone() };
+
+  let floor_price: Option<Asset> = from_binary(&market.query(deps.as_ref(), mock_env(), floor_price_msg.clone()).unwrap()).unwrap();
+
+  assert_eq!(floor_price, Some(Asset { info: uusd.clone(), amount: Uint128::from(15000000u128) }));
+
+  // and expired order
+  let mut env = mock_env();
+  env.block.height = 12400;
+
+  let floor_price: Option<Asset> = from_binary(&market.query(deps.as_ref(), env, floor_price_msg).unwrap()).unwrap();
+
+  assert_eq!(floor_price, Some(Asset { info: uusd, amount: Uint128::from(20000000u128) }));
+
+  let floor_price_msg = QueryMsg::FloorPrice { nft_address: "spaceship".to_string(), asset: mir };
+
+  let floor_price: Option<Asset> = from_binary(&market.query(deps.as_ref(), mock_env(), floor_price_msg).unwrap()).unwrap();
+
+  assert_eq!(floor_price, None);
+}
+
+fn order_by_token(market: &MarketContract, deps: Deps, token_id: &str) -> Option<u64> {
+  let query_msg = QueryMsg::OrderByToken { nft_address: "spaceship".to_string(), token_id: token_id.to_string() };
+  let order: Option<Order> = from_binary(&market.query(deps, mock_env(), query_msg).unwrap()).unwrap();
+
+  order.map(|order| order.id)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn orders(
+  market: &MarketContract,
+  deps: Deps,
+  env: Env,
+  seller_address: Option<Addr>,
+  nft_address: Option<String>,
+  order_type: Option<OrderType>,
+  asset: Option<AssetInfo>,
+  status: Option<OrderStatus>,
+  start_after: Option<u64>,
+  limit: Option<u32>,
+  descending: Option<bool>
+) -> (Vec<u64>, u64) {
+  let query_msg = QueryMsg::Orders {
+    seller_address: seller_address.clone(),
+    nft_address: nft_address.clone(),
+    order_type: order_type.clone(),
+    asset: asset.clone(),
+    status: status.clone(),
+    start_after,
+    limit,
+    descending
+  };
+  let res: Vec<Order> = from_binary(&market.query(deps, env.clone(), query_msg).unwrap()).unwrap();
+
+  // count all orders matching the filters
+  let query_msg = QueryMsg::OrdersCount { seller_address, nft_address, order_type, asset, status, start_after: None };
+  let count: OrdersCountResponse = from_binary(&market.query(deps, env, query_msg).unwrap()).unwrap();
+
+  (res.iter().map(|order| order.id).collect(), count.count)
+}
+
+#[test]
+fn order_by_token_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  let uusd: AssetInfo = AssetInfo::NativeToken { denom: "uusd".to_string()};
+  let price = Asset { info: uusd.clone(), amount: Uint128::from(10000000u128) };
+
+  // add collection
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: vec![uusd],
+    royalties: vec![],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  let make_fixed_price_order_msg = to_binary(&Cw721HookMsg::MakeFixedPriceOrder {
+    price: price.clone(),
+    expiration: None,
+    other_prices: None,
+    buyers: None
+  }).unwrap();
+
+  // make order of no1
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "seller".to_string(),
+    token_id: "no1".to_string(),
+    msg: make_fixed_price_order_msg.clone(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg.clone())).unwrap();
+
+  // try to list no1 again
+  let info = mock_info("spaceship", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
+
+  match res {
+    Err(ContractError::AlreadyListed {}) => {}
+    _ => panic!("Must return already listed error"),
+  }
+
+  // make bundle order of no2 and no3
+  for token_id in ["no2", "no3"] {
+    let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+      sender: "seller".to_string(),
+      token_id: token_id.to_string(),
+      msg: to_binary(&Cw721HookMsg::AddToBundle {}).unwrap(),
+    };
+
+    let info = mock_info("spaceship", &[]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+  }
+
+  let make_bundle_order_msg = ExecuteMsg::MakeBundleFixedPriceOrder { price, expiration: None, other_prices: None, buyers: None };
+
+  let info = mock_info("seller", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, make_bundle_order_msg).unwrap();
+
+  assert_eq!(order_by_token(&market, deps.as_ref(), "no1"), Some(1));
+  assert_eq!(order_by_token(&market, deps.as_ref(), "no3"), Some(2));
+  assert_eq!(order_by_token(&market, deps.as_ref(), "no4"), None);
+
+  // cancelled order is removed from the index
+  let info = mock_info("seller", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::CancelOrder { order_id: 1 }).unwrap();
+
+  assert_eq!(order_by_token(&market, deps.as_ref(), "no1"), None);
+}
+
+
+#[test]
+fn orders_filter_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  let uusd: AssetInfo = AssetInfo::NativeToken { denom: "uusd".to_string()};
+  let mir: AssetInfo = AssetInfo::Token { contract_addr: "mir_addr".to_string()};
+
+  // add collections
+  for nft_address in ["spaceship", "alien"] {
+    let info = mock_info("owner", &[]);
+    let add_collection_msg = ExecuteMsg::AddCollection {
+      nft_address: nft_address.to_string(),
+      support_assets: vec![uusd.clone(), mir.clone()],
+      royalties: vec![],
+      auction_extension: None,
+      swap_royalty: None,
+      protocol_fee_rate: None,
+    };
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+  }
+
+  let price = |info: &AssetInfo| Asset { info: info.clone(), amount: Uint128::from(10000000u128) };
+
+  // 1: fixed uusd, 2: fixed mir expiring at 12400, 3: auction, 4: auction with fixed price, 5: fixed uusd of seller2
+  let orders_ = [
+    ("spaceship", "seller", "no1", Cw721HookMsg::MakeFixedPriceOrder {
+      price: price(&uusd), expiration: None, other_prices: None, buyers: None
+    }),
+    ("spaceship", "seller", "no2", Cw721HookMsg::MakeFixedPriceOrder {
+      price: price(&mir), expiration: Some(Expiration::AtHeight(12400)), other_prices: None, buyers: None
+    }),
+    ("spaceship", "seller", "no3", Cw721HookMsg::MakeAuctionOrder {
+      start_price: price(&uusd), expiration: Expiration::AtHeight(12400), fixed_price: None, reserve_price: None, second_price: None, buyers: None
+    }),
+    ("spaceship", "seller", "no4", Cw721HookMsg::MakeAuctionOrder {
+      start_price: price(&uusd), expiration: Expiration::AtHeight(12400), fixed_price: Some(price(&uusd)), reserve_price: None, second_price: None, buyers: None
+    }),
+    ("alien", "seller2", "no1", Cw721HookMsg::MakeFixedPriceOrder {
+      price: price(&uusd), expiration: None, other_prices: None, buyers: None
+    }),
+  ];
+
+  for (nft_address, seller, token_id, msg) in orders_.iter() {
+    let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+      sender: seller.to_string(),
+      token_id: token_id.to_string(),
+      msg: to_binary(msg).unwrap(),
+    };
+
+    let info = mock_info(nft_address, &[]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+  }
+
+  let mut expired_env = mock_env();
+  expired_env.block.height = 12400;
+
+  // pagination
+  assert_eq!(orders(&market, deps.as_ref(), mock_env(), None, None, None, None, None, None, Some(2), None), (vec![1, 2], 5));
+  assert_eq!(orders(&market, deps.as_ref(), mock_env(), None, None, None, None, None, Some(4), None, Some(true)), (vec![3, 2, 1], 5));
+
+  // count from start_after
+  let query_msg = QueryMsg::OrdersCount {
+    seller_address: None,
+    nft_address: None,
+    order_type: None,
+    asset: None,
+    status: Some(OrderStatus::Expired),
+    start_after: Some(2)
+  };
+  let res: OrdersCountResponse = from_binary(&market.query(deps.as_ref(), expired_env.clone(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(res, OrdersCountResponse { count: 2, last_order_id: None });
+
+  // collection and order type
+  assert_eq!(
+    orders(&market, deps.as_ref(), mock_env(), None, Some("spaceship".to_string()), Some(OrderType::Fixed), None, None, None, None, None),
+    (vec![1, 2], 2)
+  );
+  assert_eq!(orders(&market, deps.as_ref(), mock_env(), None, None, Some(OrderType::Auction), None, None, None, None, None), (vec![3], 1));
+  assert_eq!(orders(&market, deps.as_ref(), mock_env(), None, None, Some(OrderType::FixedAuction), None, None, None, None, None), (vec![4], 1));
+
+  // asset
+  assert_eq!(orders(&market, deps.as_ref(), mock_env(), None, None, None, Some(mir), None, None, None, None), (vec![2], 1));
+
+  // status
+  assert_eq!(
+    orders(&market, deps.as_ref(), expired_env.clone(), None, None, None, None, Some(OrderStatus::Expired), None, None, None),
+    (vec![2, 3, 4], 3)
+  );
+  assert_eq!(
+    orders(&market, deps.as_ref(), expired_env, None, None, None, None, Some(OrderStatus::Active), None, None, None),
+    (vec![1, 5], 2)
+  );
+
+  // seller
+  assert_eq!(orders(&market, deps.as_ref(), mock_env(), Some(Addr::unchecked("seller2")), None, None, None, None, None, None, None), (vec![5], 1));
+}
\ No newline at end of file
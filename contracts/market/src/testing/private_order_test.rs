@@ -0,0 +1,183 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{from_binary, to_binary, Addr, Coin, Decimal, Uint128};
+use cw_storage_plus::U64Key;
+use cw721::Cw721ReceiveMsg;
+use cw0::Expiration;
+
+use crate::{
+  state::{MarketContract, Order},
+  msgs::{InstantiateMsg, ExecuteMsg, Cw721HookMsg, QueryMsg},
+  error::ContractError,
+  asset::{Asset, AssetInfo},
+  testing::mock_querier::mock_dependencies
+};
+
+#[test]
+fn private_order_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::from_ratio(5u128, 1000u128),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  // need tax querier
+  deps.querier.with_tax(
+    Decimal::from_ratio(1u128, 100u128),
+    &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+  );
+
+  let uusd: AssetInfo = AssetInfo::NativeToken { denom: "uusd".to_string()};
+
+  // add collection
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: vec![uusd.clone()],
+    royalties: vec![],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  let price = Asset { info: uusd.clone(), amount: Uint128::from(100000000u128) };
+
+  // make private fixed price order
+  let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder {
+    price: price.clone(),
+    expiration: None,
+    other_prices: None,
+    buyers: Some(vec!["buyer".to_string()])
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "seller".to_string(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_fixed_price_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  let order = market.orders.load(&deps.storage, U64Key::new(1)).unwrap();
+
+  assert_eq!(vec![Addr::unchecked("buyer")], order.buyers);
+
+  // make private auction order
+  let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder {
+    start_price: Asset { info: uusd.clone(), amount: Uint128::from(10000000u128) },
+    expiration: Expiration::AtHeight(12_400),
+    fixed_price: None,
+    reserve_price: None,
+    second_price: None,
+    buyers: Some(vec!["buyer".to_string(), "buyer2".to_string()])
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "seller".to_string(),
+    token_id: "no2".to_string(),
+    msg: to_binary(&make_auction_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  // make public order
+  let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder {
+    price: price.clone(),
+    expiration: None,
+    other_prices: None,
+    buyers: None
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "seller".to_string(),
+    token_id: "no3".to_string(),
+    msg: to_binary(&make_fixed_price_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  // query orders by buyer
+  let query_msg = QueryMsg::OrdersByBuyer { buyer: "buyer".to_string(), start_after: None, limit: None };
+  let orders: Vec<Order> = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(vec![1, 2], orders.iter().map(|order| order.id).collect::<Vec<u64>>());
+
+  let query_msg = QueryMsg::OrdersByBuyer { buyer: "buyer2".to_string(), start_after: None, limit: None };
+  let orders: Vec<Order> = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(vec![2], orders.iter().map(|order| order.id).collect::<Vec<u64>>());
+
+  let query_msg = QueryMsg::OrdersByBuyer { buyer: "buyer".to_string(), start_after: Some(1), limit: None };
+  let orders: Vec<Order> = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(vec![2], orders.iter().map(|order| order.id).collect::<Vec<u64>>());
+
+  // who is not allowed try to execute private order
+  let info = mock_info("stranger", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(100000000u128) }]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ExecuteOrder { order_id: 1, amount: None, referrer: None });
+
+  match res {
+    Err(ContractError::NotAllowedBuyer {}) => {}
+    _ => panic!("Must return not allowed buyer error"),
+  }
+
+  // who is not allowed try to bid on private auction
+  let bid_msg = ExecuteMsg::Bid {
+    order_id: 2,
+    bid_price: Asset { info: uusd.clone(), amount: Uint128::from(20000000u128) },
+    referrer: None
+  };
+
+  let info = mock_info("stranger", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(20000000u128) }]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, bid_msg.clone());
+
+  match res {
+    Err(ContractError::NotAllowedBuyer {}) => {}
+    _ => panic!("Must return not allowed buyer error"),
+  }
+
+  // allowed buyer bid
+  let info = mock_info("buyer2", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(20000000u128) }]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, bid_msg).unwrap();
+
+  // anyone can buy public order
+  let info = mock_info("stranger", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(100000000u128) }]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ExecuteOrder { order_id: 3, amount: None, referrer: None }).unwrap();
+
+  // allowed buyer execute private order
+  let info = mock_info("buyer", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(100000000u128) }]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ExecuteOrder { order_id: 1, amount: None, referrer: None }).unwrap();
+
+  // executed order is removed from buyer index
+  let query_msg = QueryMsg::OrdersByBuyer { buyer: "buyer".to_string(), start_after: None, limit: None };
+  let orders: Vec<Order> = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(vec![2], orders.iter().map(|order| order.id).collect::<Vec<u64>>());
+}
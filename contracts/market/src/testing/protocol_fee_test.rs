@@ -0,0 +1,522 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{attr, from_binary, to_binary, Addr, Decimal, DepsMut, SubMsg, Uint128};
+use cw721::Cw721ReceiveMsg;
+use cw20::Cw20ReceiveMsg;
+use cw0::Expiration;
+use cw_storage_plus::U64Key;
+
+use crate::{
+  error::ContractError,
+  state::{FeeDiscount, FeeDiscountSource, FeeTier, MarketContract, Royalty, Trade},
+  msgs::{InstantiateMsg, ExecuteMsg, Cw20HookMsg, Cw721HookMsg, FeeQuoteResponse, QueryMsg},
+  asset::{Asset, AssetInfo},
+  testing::mock_querier::mock_dependencies
+};
+
+#[test]
+fn protocol_fee_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::from_ratio(2u128, 100u128),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  let mir: AssetInfo = AssetInfo::Token { contract_addr: "mir_addr".to_string()};
+
+  // protocol fee + royalty can't exceed 100%
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: vec![mir.clone()],
+    royalties: vec![Royalty { address: Addr::unchecked("nft_designer"), royalty_rate: Decimal::from_ratio(99u128, 100u128) }],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg);
+
+  match res {
+    Err(ContractError::InvalidProtocolFeeRate {}) => {}
+    _ => panic!("Must return invalid protocol fee rate error"),
+  }
+
+  // add collections. alien overrides protocol fee rate
+  for (nft_address, protocol_fee_rate) in [("spaceship", None), ("alien", Some(Decimal::from_ratio(5u128, 100u128)))] {
+    let info = mock_info("owner", &[]);
+    let add_collection_msg = ExecuteMsg::AddCollection {
+      nft_address: nft_address.to_string(),
+      support_assets: vec![mir.clone()],
+      royalties: vec![Royalty { address: Addr::unchecked("nft_designer"), royalty_rate: Decimal::from_ratio(3u128, 100u128) }],
+      auction_extension: None,
+      swap_royalty: None,
+      protocol_fee_rate,
+    };
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+  }
+
+  // spaceship uses config rate, so config rate + spaceship royalty can't exceed 100%
+  let update_config_msg = ExecuteMsg::UpdateConfig {
+    owner: None,
+    min_increase: None,
+    max_auction_duration_block: None,
+    max_auction_duration_second: None,
+    auction_cancel_fee_rate: None,
+    auction_extension_block: None,
+    auction_extension_second: None,
+    protocol_fee_rate: Some(Decimal::from_ratio(98u128, 100u128)),
+    treasury: None,
+    fee_discount: None,
+    referral_share: None,
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, update_config_msg);
+
+  match res {
+    Err(ContractError::InvalidProtocolFeeRate {}) => {}
+    _ => panic!("Must return invalid protocol fee rate error"),
+  }
+
+  // override of alien also can't exceed
+  let update_collection_msg = ExecuteMsg::UpdateCollection {
+    nft_address: "alien".to_string(),
+    support_assets: None,
+    royalties: None,
+    auction_extension: None,
+    clear_auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: Some(Decimal::from_ratio(98u128, 100u128)),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, update_collection_msg);
+
+  match res {
+    Err(ContractError::InvalidProtocolFeeRate {}) => {}
+    _ => panic!("Must return invalid protocol fee rate error"),
+  }
+
+  // make orders
+  for nft_address in ["spaceship", "alien"] {
+    let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder {
+      price: Asset { info: mir.clone(), amount: Uint128::from(100000000u128) },
+      expiration: None,
+      other_prices: None,
+      buyers: None
+    };
+
+    let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+      sender: "seller".to_string(),
+      token_id: "no1".to_string(),
+      msg: to_binary(&make_fixed_price_order_msg).unwrap(),
+    };
+
+    let info = mock_info(nft_address, &[]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+  }
+
+  // buy both. spaceship pays 2% to treasury, alien pays 5%
+  for (order_id, nft_address, fee_amount, remain_amount) in [
+    (1, "spaceship", 2000000u128, 95000000u128),
+    (2, "alien", 5000000u128, 92000000u128)
+  ] {
+    let execute_msg = Cw20HookMsg::ExecuteOrder { order_id, amount: None, referrer: None };
+
+    let info = mock_info("mir_addr", &[]);
+
+    let receive_msg: Cw20ReceiveMsg = Cw20ReceiveMsg {
+      sender: "buyer".to_string(),
+      amount: Uint128::from(100000000u128),
+      msg: to_binary(&execute_msg).unwrap()
+    };
+
+    let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into())).unwrap();
+
+    let royalty_asset = Asset { info: mir.clone(), amount: Uint128::from(3000000u128) };
+    let fee_asset = Asset { info: mir.clone(), amount: Uint128::from(fee_amount) };
+    let remain_asset = Asset { info: mir.clone(), amount: Uint128::from(remain_amount) };
+
+    // first message is nft transfer
+    assert_eq!(
+      res.messages[1..],
+      vec![
+        SubMsg::new(royalty_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("nft_designer")).unwrap()),
+        SubMsg::new(fee_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("treasury")).unwrap()),
+        SubMsg::new(remain_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("seller")).unwrap()),
+      ]
+    );
+
+    assert!(res.attributes.contains(&attr("royalty_amount", "3000000")));
+    assert!(res.attributes.contains(&attr("protocol_fee", fee_amount.to_string())));
+
+    let query_msg = QueryMsg::LastSale { nft_address: nft_address.to_string(), token_id: "no1".to_string() };
+    let trade: Option<Trade> = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+    assert_eq!(trade.unwrap().protocol_fee, Uint128::from(fee_amount));
+  }
+}
+
+#[test]
+fn fee_discount_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::from_ratio(2u128, 100u128),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  // governance token balances
+  deps.querier.with_token_balances(&[
+    (&"gov_token".to_string(), &[(&"buyer".to_string(), &Uint128::from(1000u128))]),
+    (&"staking".to_string(), &[(&"seller".to_string(), &Uint128::from(5000u128))]),
+  ]);
+
+  let mir: AssetInfo = AssetInfo::Token { contract_addr: "mir_addr".to_string()};
+
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: vec![mir.clone()],
+    royalties: vec![],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  let tiers = vec![
+    FeeTier { min_balance: Uint128::from(100u128), discount_rate: Decimal::from_ratio(25u128, 100u128) },
+    FeeTier { min_balance: Uint128::from(1000u128), discount_rate: Decimal::from_ratio(50u128, 100u128) },
+    FeeTier { min_balance: Uint128::from(10000u128), discount_rate: Decimal::one() },
+  ];
+
+  let update_config_msg = |fee_discount: FeeDiscount| ExecuteMsg::UpdateConfig {
+    owner: None,
+    min_increase: None,
+    max_auction_duration_block: None,
+    max_auction_duration_second: None,
+    auction_cancel_fee_rate: None,
+    auction_extension_block: None,
+    auction_extension_second: None,
+    protocol_fee_rate: None,
+    treasury: None,
+    fee_discount: Some(fee_discount),
+    referral_share: None,
+  };
+
+  // discount can't exceed 100%
+  let fee_discount = FeeDiscount {
+    source: FeeDiscountSource::Cw20 { contract_addr: Addr::unchecked("gov_token") },
+    tiers: vec![FeeTier { min_balance: Uint128::from(100u128), discount_rate: Decimal::from_ratio(101u128, 100u128) }],
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, update_config_msg(fee_discount));
+
+  match res {
+    Err(ContractError::InvalidFeeDiscount {}) => {}
+    _ => panic!("Must return invalid fee discount error"),
+  }
+
+  let fee_discount = FeeDiscount {
+    source: FeeDiscountSource::Cw20 { contract_addr: Addr::unchecked("gov_token") },
+    tiers: tiers.clone(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, update_config_msg(fee_discount)).unwrap();
+
+  // fee quote
+  let query_msg = QueryMsg::FeeQuote { address: "buyer".to_string(), nft_address: Some("spaceship".to_string()) };
+  let res: FeeQuoteResponse = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(
+    res,
+    FeeQuoteResponse {
+      balance: Uint128::from(1000u128),
+      tier: Some(tiers[1].clone()),
+      protocol_fee_rate: Decimal::from_ratio(2u128, 100u128),
+      discount_rate: Decimal::from_ratio(50u128, 100u128)
+    }
+  );
+
+  let query_msg = QueryMsg::FeeQuote { address: "seller".to_string(), nft_address: None };
+  let res: FeeQuoteResponse = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(res.tier, None);
+  assert_eq!(res.discount_rate, Decimal::zero());
+
+  // make orders
+  for token_id in ["no1", "no2", "no3"] {
+    let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder {
+      price: Asset { info: mir.clone(), amount: Uint128::from(100000000u128) },
+      expiration: None,
+      other_prices: None,
+      buyers: None
+    };
+
+    let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+      sender: "seller".to_string(),
+      token_id: token_id.to_string(),
+      msg: to_binary(&make_fixed_price_order_msg).unwrap(),
+    };
+
+    let info = mock_info("spaceship", &[]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+  }
+
+  let execute_order = |deps: DepsMut, order_id: u64| {
+    let execute_msg = Cw20HookMsg::ExecuteOrder { order_id, amount: None, referrer: None };
+
+    let info = mock_info("mir_addr", &[]);
+
+    let receive_msg: Cw20ReceiveMsg = Cw20ReceiveMsg {
+      sender: "buyer".to_string(),
+      amount: Uint128::from(100000000u128),
+      msg: to_binary(&execute_msg).unwrap()
+    };
+
+    market.execute(deps, mock_env(), info, ExecuteMsg::Receive(receive_msg.into())).unwrap()
+  };
+
+  // buyer holds 1000 gov token. 50% discount of 2000000
+  let res = execute_order(deps.as_mut(), 1);
+
+  assert!(res.attributes.contains(&attr("protocol_fee", "1000000")));
+
+  // staked balance of seller is used. 5000 staked, 50% discount
+  let fee_discount = FeeDiscount {
+    source: FeeDiscountSource::Staking { contract_addr: Addr::unchecked("staking") },
+    tiers,
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, update_config_msg(fee_discount)).unwrap();
+
+  let res = execute_order(deps.as_mut(), 2);
+
+  assert!(res.attributes.contains(&attr("protocol_fee", "1000000")));
+
+  // empty tiers disable the discount
+  let fee_discount = FeeDiscount {
+    source: FeeDiscountSource::Staking { contract_addr: Addr::unchecked("staking") },
+    tiers: vec![],
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, update_config_msg(fee_discount)).unwrap();
+
+  let query_msg = QueryMsg::FeeQuote { address: "seller".to_string(), nft_address: None };
+  let res: FeeQuoteResponse = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(res.balance, Uint128::zero());
+  assert_eq!(res.discount_rate, Decimal::zero());
+
+  // failed balance query gives no discount
+  let fee_discount = FeeDiscount {
+    source: FeeDiscountSource::Cw20 { contract_addr: Addr::unchecked("no_contract") },
+    tiers: vec![FeeTier { min_balance: Uint128::zero(), discount_rate: Decimal::one() }],
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, update_config_msg(fee_discount)).unwrap();
+
+  let res = execute_order(deps.as_mut(), 3);
+
+  assert!(res.attributes.contains(&attr("protocol_fee", "2000000")));
+}
+
+#[test]
+fn referral_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::from_ratio(2u128, 100u128),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  let mir: AssetInfo = AssetInfo::Token { contract_addr: "mir_addr".to_string()};
+
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: vec![mir.clone()],
+    royalties: vec![],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  let update_config_msg = |referral_share: Decimal| ExecuteMsg::UpdateConfig {
+    owner: None,
+    min_increase: None,
+    max_auction_duration_block: None,
+    max_auction_duration_second: None,
+    auction_cancel_fee_rate: None,
+    auction_extension_block: None,
+    auction_extension_second: None,
+    protocol_fee_rate: None,
+    treasury: None,
+    fee_discount: None,
+    referral_share: Some(referral_share),
+  };
+
+  // referral share can't exceed 100%
+  let info = mock_info("owner", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, update_config_msg(Decimal::from_ratio(101u128, 100u128)));
+
+  match res {
+    Err(ContractError::InvalidReferralShare {}) => {}
+    _ => panic!("Must return invalid referral share error"),
+  }
+
+  // half of protocol fee goes to referrer
+  let info = mock_info("owner", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, update_config_msg(Decimal::from_ratio(50u128, 100u128))).unwrap();
+
+  let mir_asset = |amount: u128| Asset { info: mir.clone(), amount: Uint128::from(amount) };
+
+  // make fixed price order and auction
+  let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder {
+    price: mir_asset(100000000),
+    expiration: None,
+    other_prices: None,
+    buyers: None
+  };
+
+  let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder {
+    start_price: mir_asset(100000000),
+    expiration: Expiration::AtHeight(12_400),
+    fixed_price: None,
+    reserve_price: None,
+    second_price: None,
+    buyers: None,
+  };
+
+  for (token_id, msg) in [("no1", to_binary(&make_fixed_price_order_msg)), ("no2", to_binary(&make_auction_order_msg))] {
+    let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+      sender: "seller".to_string(),
+      token_id: token_id.to_string(),
+      msg: msg.unwrap(),
+    };
+
+    let info = mock_info("spaceship", &[]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+  }
+
+  // execute order with referrer
+  let execute_msg = Cw20HookMsg::ExecuteOrder { order_id: 1, amount: None, referrer: Some("frontend1".to_string()) };
+
+  let receive_msg: Cw20ReceiveMsg = Cw20ReceiveMsg {
+    sender: "buyer".to_string(),
+    amount: Uint128::from(100000000u128),
+    msg: to_binary(&execute_msg).unwrap()
+  };
+
+  let info = mock_info("mir_addr", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into())).unwrap();
+
+  assert_eq!(
+    res.messages[1..],
+    vec![
+      SubMsg::new(mir_asset(1000000).into_msg(&deps.as_mut().querier, Addr::unchecked("frontend1")).unwrap()),
+      SubMsg::new(mir_asset(1000000).into_msg(&deps.as_mut().querier, Addr::unchecked("treasury")).unwrap()),
+      SubMsg::new(mir_asset(98000000).into_msg(&deps.as_mut().querier, Addr::unchecked("seller")).unwrap()),
+    ]
+  );
+
+  assert!(res.attributes.contains(&attr("referrer", "frontend1")));
+
+  // bids. referrer of the highest bid is kept
+  for (bidder, amount, referrer) in [("bidder1", 120000000u128, "frontend1"), ("bidder2", 200000000u128, "frontend2")] {
+    let receive_msg: Cw20ReceiveMsg = Cw20ReceiveMsg {
+      sender: bidder.to_string(),
+      amount: Uint128::from(amount),
+      msg: to_binary(&Cw20HookMsg::Bid { order_id: 2, referrer: Some(referrer.to_string()) }).unwrap()
+    };
+
+    let info = mock_info("mir_addr", &[]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into())).unwrap();
+  }
+
+  let order = market.orders.load(&deps.storage, U64Key::new(2)).unwrap();
+
+  assert_eq!(order.auction_info.unwrap().referrer, Some(Addr::unchecked("frontend2")));
+
+  // execute auction. referrer of the winning bid gets the referral fee
+  let mut mock_env = mock_env();
+  mock_env.block.height = 12_400;
+
+  let res = market.execute(deps.as_mut(), mock_env, mock_info("anyone", &[]), ExecuteMsg::ExecuteAuction { order_id: 2 }).unwrap();
+
+  assert_eq!(
+    res.messages[1..],
+    vec![
+      SubMsg::new(mir_asset(2000000).into_msg(&deps.as_mut().querier, Addr::unchecked("frontend2")).unwrap()),
+      SubMsg::new(mir_asset(2000000).into_msg(&deps.as_mut().querier, Addr::unchecked("treasury")).unwrap()),
+      SubMsg::new(mir_asset(196000000).into_msg(&deps.as_mut().querier, Addr::unchecked("seller")).unwrap()),
+    ]
+  );
+}
\ No newline at end of file
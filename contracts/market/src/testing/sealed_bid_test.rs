@@ -0,0 +1,515 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{from_binary, to_binary, Addr, Binary, CosmosMsg, Decimal, SubMsg, WasmMsg, Uint128};
+use cw_storage_plus::U64Key;
+use cw721::{Cw721ExecuteMsg, Cw721ReceiveMsg};
+use cw20::Cw20ReceiveMsg;
+use cw0::Expiration;
+use sha2::{Digest, Sha256};
+
+use crate::{
+  state::{MarketContract, Royalty, SealedBid, UnrevealedPolicy},
+  msgs::{InstantiateMsg, ExecuteMsg, Cw721HookMsg, Cw20HookMsg, QueryMsg},
+  error::ContractError,
+  asset::{Asset, AssetInfo},
+  testing::mock_querier::mock_dependencies
+};
+
+fn commitment(order_id: u64, bidder: &str, amount: u128, salt: &str) -> Binary {
+  Binary::from(Sha256::digest(format!("{}:{}:{}:{}", order_id, bidder, amount, salt).as_bytes()).to_vec())
+}
+
+#[test]
+fn sealed_bid_auction_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  // some assetinfos
+  let mir: AssetInfo = AssetInfo::Token { contract_addr: "mir_addr".to_string()};
+
+  // some royalties
+  let nft_designer_royalty: Royalty = Royalty {
+    address: Addr::unchecked("nft_designer"),
+    royalty_rate: Decimal::from_ratio(2u128, 100u128)
+  };
+
+  // add collection
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: vec![mir.clone()],
+    royalties: vec![nft_designer_royalty],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  let seller = "seller".to_string();
+
+  let min_price = Asset {
+    info: mir.clone(),
+    amount: Uint128::from(100000000u128)
+  };
+
+  // try to make sealed bid auction with reveal end earlier than bid end
+  let make_sealed_bid_auction_order_msg = Cw721HookMsg::MakeSealedBidAuctionOrder {
+    min_price: min_price.clone(),
+    bid_end: Expiration::AtHeight(12_440),
+    reveal_end: Expiration::AtHeight(12_400),
+    unrevealed_policy: UnrevealedPolicy::Forfeit,
+    second_price: None,
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_sealed_bid_auction_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
+
+  match res {
+    Err(ContractError::InvalidPeriod {}) => {}
+    _ => panic!("Must return invalid period error"),
+  }
+
+  // make sealed bid auction. unrevealed deposit is forfeited
+  let make_sealed_bid_auction_order_msg = Cw721HookMsg::MakeSealedBidAuctionOrder {
+    min_price: min_price.clone(),
+    bid_end: Expiration::AtHeight(12_400),
+    reveal_end: Expiration::AtHeight(12_440),
+    unrevealed_policy: UnrevealedPolicy::Forfeit,
+    second_price: None,
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_sealed_bid_auction_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  // try to commit with deposit lower than min price
+  let commit_msg = Cw20HookMsg::CommitBid { order_id: 1, commitment: commitment(1, "bidder1", 90000000, "salt1") };
+
+  let receive_msg: Cw20ReceiveMsg = Cw20ReceiveMsg {
+    sender: "bidder1".to_string(),
+    amount: Uint128::from(90000000u128),
+    msg: to_binary(&commit_msg).unwrap()
+  };
+
+  let info = mock_info("mir_addr", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into()));
+
+  match res {
+    Err(ContractError::MinPrice { min_bid_amount: _ }) => {}
+    _ => panic!("Must return min price error"),
+  }
+
+  // commit bids. (bidder, deposit, bid)
+  let bids = [
+    ("bidder1", 200000000u128, 150000000u128),
+    ("bidder2", 300000000u128, 180000000u128),
+    ("bidder3", 150000000u128, 120000000u128),
+  ];
+
+  for (bidder, deposit, amount) in bids.iter() {
+    let commit_msg = Cw20HookMsg::CommitBid { order_id: 1, commitment: commitment(1, bidder, *amount, "salt") };
+
+    let receive_msg: Cw20ReceiveMsg = Cw20ReceiveMsg {
+      sender: bidder.to_string(),
+      amount: Uint128::from(*deposit),
+      msg: to_binary(&commit_msg).unwrap()
+    };
+
+    let info = mock_info("mir_addr", &[]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into())).unwrap();
+  }
+
+  // try to commit again
+  let commit_msg = Cw20HookMsg::CommitBid { order_id: 1, commitment: commitment(1, "bidder1", 160000000, "salt") };
+
+  let receive_msg: Cw20ReceiveMsg = Cw20ReceiveMsg {
+    sender: "bidder1".to_string(),
+    amount: Uint128::from(200000000u128),
+    msg: to_binary(&commit_msg).unwrap()
+  };
+
+  let info = mock_info("mir_addr", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into()));
+
+  match res {
+    Err(ContractError::AlreadyCommitted {}) => {}
+    _ => panic!("Must return already committed error"),
+  }
+
+  // try to cancel after commit
+  let info = mock_info("seller", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::CancelOrder { order_id: 1 });
+
+  match res {
+    Err(ContractError::BidExist {}) => {}
+    _ => panic!("Must return bid exist error"),
+  }
+
+  // try to reveal in bid phase
+  let reveal_msg = ExecuteMsg::RevealBid { order_id: 1, amount: Uint128::from(150000000u128), salt: "salt".to_string() };
+
+  let info = mock_info("bidder1", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, reveal_msg);
+
+  match res {
+    Err(ContractError::RevealNotStarted {}) => {}
+    _ => panic!("Must return reveal not started error"),
+  }
+
+  // reveal phase
+  let mut mock_env = mock_env();
+  mock_env.block.height = 12_410;
+
+  // try to reveal with wrong salt
+  let reveal_msg = ExecuteMsg::RevealBid { order_id: 1, amount: Uint128::from(150000000u128), salt: "wrong".to_string() };
+
+  let info = mock_info("bidder1", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env.clone(), info, reveal_msg);
+
+  match res {
+    Err(ContractError::InvalidReveal {}) => {}
+    _ => panic!("Must return invalid reveal error"),
+  }
+
+  // reveal bidder1, bidder2. bidder3 does not reveal
+  for (bidder, _, amount) in bids[0..2].iter() {
+    let reveal_msg = ExecuteMsg::RevealBid { order_id: 1, amount: Uint128::from(*amount), salt: "salt".to_string() };
+
+    let info = mock_info(bidder, &[]);
+
+    let _res = market.execute(deps.as_mut(), mock_env.clone(), info, reveal_msg).unwrap();
+  }
+
+  // try to reveal again
+  let reveal_msg = ExecuteMsg::RevealBid { order_id: 1, amount: Uint128::from(180000000u128), salt: "salt".to_string() };
+
+  let info = mock_info("bidder2", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env.clone(), info, reveal_msg);
+
+  match res {
+    Err(ContractError::AlreadyRevealed {}) => {}
+    _ => panic!("Must return already revealed error"),
+  }
+
+  let order = market.orders.load(&deps.storage, U64Key::new(1)).unwrap();
+  let sealed_bid_auction_info = order.sealed_bid_auction_info.unwrap();
+
+  assert_eq!(Some(Addr::unchecked("bidder2")), sealed_bid_auction_info.bidder);
+  assert_eq!(Some(Asset { info: mir.clone(), amount: Uint128::from(180000000u128) }), sealed_bid_auction_info.highest_bid);
+
+  let sealed_bids: Vec<SealedBid> = from_binary(
+    &market.query(deps.as_ref(), mock_env.clone(), QueryMsg::SealedBids { order_id: 1, start_after: None, limit: None }).unwrap()
+  ).unwrap();
+
+  assert_eq!(3, sealed_bids.len());
+  assert_eq!(None, sealed_bids[2].amount);
+
+  // try to execute in reveal phase
+  let res = market.execute(deps.as_mut(), mock_env.clone(), mock_info("anyone", &[]), ExecuteMsg::ExecuteAuction { order_id: 1 });
+
+  match res {
+    Err(ContractError::NotExpired {}) => {}
+    _ => panic!("Must return not expired error"),
+  }
+
+  // execute auction
+  mock_env.block.height = 12_440;
+
+  let res = market.execute(deps.as_mut(), mock_env.clone(), mock_info("anyone", &[]), ExecuteMsg::ExecuteAuction { order_id: 1 }).unwrap();
+
+  let mir_asset = |amount: u128| Asset { info: mir.clone(), amount: Uint128::from(amount) };
+
+  assert_eq!(
+    res.messages,
+    vec![
+      // transfer nft to winner
+      SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "spaceship".to_string(),
+        msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+          recipient: "bidder2".to_string(),
+          token_id: "no1".to_string()
+        }).unwrap(),
+        funds: vec![]
+      })),
+      // transfer royalty
+      SubMsg::new(mir_asset(3600000).into_msg(&deps.as_mut().querier, Addr::unchecked("nft_designer")).unwrap()),
+      // transfer remain to seller
+      SubMsg::new(mir_asset(176400000).into_msg(&deps.as_mut().querier, Addr::unchecked("seller")).unwrap()),
+      // refund rest of the winner's deposit
+      SubMsg::new(mir_asset(120000000).into_msg(&deps.as_mut().querier, Addr::unchecked("bidder2")).unwrap()),
+    ]
+  );
+
+  let order = market.orders.may_load(&deps.storage, U64Key::new(1));
+
+  assert_eq!(order, Ok(None));
+
+  // deposits of loser and unrevealed bid are left
+  let sealed_bids: Vec<SealedBid> = from_binary(
+    &market.query(deps.as_ref(), mock_env.clone(), QueryMsg::SealedBids { order_id: 1, start_after: None, limit: None }).unwrap()
+  ).unwrap();
+
+  assert_eq!(2, sealed_bids.len());
+
+  // loser withdraws deposit
+  let withdraw_msg = ExecuteMsg::WithdrawSealedBid { order_id: 1, bidder: None };
+
+  let res = market.execute(deps.as_mut(), mock_env.clone(), mock_info("bidder1", &[]), withdraw_msg.clone()).unwrap();
+
+  assert_eq!(
+    res.messages,
+    vec![SubMsg::new(mir_asset(200000000).into_msg(&deps.as_mut().querier, Addr::unchecked("bidder1")).unwrap())]
+  );
+
+  // try to withdraw again
+  let res = market.execute(deps.as_mut(), mock_env.clone(), mock_info("bidder1", &[]), withdraw_msg);
+
+  match res {
+    Err(ContractError::Std(_)) => {}
+    _ => panic!("Must return error"),
+  }
+
+  // unrevealed deposit is forfeited to seller
+  let withdraw_msg = ExecuteMsg::WithdrawSealedBid { order_id: 1, bidder: Some("bidder3".to_string()) };
+
+  let res = market.execute(deps.as_mut(), mock_env.clone(), mock_info("seller", &[]), withdraw_msg).unwrap();
+
+  assert_eq!(
+    res.messages,
+    vec![SubMsg::new(mir_asset(150000000).into_msg(&deps.as_mut().querier, Addr::unchecked("seller")).unwrap())]
+  );
+
+  assert_eq!(market.sealed_bid_settlements.may_load(&deps.storage, U64Key::new(1)), Ok(None));
+
+  // make sealed bid auction. unrevealed deposit is refunded
+  mock_env.block.height = 12_345;
+
+  let make_sealed_bid_auction_order_msg = Cw721HookMsg::MakeSealedBidAuctionOrder {
+    min_price: min_price.clone(),
+    bid_end: Expiration::AtHeight(12_400),
+    reveal_end: Expiration::AtHeight(12_440),
+    unrevealed_policy: UnrevealedPolicy::Refund,
+    second_price: None,
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no2".to_string(),
+    msg: to_binary(&make_sealed_bid_auction_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env.clone(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  let commit_msg = Cw20HookMsg::CommitBid { order_id: 2, commitment: commitment(2, "bidder1", 100000000, "salt") };
+
+  let receive_msg: Cw20ReceiveMsg = Cw20ReceiveMsg {
+    sender: "bidder1".to_string(),
+    amount: Uint128::from(100000000u128),
+    msg: to_binary(&commit_msg).unwrap()
+  };
+
+  let info = mock_info("mir_addr", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env.clone(), info, ExecuteMsg::Receive(receive_msg.into())).unwrap();
+
+  // try to withdraw before the auction is executed
+  let withdraw_msg = ExecuteMsg::WithdrawSealedBid { order_id: 2, bidder: None };
+
+  let res = market.execute(deps.as_mut(), mock_env.clone(), mock_info("bidder1", &[]), withdraw_msg.clone());
+
+  match res {
+    Err(ContractError::NotSettled {}) => {}
+    _ => panic!("Must return not settled error"),
+  }
+
+  // no reveal. nft returns to seller and deposit is refunded
+  mock_env.block.height = 12_440;
+
+  let res = market.execute(deps.as_mut(), mock_env.clone(), mock_info("anyone", &[]), ExecuteMsg::ExecuteAuction { order_id: 2 }).unwrap();
+
+  assert_eq!(
+    res.messages,
+    vec![
+      // return nft to seller
+      SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "spaceship".to_string(),
+        msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+          recipient: "seller".to_string(),
+          token_id: "no2".to_string()
+        }).unwrap(),
+        funds: vec![]
+      })),
+    ]
+  );
+
+  let res = market.execute(deps.as_mut(), mock_env, mock_info("bidder1", &[]), withdraw_msg).unwrap();
+
+  assert_eq!(
+    res.messages,
+    vec![SubMsg::new(mir_asset(100000000).into_msg(&deps.as_mut().querier, Addr::unchecked("bidder1")).unwrap())]
+  );
+}
+
+#[test]
+fn sealed_bid_second_price_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  // some assetinfos
+  let mir: AssetInfo = AssetInfo::Token { contract_addr: "mir_addr".to_string()};
+
+  // add collection
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: vec![mir.clone()],
+    royalties: vec![],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  // make second price sealed bid auction
+  let make_sealed_bid_auction_order_msg = Cw721HookMsg::MakeSealedBidAuctionOrder {
+    min_price: Asset { info: mir.clone(), amount: Uint128::from(100000000u128) },
+    bid_end: Expiration::AtHeight(12_400),
+    reveal_end: Expiration::AtHeight(12_440),
+    unrevealed_policy: UnrevealedPolicy::Refund,
+    second_price: Some(true),
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "seller".to_string(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_sealed_bid_auction_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  // commit bids. (bidder, deposit, bid)
+  let bids = [
+    ("bidder1", 200000000u128, 180000000u128),
+    ("bidder2", 200000000u128, 120000000u128),
+    ("bidder3", 200000000u128, 150000000u128),
+  ];
+
+  for (bidder, deposit, amount) in bids.iter() {
+    let commit_msg = Cw20HookMsg::CommitBid { order_id: 1, commitment: commitment(1, bidder, *amount, "salt") };
+
+    let receive_msg: Cw20ReceiveMsg = Cw20ReceiveMsg {
+      sender: bidder.to_string(),
+      amount: Uint128::from(*deposit),
+      msg: to_binary(&commit_msg).unwrap()
+    };
+
+    let info = mock_info("mir_addr", &[]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into())).unwrap();
+  }
+
+  // reveal all
+  let mut mock_env = mock_env();
+  mock_env.block.height = 12_410;
+
+  for (bidder, _, amount) in bids.iter() {
+    let reveal_msg = ExecuteMsg::RevealBid { order_id: 1, amount: Uint128::from(*amount), salt: "salt".to_string() };
+
+    let info = mock_info(bidder, &[]);
+
+    let _res = market.execute(deps.as_mut(), mock_env.clone(), info, reveal_msg).unwrap();
+  }
+
+  let order = market.orders.load(&deps.storage, U64Key::new(1)).unwrap();
+  let sealed_bid_auction_info = order.sealed_bid_auction_info.unwrap();
+
+  assert_eq!(Some(Addr::unchecked("bidder1")), sealed_bid_auction_info.bidder);
+  assert_eq!(Some(Asset { info: mir.clone(), amount: Uint128::from(150000000u128) }), sealed_bid_auction_info.runner_up_bid);
+
+  // execute auction. winner pays runner-up bid + 10%
+  mock_env.block.height = 12_440;
+
+  let res = market.execute(deps.as_mut(), mock_env, mock_info("anyone", &[]), ExecuteMsg::ExecuteAuction { order_id: 1 }).unwrap();
+
+  let mir_asset = |amount: u128| Asset { info: mir.clone(), amount: Uint128::from(amount) };
+
+  assert_eq!(
+    res.messages,
+    vec![
+      // transfer nft to winner
+      SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "spaceship".to_string(),
+        msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+          recipient: "bidder1".to_string(),
+          token_id: "no1".to_string()
+        }).unwrap(),
+        funds: vec![]
+      })),
+      // clearing price to seller
+      SubMsg::new(mir_asset(165000000).into_msg(&deps.as_mut().querier, Addr::unchecked("seller")).unwrap()),
+      // refund rest of the winner's deposit
+      SubMsg::new(mir_asset(35000000).into_msg(&deps.as_mut().querier, Addr::unchecked("bidder1")).unwrap()),
+    ]
+  );
+}
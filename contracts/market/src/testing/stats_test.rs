@@ -0,0 +1,247 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{from_binary, to_binary, Addr, Coin, Decimal, Uint128};
+use cw0::Expiration;
+use cw721::Cw721ReceiveMsg;
+
+use crate::{
+  state::{MarketContract, Sweetener, SweetenerPayer, TradeStats},
+  msgs::{InstantiateMsg, ExecuteMsg, Cw721HookMsg, QueryMsg, AssetStats, CollectionStatsResponse, MarketStatsResponse},
+  asset::{Asset, AssetInfo},
+  testing::mock_querier::mock_dependencies
+};
+
+#[test]
+fn stats_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  // need tax querier
+  deps.querier.with_tax(
+    Decimal::from_ratio(1u128, 100u128),
+    &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+  );
+
+  let uusd: AssetInfo = AssetInfo::NativeToken { denom: "uusd".to_string()};
+  let mir: AssetInfo = AssetInfo::Token { contract_addr: "mir_addr".to_string()};
+
+  // add collections
+  let collections = [("spaceship", vec![uusd.clone(), mir.clone()]), ("alien", vec![uusd.clone()])];
+
+  for (nft_address, support_assets) in collections.iter() {
+    let info = mock_info("owner", &[]);
+    let add_collection_msg = ExecuteMsg::AddCollection {
+      nft_address: nft_address.to_string(),
+      support_assets: support_assets.clone(),
+      royalties: vec![],
+      auction_extension: None,
+      swap_royalty: None,
+      protocol_fee_rate: None,
+    };
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+  }
+
+  // make orders
+  let orders = [
+    ("spaceship", "no1", 10000000u128),
+    ("spaceship", "no2", 30000000u128),
+    ("spaceship", "no3", 20000000u128),
+    ("alien", "no1", 5000000u128),
+  ];
+
+  for (nft_address, token_id, amount) in orders.iter() {
+    let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder {
+      price: Asset { info: uusd.clone(), amount: Uint128::from(*amount) },
+      expiration: None,
+      other_prices: None,
+      buyers: None
+    };
+
+    let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+      sender: "seller".to_string(),
+      token_id: token_id.to_string(),
+      msg: to_binary(&make_fixed_price_order_msg).unwrap(),
+    };
+
+    let info = mock_info(nft_address, &[]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+  }
+
+  // buy no2 and no1 of spaceship
+  for (order_id, amount) in [(2, 30000000u128), (1, 10000000u128)] {
+    let info = mock_info("buyer", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(amount) }]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ExecuteOrder { order_id, amount: None, referrer: None }).unwrap();
+  }
+
+  // updating an order keeps the listed count
+  let update_order_msg = ExecuteMsg::UpdateOrder {
+    order_id: 4,
+    price: Some(Asset { info: uusd.clone(), amount: Uint128::from(6000000u128) }),
+    fixed_price: None,
+    expiration: None
+  };
+
+  let info = mock_info("seller", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, update_order_msg).unwrap();
+
+  let uusd_stats = TradeStats {
+    asset: uusd.clone(),
+    volume: Uint128::from(40000000u128),
+    sales_count: 2,
+    all_time_high: Uint128::from(30000000u128)
+  };
+
+  let query_msg = QueryMsg::CollectionStats { nft_address: "spaceship".to_string() };
+  let res: CollectionStatsResponse = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(
+    res,
+    CollectionStatsResponse {
+      nft_address: Addr::unchecked("spaceship"),
+      listed_count: 1,
+      assets: vec![
+        AssetStats { stats: uusd_stats.clone(), floor: Some(Uint128::from(20000000u128)) },
+        AssetStats {
+          stats: TradeStats { asset: mir, volume: Uint128::zero(), sales_count: 0, all_time_high: Uint128::zero() },
+          floor: None
+        },
+      ]
+    }
+  );
+
+  let query_msg = QueryMsg::MarketStats {};
+  let res: MarketStatsResponse = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(res, MarketStatsResponse { listed_count: 2, assets: vec![uusd_stats.clone()] });
+
+  // cancelled order is not listed
+  let info = mock_info("seller", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::CancelOrder { order_id: 3 }).unwrap();
+
+  let query_msg = QueryMsg::CollectionStats { nft_address: "spaceship".to_string() };
+  let res: CollectionStatsResponse = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(res.listed_count, 0);
+  assert_eq!(res.assets[0].floor, None);
+
+  // swap is not listed and not a sale
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "seller".to_string(),
+    token_id: "no5".to_string(),
+    msg: to_binary(&Cw721HookMsg::AddToBundle {}).unwrap(),
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), mock_info("spaceship", &[]), ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  let make_swap_order_msg = ExecuteMsg::MakeBundleSwapOrder {
+    nft_address: "alien".to_string(),
+    token_id: None,
+    sweetener: Some(Sweetener { asset: Asset { info: uusd.clone(), amount: Uint128::from(1000000u128) }, payer: SweetenerPayer::Seller }),
+    expiration: None
+  };
+
+  let info = mock_info("seller", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(1000000u128) }]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, make_swap_order_msg).unwrap();
+
+  let query_msg = QueryMsg::CollectionStats { nft_address: "spaceship".to_string() };
+  let res: CollectionStatsResponse = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(res.listed_count, 0);
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "counterparty".to_string(),
+    token_id: "no2".to_string(),
+    msg: to_binary(&Cw721HookMsg::FulfillSwap { order_id: 5 }).unwrap(),
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), mock_info("alien", &[]), ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  let query_msg = QueryMsg::MarketStats {};
+  let res: MarketStatsResponse = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(res, MarketStatsResponse { listed_count: 1, assets: vec![uusd_stats] });
+
+  // bundle of spaceship and alien expiring at 12400
+  for (nft_address, token_id) in [("spaceship", "no6"), ("alien", "no3")] {
+    let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+      sender: "seller".to_string(),
+      token_id: token_id.to_string(),
+      msg: to_binary(&Cw721HookMsg::AddToBundle {}).unwrap(),
+    };
+
+    let _res = market.execute(deps.as_mut(), mock_env(), mock_info(nft_address, &[]), ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+  }
+
+  let make_bundle_order_msg = ExecuteMsg::MakeBundleFixedPriceOrder {
+    price: Asset { info: uusd.clone(), amount: Uint128::from(80000000u128) },
+    expiration: Some(Expiration::AtHeight(12_400)),
+    other_prices: None,
+    buyers: None
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), mock_info("seller", &[]), make_bundle_order_msg).unwrap();
+
+  let query_msg = QueryMsg::CollectionStats { nft_address: "spaceship".to_string() };
+  let res: CollectionStatsResponse = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg.clone()).unwrap()).unwrap();
+
+  assert_eq!(res.listed_count, 1);
+
+  // expired listing is not counted
+  let mut env = mock_env();
+  env.block.height = 12_400;
+
+  let res: CollectionStatsResponse = from_binary(&market.query(deps.as_ref(), env, query_msg.clone()).unwrap()).unwrap();
+
+  assert_eq!(res.listed_count, 0);
+
+  // bundle volume is split by collection. all time high is the price of a nft
+  let info = mock_info("buyer", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(80000000u128) }]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ExecuteOrder { order_id: 6, amount: None, referrer: None }).unwrap();
+
+  let res: CollectionStatsResponse = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(
+    res.assets[0].stats,
+    TradeStats { asset: uusd.clone(), volume: Uint128::from(80000000u128), sales_count: 3, all_time_high: Uint128::from(40000000u128) }
+  );
+
+  let query_msg = QueryMsg::CollectionStats { nft_address: "alien".to_string() };
+  let res: CollectionStatsResponse = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(
+    res.assets[0].stats,
+    TradeStats { asset: uusd.clone(), volume: Uint128::from(40000000u128), sales_count: 1, all_time_high: Uint128::from(40000000u128) }
+  );
+
+  let query_msg = QueryMsg::MarketStats {};
+  let res: MarketStatsResponse = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(
+    res.assets,
+    vec![TradeStats { asset: uusd, volume: Uint128::from(120000000u128), sales_count: 3, all_time_high: Uint128::from(40000000u128) }]
+  );
+}
@@ -0,0 +1,373 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{to_binary, Addr, Coin, CosmosMsg, Decimal, SubMsg, WasmMsg, Uint128};
+use cw_storage_plus::U64Key;
+use cw20::Cw20ReceiveMsg;
+use cw721::{Cw721ExecuteMsg, Cw721ReceiveMsg};
+
+use crate::{
+  state::{MarketContract, Royalty, Sweetener, SweetenerPayer, SwapInfo, SwapRoyalty},
+  msgs::{InstantiateMsg, ExecuteMsg, Cw20HookMsg, Cw721HookMsg},
+  error::ContractError,
+  asset::{Asset, AssetInfo},
+  testing::mock_querier::mock_dependencies
+};
+
+fn transfer_nft_msg(nft_address: &str, recipient: &str, token_id: &str) -> SubMsg {
+  SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+    contract_addr: nft_address.to_string(),
+    msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+      recipient: recipient.to_string(),
+      token_id: token_id.to_string()
+    }).unwrap(),
+    funds: vec![]
+  }))
+}
+
+#[test]
+fn swap_order_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  // need tax querier
+  deps.querier.with_tax(
+    Decimal::from_ratio(1u128, 100u128),
+    &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+  );
+
+  let uusd: AssetInfo = AssetInfo::NativeToken { denom: "uusd".to_string()};
+
+  // add collections. royalties of alien are not paid on swaps
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: vec![uusd.clone()],
+    royalties: vec![Royalty { address: Addr::unchecked("nft_designer"), royalty_rate: Decimal::from_ratio(2u128, 100u128) }],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "alien".to_string(),
+    support_assets: vec![uusd.clone()],
+    royalties: vec![Royalty { address: Addr::unchecked("alien_designer"), royalty_rate: Decimal::from_ratio(3u128, 100u128) }],
+    auction_extension: None,
+    swap_royalty: Some(SwapRoyalty::Exempt),
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  let sweetener_asset = Asset { info: uusd.clone(), amount: Uint128::from(10000000u128) };
+
+  // try to swap for unlisted collection
+  let make_swap_order_msg = Cw721HookMsg::MakeSwapOrder {
+    nft_address: "unlisted".to_string(),
+    token_id: None,
+    sweetener: None,
+    expiration: None
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "seller".to_string(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_swap_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
+
+  match res {
+    Err(ContractError::Std(_)) => {}
+    _ => panic!("Must return error"),
+  }
+
+  // try to list nft of unlisted collection
+  let make_swap_order_msg = Cw721HookMsg::MakeSwapOrder {
+    nft_address: "alien".to_string(),
+    token_id: None,
+    sweetener: None,
+    expiration: None
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "seller".to_string(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_swap_order_msg).unwrap(),
+  };
+
+  let info = mock_info("unlisted", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
+
+  match res {
+    Err(ContractError::Std(_)) => {}
+    _ => panic!("Must return error"),
+  }
+
+  // make swap order. any alien + sweetener from counterparty
+  let make_swap_order_msg = Cw721HookMsg::MakeSwapOrder {
+    nft_address: "alien".to_string(),
+    token_id: None,
+    sweetener: Some(Sweetener { asset: sweetener_asset.clone(), payer: SweetenerPayer::Counterparty }),
+    expiration: None
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "seller".to_string(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_swap_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  let order = market.orders.load(&deps.storage, U64Key::new(1)).unwrap();
+
+  assert_eq!(None, order.price);
+  assert_eq!(
+    Some(SwapInfo {
+      nft_address: Addr::unchecked("alien"),
+      token_id: None,
+      sweetener: Some(Sweetener { asset: sweetener_asset.clone(), payer: SweetenerPayer::Counterparty })
+    }),
+    order.swap_info
+  );
+
+  // swap order can not be bought or updated
+  let info = mock_info("buyer", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(10000000u128) }]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ExecuteOrder { order_id: 1, amount: None, referrer: None });
+
+  match res {
+    Err(ContractError::NoFixedPrice {}) => {}
+    _ => panic!("Must return no fixed price error"),
+  }
+
+  let update_order_msg = ExecuteMsg::UpdateOrder { order_id: 1, price: None, fixed_price: None, expiration: None };
+
+  let info = mock_info("seller", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, update_order_msg);
+
+  match res {
+    Err(ContractError::SwapOrder {}) => {}
+    _ => panic!("Must return swap order error"),
+  }
+
+  // try to fulfill with nft of another collection
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "counterparty".to_string(),
+    token_id: "no5".to_string(),
+    msg: to_binary(&Cw721HookMsg::FulfillSwap { order_id: 1 }).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
+
+  match res {
+    Err(ContractError::CollectionMismatch {}) => {}
+    _ => panic!("Must return collection mismatch error"),
+  }
+
+  // try to fulfill without sweetener
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "counterparty".to_string(),
+    token_id: "no7".to_string(),
+    msg: to_binary(&Cw721HookMsg::FulfillSwap { order_id: 1 }).unwrap(),
+  };
+
+  let info = mock_info("alien", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
+
+  match res {
+    Err(ContractError::Std(_)) => {}
+    _ => panic!("Must return error"),
+  }
+
+  // fulfill with bundled nft and sweetener
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "counterparty".to_string(),
+    token_id: "no7".to_string(),
+    msg: to_binary(&Cw721HookMsg::AddToBundle {}).unwrap(),
+  };
+
+  let info = mock_info("alien", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  let info = mock_info("counterparty", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(10000000u128) }]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::FulfillBundleSwap { order_id: 1 }).unwrap();
+
+  // royalty of spaceship is paid from the sweetener
+  let designer_royalty_asset = Asset { info: uusd.clone(), amount: Uint128::from(200000u128) };
+  let remain_asset = Asset { info: uusd.clone(), amount: Uint128::from(9800000u128) };
+
+  assert_eq!(
+    res.messages,
+    vec![
+      transfer_nft_msg("spaceship", "counterparty", "no1"),
+      transfer_nft_msg("alien", "seller", "no7"),
+      SubMsg::new(designer_royalty_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("nft_designer")).unwrap()),
+      SubMsg::new(remain_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("seller")).unwrap()),
+    ]
+  );
+
+  assert_eq!(market.orders.may_load(&deps.storage, U64Key::new(1)), Ok(None));
+
+  // make swap order for alien no8 + sweetener from seller
+  let seller_sweetener = Sweetener { asset: sweetener_asset.clone(), payer: SweetenerPayer::Seller };
+
+  for token_id in ["no2", "no3"] {
+    let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+      sender: "seller".to_string(),
+      token_id: token_id.to_string(),
+      msg: to_binary(&Cw721HookMsg::AddToBundle {}).unwrap(),
+    };
+
+    let info = mock_info("spaceship", &[]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+    let make_swap_order_msg = ExecuteMsg::MakeBundleSwapOrder {
+      nft_address: "alien".to_string(),
+      token_id: Some("no8".to_string()),
+      sweetener: Some(seller_sweetener.clone()),
+      expiration: None
+    };
+
+    let info = mock_info("seller", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(10000000u128) }]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, make_swap_order_msg).unwrap();
+  }
+
+  // try to fulfill with another token
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "counterparty".to_string(),
+    token_id: "no9".to_string(),
+    msg: to_binary(&Cw721HookMsg::FulfillSwap { order_id: 2 }).unwrap(),
+  };
+
+  let info = mock_info("alien", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg));
+
+  match res {
+    Err(ContractError::TokenIdMismatch {}) => {}
+    _ => panic!("Must return token id mismatch error"),
+  }
+
+  // try to send cw20 with the nft when seller pays the sweetener
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "counterparty".to_string(),
+    token_id: "no8".to_string(),
+    msg: to_binary(&Cw721HookMsg::AddToBundle {}).unwrap(),
+  };
+
+  let info = mock_info("alien", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  let receive_msg: Cw20ReceiveMsg = Cw20ReceiveMsg {
+    sender: "counterparty".to_string(),
+    amount: Uint128::from(5000u128),
+    msg: to_binary(&Cw20HookMsg::FulfillBundleSwap { order_id: 2 }).unwrap()
+  };
+
+  let info = mock_info("mir_addr", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into()));
+
+  match res {
+    Err(ContractError::TokenMismatch {}) => {}
+    _ => panic!("Must return token mismatch error"),
+  }
+
+  // fulfill. no royalty because alien is exempt from swap royalty
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "counterparty".to_string(),
+    token_id: "no8".to_string(),
+    msg: to_binary(&Cw721HookMsg::FulfillSwap { order_id: 2 }).unwrap(),
+  };
+
+  let info = mock_info("alien", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  assert_eq!(
+    res.messages,
+    vec![
+      transfer_nft_msg("spaceship", "counterparty", "no2"),
+      transfer_nft_msg("alien", "seller", "no8"),
+      SubMsg::new(sweetener_asset.clone().into_msg(&deps.as_mut().querier, Addr::unchecked("counterparty")).unwrap()),
+    ]
+  );
+
+  // cancel returns nft and sweetener to seller
+  let info = mock_info("seller", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::CancelOrder { order_id: 3 }).unwrap();
+
+  assert_eq!(
+    res.messages,
+    vec![
+      transfer_nft_msg("spaceship", "seller", "no3"),
+      SubMsg::new(sweetener_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("seller")).unwrap()),
+    ]
+  );
+
+  assert_eq!(market.orders.may_load(&deps.storage, U64Key::new(3)), Ok(None));
+
+  // try to send funds with swap order without seller sweetener
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "seller".to_string(),
+    token_id: "no4".to_string(),
+    msg: to_binary(&Cw721HookMsg::AddToBundle {}).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  let make_swap_order_msg = ExecuteMsg::MakeBundleSwapOrder {
+    nft_address: "alien".to_string(),
+    token_id: None,
+    sweetener: None,
+    expiration: None
+  };
+
+  let info = mock_info("seller", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(10000000u128) }]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, make_swap_order_msg);
+
+  match res {
+    Err(ContractError::TokenMismatch {}) => {}
+    _ => panic!("Must return token mismatch error"),
+  }
+}
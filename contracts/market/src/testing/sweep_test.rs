@@ -0,0 +1,378 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{attr, to_binary, Addr, Coin, CosmosMsg, Decimal, SubMsg, WasmMsg, Uint128};
+use cw_storage_plus::U64Key;
+use cw721::{Cw721ExecuteMsg, Cw721ReceiveMsg};
+use cw20::Cw20ReceiveMsg;
+
+use crate::{
+  state::{MarketContract, MAX_FLOOR_SCAN},
+  msgs::{InstantiateMsg, ExecuteMsg, Cw721HookMsg, Cw20HookMsg},
+  error::ContractError,
+  asset::{Asset, AssetInfo},
+  testing::mock_querier::mock_dependencies
+};
+
+#[test]
+fn execute_orders_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  // need tax querier
+  deps.querier.with_tax(
+    Decimal::from_ratio(1u128, 100u128),
+    &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+  );
+
+  let uusd: AssetInfo = AssetInfo::NativeToken { denom: "uusd".to_string()};
+  let mir: AssetInfo = AssetInfo::Token { contract_addr: "mir_addr".to_string()};
+
+  // add collection
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: vec![uusd.clone(), mir.clone()],
+    royalties: vec![],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  // make orders. no3 is sold only for mir
+  let orders = [
+    ("no1", Asset { info: uusd.clone(), amount: Uint128::from(10000000u128) }),
+    ("no2", Asset { info: uusd.clone(), amount: Uint128::from(20000000u128) }),
+    ("no3", Asset { info: mir.clone(), amount: Uint128::from(30000000u128) }),
+  ];
+
+  for (token_id, price) in orders.iter() {
+    let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder {
+      price: price.clone(),
+      expiration: None,
+      other_prices: None,
+      buyers: None
+    };
+
+    let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+      sender: "seller".to_string(),
+      token_id: token_id.to_string(),
+      msg: to_binary(&make_fixed_price_order_msg).unwrap(),
+    };
+
+    let info = mock_info("spaceship", &[]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+  }
+
+  // try to buy order that is not sold for uusd
+  let execute_orders_msg = ExecuteMsg::ExecuteOrders {
+    order_ids: vec![1, 2, 3],
+    max_total: Asset { info: uusd.clone(), amount: Uint128::from(100000000u128) },
+    skip_unavailable: false
+  };
+
+  let info = mock_info("buyer", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(100000000u128) }]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, execute_orders_msg);
+
+  match res {
+    Err(ContractError::TokenMismatch {}) => {}
+    _ => panic!("Must return token mismatch error"),
+  }
+
+  // try to buy over max total
+  let execute_orders_msg = ExecuteMsg::ExecuteOrders {
+    order_ids: vec![1, 2],
+    max_total: Asset { info: uusd.clone(), amount: Uint128::from(25000000u128) },
+    skip_unavailable: false
+  };
+
+  let info = mock_info("buyer", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(25000000u128) }]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, execute_orders_msg);
+
+  match res {
+    Err(ContractError::MaxTotalExceeded {}) => {}
+    _ => panic!("Must return max total exceeded error"),
+  }
+
+  // try to send less than max total
+  let execute_orders_msg = ExecuteMsg::ExecuteOrders {
+    order_ids: vec![1],
+    max_total: Asset { info: uusd.clone(), amount: Uint128::from(25000000u128) },
+    skip_unavailable: false
+  };
+
+  let info = mock_info("buyer", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(10000000u128) }]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, execute_orders_msg);
+
+  match res {
+    Err(ContractError::Std(_)) => {}
+    _ => panic!("Must return error"),
+  }
+
+  // skip unavailable orders and refund the rest
+  let execute_orders_msg = ExecuteMsg::ExecuteOrders {
+    order_ids: vec![1, 1, 2, 3, 9],
+    max_total: Asset { info: uusd.clone(), amount: Uint128::from(25000000u128) },
+    skip_unavailable: true
+  };
+
+  let info = mock_info("buyer", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(25000000u128) }]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, execute_orders_msg).unwrap();
+
+  let seller_asset = Asset { info: uusd.clone(), amount: Uint128::from(10000000u128) };
+  let refund_asset = Asset { info: uusd.clone(), amount: Uint128::from(15000000u128) };
+
+  assert_eq!(
+    res.messages,
+    vec![
+      SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "spaceship".to_string(),
+        msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+          recipient: "buyer".to_string(),
+          token_id: "no1".to_string()
+        }).unwrap(),
+        funds: vec![]
+      })),
+      SubMsg::new(seller_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("seller")).unwrap()),
+      SubMsg::new(refund_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("buyer")).unwrap()),
+    ]
+  );
+
+  assert!(res.attributes.contains(&attr("executed_order_ids", "1")));
+  assert!(res.attributes.contains(&attr("skipped_order_ids", "1,2,3,9")));
+  assert!(res.attributes.contains(&attr("royalty_amount", "0")));
+  assert!(res.attributes.contains(&attr("protocol_fee", "0")));
+  assert_eq!(market.orders.may_load(&deps.storage, U64Key::new(1)), Ok(None));
+
+  // buy with cw20
+  let receive_msg = Cw20ReceiveMsg {
+    sender: "buyer".to_string(),
+    amount: Uint128::from(30000000u128),
+    msg: to_binary(&Cw20HookMsg::ExecuteOrders { order_ids: vec![2, 3], skip_unavailable: true }).unwrap()
+  };
+
+  let info = mock_info("mir_addr", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into())).unwrap();
+
+  assert_eq!(res.messages.len(), 2);
+  assert!(res.attributes.contains(&attr("executed_order_ids", "3")));
+  assert!(res.attributes.contains(&attr("refund_amount", "0")));
+  assert_eq!(market.orders.may_load(&deps.storage, U64Key::new(3)), Ok(None));
+}
+
+#[test]
+fn sweep_floor_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::from_ratio(2u128, 100u128),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  // need tax querier
+  deps.querier.with_tax(
+    Decimal::from_ratio(1u128, 100u128),
+    &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+  );
+
+  let uusd: AssetInfo = AssetInfo::NativeToken { denom: "uusd".to_string()};
+  let mir: AssetInfo = AssetInfo::Token { contract_addr: "mir_addr".to_string()};
+
+  // add collection
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: vec![uusd.clone(), mir.clone()],
+    royalties: vec![],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  // make orders. no4 is the cheapest but private, no1 is also sold for mir
+  let orders = [
+    ("no1", 30000000u128, Some(vec![Asset { info: mir.clone(), amount: Uint128::from(15000000u128) }]), None),
+    ("no2", 10000000u128, None, None),
+    ("no3", 20000000u128, None, None),
+    ("no4", 5000000u128, None, Some(vec!["friend".to_string()])),
+  ];
+
+  for (token_id, amount, other_prices, buyers) in orders.iter() {
+    let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder {
+      price: Asset { info: uusd.clone(), amount: Uint128::from(*amount) },
+      expiration: None,
+      other_prices: other_prices.clone(),
+      buyers: buyers.clone()
+    };
+
+    let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+      sender: "seller".to_string(),
+      token_id: token_id.to_string(),
+      msg: to_binary(&make_fixed_price_order_msg).unwrap(),
+    };
+
+    let info = mock_info("spaceship", &[]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+  }
+
+  // try to send less than budget
+  let sweep_floor_msg = ExecuteMsg::SweepFloor {
+    nft_address: "spaceship".to_string(),
+    count: 2,
+    max_unit_price: Uint128::from(25000000u128),
+    asset: Asset { info: uusd.clone(), amount: Uint128::from(100000000u128) }
+  };
+
+  let info = mock_info("buyer", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(10000000u128) }]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, sweep_floor_msg.clone());
+
+  match res {
+    Err(ContractError::Std(_)) => {}
+    _ => panic!("Must return error"),
+  }
+
+  // buy the 2 cheapest public orders and refund the rest
+  let info = mock_info("buyer", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(100000000u128) }]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, sweep_floor_msg).unwrap();
+
+  let refund_asset = Asset { info: uusd.clone(), amount: Uint128::from(70000000u128) };
+
+  // nft, protocol fee and seller of each order
+  assert_eq!(res.messages.len(), 7);
+  assert_eq!(res.messages[6], SubMsg::new(refund_asset.into_msg(&deps.as_mut().querier, Addr::unchecked("buyer")).unwrap()));
+  assert!(res.attributes.contains(&attr("executed_order_ids", "2,3")));
+  assert!(res.attributes.contains(&attr("count", "2")));
+  assert!(res.attributes.contains(&attr("royalty_amount", "0")));
+  assert!(res.attributes.contains(&attr("protocol_fee", "600000")));
+  assert_eq!(market.orders.may_load(&deps.storage, U64Key::new(2)), Ok(None));
+  assert_eq!(market.orders.may_load(&deps.storage, U64Key::new(3)), Ok(None));
+
+  // nothing under max unit price. whole budget is refunded
+  let sweep_floor_msg = ExecuteMsg::SweepFloor {
+    nft_address: "spaceship".to_string(),
+    count: 2,
+    max_unit_price: Uint128::from(25000000u128),
+    asset: Asset { info: uusd.clone(), amount: Uint128::from(100000000u128) }
+  };
+
+  let info = mock_info("buyer", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(100000000u128) }]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, sweep_floor_msg).unwrap();
+
+  assert_eq!(res.messages.len(), 1);
+  assert!(res.attributes.contains(&attr("executed_order_ids", "")));
+
+  // sweep with cw20. no1 is bought at its mir price
+  let receive_msg = Cw20ReceiveMsg {
+    sender: "buyer".to_string(),
+    amount: Uint128::from(20000000u128),
+    msg: to_binary(&Cw20HookMsg::SweepFloor {
+      nft_address: "spaceship".to_string(),
+      count: 5,
+      max_unit_price: Uint128::from(20000000u128)
+    }).unwrap()
+  };
+
+  let info = mock_info("mir_addr", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Receive(receive_msg.into())).unwrap();
+
+  assert_eq!(res.messages.len(), 4);
+  assert!(res.attributes.contains(&attr("executed_order_ids", "1")));
+  assert!(res.attributes.contains(&attr("protocol_fee", "300000")));
+  assert!(res.attributes.contains(&attr("refund_amount", "5000000")));
+  assert_eq!(market.orders.may_load(&deps.storage, U64Key::new(1)), Ok(None));
+
+  // private order is still listed
+  assert!(market.orders.may_load(&deps.storage, U64Key::new(4)).unwrap().is_some());
+
+  // public order behind too many cheaper private orders is not read
+  for i in 1..MAX_FLOOR_SCAN {
+    let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder {
+      price: Asset { info: uusd.clone(), amount: Uint128::from(5000000u128) },
+      expiration: None,
+      other_prices: None,
+      buyers: Some(vec!["friend".to_string()])
+    };
+
+    let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+      sender: "seller".to_string(),
+      token_id: format!("private{}", i),
+      msg: to_binary(&make_fixed_price_order_msg).unwrap(),
+    };
+
+    let _res = market.execute(deps.as_mut(), mock_env(), mock_info("spaceship", &[]), ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+  }
+
+  let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder {
+    price: Asset { info: uusd.clone(), amount: Uint128::from(6000000u128) },
+    expiration: None,
+    other_prices: None,
+    buyers: None
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "seller".to_string(),
+    token_id: "no5".to_string(),
+    msg: to_binary(&make_fixed_price_order_msg).unwrap(),
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), mock_info("spaceship", &[]), ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  let sweep_floor_msg = ExecuteMsg::SweepFloor {
+    nft_address: "spaceship".to_string(),
+    count: 1,
+    max_unit_price: Uint128::from(10000000u128),
+    asset: Asset { info: uusd, amount: Uint128::from(10000000u128) }
+  };
+
+  let info = mock_info("buyer", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(10000000u128) }]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, sweep_floor_msg).unwrap();
+
+  assert_eq!(res.messages.len(), 1);
+  assert!(res.attributes.contains(&attr("executed_order_ids", "")));
+}
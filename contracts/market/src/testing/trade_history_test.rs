@@ -0,0 +1,208 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{from_binary, to_binary, Addr, Coin, Decimal, Uint128};
+use cw0::Expiration;
+use cw721::Cw721ReceiveMsg;
+
+use crate::{
+  state::{BundleItem, MarketContract, Royalty, SaleType, Trade},
+  msgs::{InstantiateMsg, ExecuteMsg, Cw721HookMsg, QueryMsg},
+  asset::{Asset, AssetInfo},
+  testing::mock_querier::mock_dependencies
+};
+
+#[test]
+fn trade_history_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  // need tax querier
+  deps.querier.with_tax(
+    Decimal::from_ratio(1u128, 100u128),
+    &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+  );
+
+  let uusd: AssetInfo = AssetInfo::NativeToken { denom: "uusd".to_string()};
+
+  // add collection
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: vec![uusd.clone()],
+    royalties: vec![Royalty { address: Addr::unchecked("nft_designer"), royalty_rate: Decimal::from_ratio(2u128, 100u128) }],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  let price = Asset { info: uusd.clone(), amount: Uint128::from(10000000u128) };
+
+  // seller sells no1 to buyer
+  let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder {
+    price: price.clone(),
+    expiration: None,
+    other_prices: None,
+    buyers: None
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "seller".to_string(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_fixed_price_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  let info = mock_info("buyer", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(10000000u128) }]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ExecuteOrder { order_id: 1, amount: None, referrer: None }).unwrap();
+
+  // buyer sells no1 to buyer2 by accepting the offer
+  let make_offer_msg = ExecuteMsg::MakeOffer {
+    nft_address: "spaceship".to_string(),
+    token_id: "no1".to_string(),
+    price: Asset { info: uusd.clone(), amount: Uint128::from(20000000u128) },
+    expiration: Expiration::AtHeight(12_400)
+  };
+
+  let info = mock_info("buyer2", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(20000000u128) }]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, make_offer_msg).unwrap();
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "buyer".to_string(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&Cw721HookMsg::AcceptOffer { offer_id: 1 }).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  // cancelled order leaves no trade
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: "seller".to_string(),
+    token_id: "no2".to_string(),
+    msg: to_binary(&make_fixed_price_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  let info = mock_info("seller", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::CancelOrder { order_id: 2 }).unwrap();
+
+  let first_trade = Trade {
+    id: 1,
+    sale_type: SaleType::FixedPrice,
+    order_id: Some(1),
+    offer_id: None,
+    nft_address: Addr::unchecked("spaceship"),
+    token_id: "no1".to_string(),
+    bundle: vec![],
+    amount: None,
+    seller: Addr::unchecked("seller"),
+    buyer: Addr::unchecked("buyer"),
+    price: Some(price),
+    royalty_amount: Uint128::from(200000u128),
+    protocol_fee: Uint128::zero(),
+    height: mock_env().block.height,
+    time: mock_env().block.time.seconds()
+  };
+
+  let query_msg = QueryMsg::TradeHistoryByCollection { nft_address: "spaceship".to_string(), start_after: None, limit: None };
+  let trades: Vec<Trade> = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(trades.len(), 2);
+  assert_eq!(trades[1], first_trade);
+  assert_eq!(trades[0].sale_type, SaleType::Offer);
+  assert_eq!(trades[0].offer_id, Some(1));
+  assert_eq!(trades[0].royalty_amount, Uint128::from(400000u128));
+
+  // by token, newest first
+  let query_msg = QueryMsg::TradeHistoryByToken { nft_address: "spaceship".to_string(), token_id: "no1".to_string(), start_after: Some(2), limit: None };
+  let trades: Vec<Trade> = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(trades, vec![first_trade]);
+
+  // buyer bought once and sold once
+  let query_msg = QueryMsg::TradeHistoryByBuyer { buyer: "buyer".to_string(), start_after: None, limit: None };
+  let trades: Vec<Trade> = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(trades.iter().map(|trade| trade.id).collect::<Vec<u64>>(), vec![1]);
+
+  let query_msg = QueryMsg::TradeHistoryBySeller { seller: "buyer".to_string(), start_after: None, limit: None };
+  let trades: Vec<Trade> = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(trades.iter().map(|trade| trade.id).collect::<Vec<u64>>(), vec![2]);
+
+  // last sale
+  let query_msg = QueryMsg::LastSale { nft_address: "spaceship".to_string(), token_id: "no1".to_string() };
+  let last_sale: Option<Trade> = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(last_sale.map(|trade| (trade.id, trade.buyer)), Some((2, Addr::unchecked("buyer2"))));
+
+  let query_msg = QueryMsg::LastSale { nft_address: "spaceship".to_string(), token_id: "no2".to_string() };
+  let last_sale: Option<Trade> = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+
+  assert_eq!(last_sale, None);
+
+  // bundle trade is found by each token
+  for token_id in ["no3", "no4"] {
+    let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+      sender: "seller".to_string(),
+      token_id: token_id.to_string(),
+      msg: to_binary(&Cw721HookMsg::AddToBundle {}).unwrap(),
+    };
+
+    let info = mock_info("spaceship", &[]);
+
+    let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+  }
+
+  let make_bundle_order_msg = ExecuteMsg::MakeBundleFixedPriceOrder {
+    price: Asset { info: uusd, amount: Uint128::from(30000000u128) },
+    expiration: None,
+    other_prices: None,
+    buyers: None
+  };
+
+  let info = mock_info("seller", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, make_bundle_order_msg).unwrap();
+
+  let info = mock_info("buyer", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(30000000u128) }]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ExecuteOrder { order_id: 3, amount: None, referrer: None }).unwrap();
+
+  let query_msg = QueryMsg::LastSale { nft_address: "spaceship".to_string(), token_id: "no4".to_string() };
+  let last_sale: Option<Trade> = from_binary(&market.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+  let last_sale = last_sale.unwrap();
+
+  assert_eq!(last_sale.id, 3);
+  assert_eq!(last_sale.token_id, "no3".to_string());
+  assert_eq!(last_sale.bundle, vec![BundleItem { nft_address: Addr::unchecked("spaceship"), token_id: "no4".to_string() }]);
+}
@@ -0,0 +1,279 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{to_binary, Addr, Coin, Decimal, Uint128};
+use cw_storage_plus::U64Key;
+use cw721::Cw721ReceiveMsg;
+use cw0::Expiration;
+
+use crate::{
+  state::{AuctionInfo, MarketContract, Royalty},
+  msgs::{InstantiateMsg, ExecuteMsg, Cw721HookMsg},
+  error::ContractError,
+  asset::{Asset, AssetInfo},
+  testing::mock_querier::mock_dependencies
+};
+
+#[test]
+fn update_order_test() {
+  // instantiate
+  let market = MarketContract::default();
+
+  let mut deps = mock_dependencies(&[]);
+
+  let instantiate_msg = InstantiateMsg {
+    owner: "owner".to_string(),
+    min_increase: Decimal::from_ratio(10u128, 100u128),
+    max_auction_duration_block: 100,
+    max_auction_duration_second: 1000,
+    auction_cancel_fee_rate: Decimal::zero(),
+    auction_extension_block: 0,
+    auction_extension_second: 0,
+    protocol_fee_rate: Decimal::zero(),
+    treasury: "treasury".to_string(),
+  };
+
+  let info = mock_info("owner", &[]);
+
+  let _res = market.instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+  // some assetinfos
+  let uusd: AssetInfo = AssetInfo::NativeToken { denom: "uusd".to_string()};
+  let mir: AssetInfo = AssetInfo::Token { contract_addr: "mir_addr".to_string()};
+  let shib: AssetInfo = AssetInfo::Token { contract_addr: "shib_addr".to_string()};
+
+  // some royalties
+  let nft_designer_royalty: Royalty = Royalty {
+    address: Addr::unchecked("nft_designer"),
+    royalty_rate: Decimal::from_ratio(2u128, 100u128)
+  };
+
+  // add collection
+  let info = mock_info("owner", &[]);
+  let add_collection_msg = ExecuteMsg::AddCollection {
+    nft_address: "spaceship".to_string(),
+    support_assets: vec![uusd.clone(), mir.clone()],
+    royalties: vec![nft_designer_royalty],
+    auction_extension: None,
+    swap_royalty: None,
+    protocol_fee_rate: None,
+  };
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, add_collection_msg).unwrap();
+
+  let seller = "seller".to_string();
+
+  // make fixed price order
+  let price = Asset {
+    info: uusd.clone(),
+    amount: Uint128::from(100000000u128)
+  };
+
+  let make_fixed_price_order_msg = Cw721HookMsg::MakeFixedPriceOrder { price: price.clone(), expiration: None, other_prices: None, buyers: None };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no1".to_string(),
+    msg: to_binary(&make_fixed_price_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  // who is not seller try to update order
+  let new_price = Asset {
+    info: mir.clone(),
+    amount: Uint128::from(50000000u128)
+  };
+
+  let update_order_msg = ExecuteMsg::UpdateOrder {
+    order_id: 1,
+    price: Some(new_price.clone()),
+    fixed_price: None,
+    expiration: None,
+  };
+
+  let info = mock_info("not_seller", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, update_order_msg);
+
+  match res {
+    Err(ContractError::Unauthorized {}) => {}
+    _ => panic!("Must return unauthorized error"),
+  }
+
+  // try to update price with unsupport asset
+  let update_order_msg = ExecuteMsg::UpdateOrder {
+    order_id: 1,
+    price: Some(Asset { info: shib.clone(), amount: Uint128::from(50000000u128) }),
+    fixed_price: None,
+    expiration: None,
+  };
+
+  let info = mock_info("seller", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, update_order_msg);
+
+  match res {
+    Err(ContractError::Unsupport {}) => {}
+    _ => panic!("Must return unsupport error"),
+  }
+
+  // try to update auction terms of fixed price order
+  let update_order_msg = ExecuteMsg::UpdateOrder {
+    order_id: 1,
+    price: None,
+    fixed_price: Some(new_price.clone()),
+    expiration: None,
+  };
+
+  let info = mock_info("seller", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, update_order_msg);
+
+  match res {
+    Err(ContractError::NotAuction {}) => {}
+    _ => panic!("Must return not auction error"),
+  }
+
+  // update price and expiration
+  let update_order_msg = ExecuteMsg::UpdateOrder {
+    order_id: 1,
+    price: Some(new_price.clone()),
+    fixed_price: None,
+    expiration: Some(Expiration::AtHeight(20_000)),
+  };
+
+  let info = mock_info("seller", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, update_order_msg).unwrap();
+
+  let order = market.orders.load(&deps.storage, U64Key::new(1)).unwrap();
+
+  assert_eq!(1, order.id);
+  assert_eq!(Some(new_price), order.price);
+  assert_eq!(Some(Expiration::AtHeight(20_000)), order.expiration);
+
+  // make auction order
+  let start_price = Asset {
+    info: uusd.clone(),
+    amount: Uint128::from(100000000u128)
+  };
+
+  let make_auction_order_msg = Cw721HookMsg::MakeAuctionOrder {
+    start_price: start_price.clone(),
+    expiration: Expiration::AtHeight(12_400),
+    fixed_price: None,
+    reserve_price: None,
+    second_price: None,
+    buyers: None
+  };
+
+  let receive_msg: Cw721ReceiveMsg = Cw721ReceiveMsg {
+    sender: seller.clone(),
+    token_id: "no2".to_string(),
+    msg: to_binary(&make_auction_order_msg).unwrap(),
+  };
+
+  let info = mock_info("spaceship", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ReceiveNft(receive_msg)).unwrap();
+
+  // try to update expiration exceed max duration
+  let update_order_msg = ExecuteMsg::UpdateOrder {
+    order_id: 2,
+    price: None,
+    fixed_price: None,
+    expiration: Some(Expiration::AtHeight(13_000)),
+  };
+
+  let info = mock_info("seller", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, update_order_msg);
+
+  match res {
+    Err(ContractError::MaxDuration {}) => {}
+    _ => panic!("Must return max duration error"),
+  }
+
+  // try to update fixed price with another asset
+  let update_order_msg = ExecuteMsg::UpdateOrder {
+    order_id: 2,
+    price: None,
+    fixed_price: Some(Asset { info: mir.clone(), amount: Uint128::from(300000000u128) }),
+    expiration: None,
+  };
+
+  let info = mock_info("seller", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, update_order_msg);
+
+  match res {
+    Err(ContractError::AssetInfoMismatch {}) => {}
+    _ => panic!("Must return asset info mismatch error"),
+  }
+
+  // update auction
+  let new_start_price = Asset {
+    info: uusd.clone(),
+    amount: Uint128::from(80000000u128)
+  };
+
+  let fixed_price = Asset {
+    info: uusd.clone(),
+    amount: Uint128::from(300000000u128)
+  };
+
+  let update_order_msg = ExecuteMsg::UpdateOrder {
+    order_id: 2,
+    price: Some(new_start_price.clone()),
+    fixed_price: Some(fixed_price.clone()),
+    expiration: Some(Expiration::AtHeight(12_440)),
+  };
+
+  let info = mock_info("seller", &[]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, update_order_msg).unwrap();
+
+  let order = market.orders.load(&deps.storage, U64Key::new(2)).unwrap();
+
+  assert_eq!(Some(fixed_price), order.price);
+  assert_eq!(
+    Some(AuctionInfo {
+      highest_bid: new_start_price,
+      bidder: None,
+      expiration: Expiration::AtHeight(12_440),
+      runner_up_bid: None,
+      second_price: false,
+      referrer: None,
+    }),
+    order.auction_info
+  );
+
+  // bid
+  let bid_msg = ExecuteMsg::Bid {
+    order_id: 2,
+    bid_price: Asset { info: uusd.clone(), amount: Uint128::from(100000000u128) },
+    referrer: None
+  };
+
+  let info = mock_info("bidder", &[Coin{ denom: "uusd".to_string(), amount: Uint128::from(100000000u128) }]);
+
+  let _res = market.execute(deps.as_mut(), mock_env(), info, bid_msg).unwrap();
+
+  // try to update auction after bid
+  let update_order_msg = ExecuteMsg::UpdateOrder {
+    order_id: 2,
+    price: None,
+    fixed_price: None,
+    expiration: Some(Expiration::AtHeight(12_400)),
+  };
+
+  let info = mock_info("seller", &[]);
+
+  let res = market.execute(deps.as_mut(), mock_env(), info, update_order_msg);
+
+  match res {
+    Err(ContractError::BidExist {}) => {}
+    _ => panic!("Must return bid exist error"),
+  }
+}